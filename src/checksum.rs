@@ -0,0 +1,78 @@
+use crate::error::{Result, TaskError};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Sidecar file holding the checksum of `path`, e.g. `tasks.json.sha256`
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".sha256");
+    PathBuf::from(name)
+}
+
+/// Hex-encoded SHA-256 digest of `data`
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Write the checksum of `data`, the exact bytes stored at `path`, next to it.
+///
+/// Uses the `sha256sum` line format (`<hex>  <file name>`), so the pair can also
+/// be checked with `sha256sum -c`. Returns the sidecar's path.
+pub async fn write_sidecar(path: &Path, data: &[u8]) -> Result<PathBuf> {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let sidecar = sidecar_path(path);
+    tokio::fs::write(&sidecar, format!("{}  {}\n", sha256_hex(data), name)).await?;
+    Ok(sidecar)
+}
+
+/// Check `path` against its sidecar checksum, if it has one.
+///
+/// Returns `Ok(false)` when there is no sidecar and `Ok(true)` when the contents
+/// match; a mismatch or unreadable sidecar is an error.
+pub async fn verify_sidecar(path: &Path) -> Result<bool> {
+    let sidecar = sidecar_path(path);
+    let recorded = match tokio::fs::read_to_string(&sidecar).await {
+        Ok(recorded) => recorded,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    let expected = recorded.split_whitespace().next().unwrap_or_default().to_ascii_lowercase();
+    if expected.len() != 64 || !expected.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(TaskError::ValidationError(format!("{} does not contain a SHA-256 checksum", sidecar.display())));
+    }
+
+    let actual = sha256_hex(&tokio::fs::read(path).await?);
+    if actual != expected {
+        return Err(TaskError::ValidationError(format!(
+            "Checksum mismatch for {}: expected {}, found {}. The file may be corrupted; \
+             use --skip-checksum to import it anyway",
+            path.display(), expected, actual
+        )));
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(sidecar_path(Path::new("out/tasks.json")), PathBuf::from("out/tasks.json.sha256"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+        tokio::fs::write(&path, b"[]").await.unwrap();
+        assert!(!verify_sidecar(&path).await.unwrap());
+
+        write_sidecar(&path, b"[]").await.unwrap();
+        assert!(verify_sidecar(&path).await.unwrap());
+
+        tokio::fs::write(&path, b"[ ]").await.unwrap();
+        assert!(verify_sidecar(&path).await.is_err());
+    }
+}
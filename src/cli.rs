@@ -1,5 +1,6 @@
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
+use std::str::FromStr;
 
 /// Enterprise Task Manager CLI
 #[derive(Parser)]
@@ -14,9 +15,105 @@ pub struct Cli {
     #[arg(short, long)]
     pub verbose: bool,
 
-    /// Data file path
+    /// Config file providing defaults for these options (default: ~/.config/task-manager/config.toml)
+    #[arg(long, value_name = "FILE", env = "TASK_MANAGER_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Data file path (default: TASK_MANAGER_FILE, then tasks.json)
     #[arg(short = 'f', long, value_name = "FILE")]
     pub file: Option<PathBuf>,
+
+    /// Data file format (defaults to the file extension: .yaml/.yml for YAML, otherwise JSON)
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub format: Option<FormatArg>,
+
+    /// Operate on a named project from the workspace file (ignored when --file is given)
+    #[arg(long, value_name = "NAME", env = "TASK_PROJECT")]
+    pub project: Option<String>,
+
+    /// Workspace file mapping project names to task files
+    #[arg(long, value_name = "FILE", default_value = crate::workspace::DEFAULT_WORKSPACE_FILE)]
+    pub workspace: PathBuf,
+
+    /// Journal every change to a write-ahead log (<FILE>.wal) for crash durability
+    #[arg(long)]
+    pub wal: bool,
+
+    /// Largest file `import` will accept, in bytes
+    #[arg(long, value_name = "BYTES", default_value_t = crate::manager::DEFAULT_MAX_IMPORT_SIZE)]
+    pub max_import_size: u64,
+
+    /// Copy the data file to a timestamped backup (<FILE>.bak-<TIME>) before each write, keeping this many (0 disables)
+    #[arg(long, value_name = "N", default_value_t = crate::manager::DEFAULT_BACKUPS)]
+    pub backups: usize,
+
+    /// Gzip the data file (implied by a .gz file name; compressed files are detected automatically)
+    #[arg(long)]
+    pub compress: bool,
+
+    /// Encrypt the data file, with the passphrase from TASK_MANAGER_KEY or a prompt (encrypted files are detected automatically)
+    #[arg(long)]
+    pub encrypt: bool,
+
+    /// Load the readable tasks from a damaged (e.g. truncated) JSON data file instead of failing
+    #[arg(long)]
+    pub recover: bool,
+
+    /// Apply changes in memory only; never write the data file or log
+    #[arg(long)]
+    pub no_save: bool,
+
+    /// Timezone for dates entered without an offset (e.g. America/New_York)
+    #[arg(long, value_name = "TZ", default_value = "UTC")]
+    pub input_tz: chrono_tz::Tz,
+
+    /// Timezone used to display dates and times (e.g. Europe/Berlin); data is always stored in UTC
+    #[arg(long, value_name = "TZ", default_value = "UTC")]
+    pub timezone: chrono_tz::Tz,
+
+    /// First day of the week, used to resolve `eow` due dates (e.g. monday, sunday)
+    #[arg(long, value_name = "DAY", default_value = "monday")]
+    pub week_start: chrono::Weekday,
+
+    /// Make `stats` warn when more than this many tasks are overdue
+    #[arg(long, value_name = "N", default_value_t = crate::manager::DEFAULT_OVERDUE_WARN_THRESHOLD)]
+    pub overdue_warn_threshold: usize,
+
+    /// Show the `stats` completion rate in green at or above this percentage
+    #[arg(long, value_name = "PCT", default_value_t = crate::manager::DEFAULT_COMPLETION_GOOD_THRESHOLD)]
+    pub completion_good_threshold: f64,
+
+    /// Show the `stats` completion rate in red below this percentage (yellow in between)
+    #[arg(long, value_name = "PCT", default_value_t = crate::manager::DEFAULT_COMPLETION_WARN_THRESHOLD)]
+    pub completion_warn_threshold: f64,
+
+    /// Ask before completing tasks at or above this priority (e.g. critical)
+    #[arg(long, value_name = "LEVEL")]
+    pub confirm_complete_priority: Option<PriorityArg>,
+
+    /// Maximum task title length in characters
+    #[arg(long, value_name = "N", default_value_t = 200)]
+    pub max_title_length: usize,
+
+    /// Maximum task description length in characters
+    #[arg(long, value_name = "N", default_value_t = 2000)]
+    pub max_description_length: usize,
+
+    /// Maximum task category length in characters
+    #[arg(long, value_name = "N", default_value_t = 50)]
+    pub max_category_length: usize,
+
+    /// Maximum note length in characters
+    #[arg(long, value_name = "N", default_value_t = 1000)]
+    pub max_note_length: usize,
+
+    /// When to use colored output (auto detects a terminal on stdout and honors NO_COLOR)
+    #[arg(long, value_enum, value_name = "WHEN", default_value = "auto")]
+    pub color: ColorArg,
+
+    /// Disable colored output, the same as --color never
+    #[arg(long)]
+    pub no_color: bool,
 }
 
 #[derive(Subcommand)]
@@ -30,59 +127,183 @@ pub enum Commands {
         #[arg(short, long)]
         description: Option<String>,
 
-        /// Task priority (low, medium, high, critical)
-        #[arg(short, long, value_enum, default_value = "medium")]
+        /// Task priority (none, low, medium, high, critical; aliases like l/m/h/c, hi, crit)
+        #[arg(short, long, default_value = "medium")]
         priority: PriorityArg,
 
         /// Task category for organization
         #[arg(short, long)]
         category: Option<String>,
 
-        /// Due date in ISO 8601 format (e.g., 2024-01-01T12:00:00Z)
+        /// Due date in ISO 8601 format (e.g., 2024-01-01T12:00:00Z), eow/eom/eoq, or a phrase like tomorrow, friday, "in 3 days"
         #[arg(long)]
         due_date: Option<String>,
+
+        /// Attach a related URL (repeatable)
+        #[arg(long = "link", value_name = "URL")]
+        links: Vec<String>,
+
+        /// Repeat the task; completing it creates the next instance
+        #[arg(long, value_enum, value_name = "INTERVAL")]
+        repeat: Option<RecurrenceArg>,
+
+        /// Person responsible for the task
+        #[arg(long, value_name = "NAME")]
+        owner: Option<String>,
+
+        /// Expected effort, in minutes or with units (e.g. 45, 2h, 1h30m)
+        #[arg(long, value_name = "DURATION")]
+        estimate: Option<String>,
+
+        /// Make this a subtask of the given task (UUID or #N)
+        #[arg(long, value_name = "ID")]
+        parent: Option<String>,
+
+        /// Label the task (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// A task that must be done before this one can be completed (repeatable)
+        #[arg(long, value_name = "ID")]
+        depends_on: Vec<String>,
+
+        /// Open the new task in $EDITOR to fill in the rest of its details
+        #[arg(long)]
+        then_edit: bool,
     },
 
     /// List tasks with comprehensive filtering and sorting options
     List {
-        /// Filter by task status (todo, in-progress, done, cancelled)
-        #[arg(short, long, value_enum)]
+        /// Filter by task status (todo, in-progress, waiting, done, cancelled; aliases like open, wip)
+        #[arg(short, long)]
         status: Option<StatusArg>,
 
-        /// Filter by task priority (low, medium, high, critical)
-        #[arg(short = 'P', long, value_enum)]
+        /// Show only todo and in-progress tasks
+        #[arg(long)]
+        active: bool,
+
+        /// Show only done and cancelled tasks
+        #[arg(long)]
+        closed: bool,
+
+        /// Show every task that is not done
+        #[arg(long)]
+        open: bool,
+
+        /// Hide tasks with this status (repeatable)
+        #[arg(long = "exclude-status", value_name = "STATUS")]
+        exclude_statuses: Vec<StatusArg>,
+
+        /// Filter by task priority (none, low, medium, high, critical; aliases like l/m/h/c, hi, crit)
+        #[arg(short = 'P', long)]
         priority: Option<PriorityArg>,
 
         /// Filter by exact category name
         #[arg(short, long)]
         category: Option<String>,
 
+        /// Hide tasks in this category (repeatable, exact name)
+        #[arg(long = "exclude-category", value_name = "NAME")]
+        exclude_categories: Vec<String>,
+
         /// Show only tasks that are overdue
         #[arg(long)]
         overdue: bool,
 
+        /// Show only tasks changed after this time (ISO 8601, local time in --input-tz)
+        #[arg(long, value_name = "DATETIME")]
+        modified_since: Option<String>,
+
+        /// Show only tasks estimated to take at most this long (e.g. 30m, 2h)
+        #[arg(long, value_name = "DURATION")]
+        max_estimate: Option<String>,
+
         /// Sort tasks by specific criteria
         #[arg(short = 'S', long, value_enum, default_value = "created-desc")]
         sort: SortArg,
 
+        /// Seed for `--sort random` to make the shuffle reproducible
+        #[arg(long)]
+        seed: Option<u64>,
+
         /// Limit the number of results displayed
         #[arg(short, long)]
         limit: Option<usize>,
 
+        /// Show only tasks after this task ID in the current order (cursor paging)
+        #[arg(long, value_name = "ID", conflicts_with = "before")]
+        after: Option<String>,
+
+        /// Show only tasks before this task ID in the current order (cursor paging)
+        #[arg(long, value_name = "ID")]
+        before: Option<String>,
+
         /// Search query that matches against title and description
         #[arg(short = 'q', long)]
         search: Option<String>,
+
+        /// Show only tasks that depend on the given task ID
+        #[arg(long, value_name = "ID")]
+        blocking: Option<String>,
+
+        /// Prefix rows with 1-based numbers usable as `#N` in later commands (not recorded under --no-save)
+        #[arg(short = 'n', long)]
+        numbered: bool,
+
+        /// Minimal rows (priority, short ID, short title) that fit in about 40 columns
+        #[arg(long, visible_alias = "compact-mobile")]
+        narrow: bool,
+
+        /// Shorten displayed categories to this many terminal columns
+        #[arg(long, value_name = "N", default_value_t = crate::CATEGORY_MAX_DISPLAY)]
+        category_width: usize,
+
+        /// Show exact dates instead of relative times like "3h ago"
+        #[arg(long)]
+        absolute: bool,
+
+        /// Show only the focused task and its subtasks
+        #[arg(long)]
+        focus: bool,
+
+        /// List archived tasks instead of active ones
+        #[arg(long, conflicts_with_all = ["search", "blocking", "focus", "after", "before", "numbered"])]
+        archived: bool,
+
+        /// Output format: the table, a JSON array, or CSV with the default export columns
+        #[arg(long, value_enum, value_name = "FORMAT", default_value = "table")]
+        format: ListFormatArg,
+
+        /// Shorthand for --format json
+        #[arg(long, conflicts_with_all = ["format", "narrow", "numbered"])]
+        json: bool,
     },
 
     /// Show detailed information about a specific task including all metadata
     Show {
-        /// Full task UUID
+        /// Task UUID or a unique prefix of it, or #N from the last numbered list
         id: String,
+
+        /// Number of most recent notes to display
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        max_notes: usize,
+
+        /// Display every note regardless of --max-notes
+        #[arg(long)]
+        all_notes: bool,
+
+        /// Show exact timestamps instead of relative times like "3h ago"
+        #[arg(long)]
+        absolute: bool,
+
+        /// Print the task as a JSON object instead of the details view
+        #[arg(long)]
+        json: bool,
     },
 
     /// Update an existing task's fields
     Update {
-        /// Full task UUID
+        /// Task UUID or a unique prefix of it, or #N from the last numbered list
         id: String,
 
         /// Update the task title
@@ -93,45 +314,242 @@ pub enum Commands {
         #[arg(short, long)]
         description: Option<String>,
 
-        /// Update priority level
-        #[arg(short, long, value_enum)]
+        /// Append text to the description on a new line instead of replacing it
+        #[arg(long, value_name = "TEXT", conflicts_with = "description")]
+        append_description: Option<String>,
+
+        /// Update priority level (none, low, medium, high, critical; aliases like l/m/h/c, hi, crit)
+        #[arg(short, long)]
         priority: Option<PriorityArg>,
 
         /// Update category (use empty string "" to clear)
         #[arg(short, long)]
         category: Option<String>,
 
-        /// Update due date in ISO 8601 format (use empty string "" to clear)
+        /// Update due date in ISO 8601 format, eow/eom/eoq or a phrase like tomorrow (use empty string "" to clear)
         #[arg(long)]
         due_date: Option<String>,
+
+        /// Replace the task's links (repeatable)
+        #[arg(long = "link", value_name = "URL")]
+        links: Vec<String>,
+
+        /// Update the owner (use empty string "" to clear)
+        #[arg(long, value_name = "NAME")]
+        owner: Option<String>,
+
+        /// Update the effort estimate, e.g. 45, 2h, 1h30m (use empty string "" to clear)
+        #[arg(long, value_name = "DURATION")]
+        estimate: Option<String>,
+
+        /// Replace the task's dependencies (repeatable; a single "" clears them)
+        #[arg(long, value_name = "ID")]
+        depends_on: Vec<String>,
+
+        /// Replace the task's tags (repeatable; a single "" clears them)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Validate the changes and report the result without saving them
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Focus on a single task: `focus <ID>` sets it, `focus show` displays it, `focus clear` removes it
+    #[command(args_conflicts_with_subcommands = true)]
+    Focus {
+        /// Task UUID, unique UUID prefix or #N from the last numbered list
+        id: Option<String>,
+
+        #[command(subcommand)]
+        action: Option<FocusAction>,
+    },
+
+    /// Punch in and out of manual time tracking on a task
+    #[command(visible_alias = "timer")]
+    Track {
+        #[command(subcommand)]
+        action: TrackAction,
+    },
+
+    /// Append a timestamped progress note to a task (shown newest first by `show`)
+    Note {
+        /// Task UUID, unique UUID prefix or #N from the last numbered list
+        id: String,
+
+        /// Text of the note
+        text: String,
+    },
+
+    /// Attach or detach related URLs on a task
+    Link {
+        #[command(subcommand)]
+        action: LinkAction,
+    },
+
+    /// Edit a task's fields in $EDITOR
+    Edit {
+        /// Task UUID, unique UUID prefix or #N from the last numbered list (optional - triggers interactive selection if omitted)
+        #[arg(conflicts_with = "last")]
+        id: Option<String>,
+
+        /// Edit the most recently created task
+        #[arg(long)]
+        last: bool,
     },
 
     /// Mark a task as completed (Done status)
     Complete {
-        /// Task UUID (optional - triggers interactive selection if omitted)
-        id: Option<String>,
+        /// Task UUIDs, unique UUID prefixes or #N rows from the last numbered list (optional - triggers interactive selection if omitted)
+        ids: Vec<String>,
+
+        /// Also act on the task IDs listed in this file, one per line ('#' starts a comment line)
+        #[arg(long, value_name = "PATH")]
+        id_file: Option<PathBuf>,
+
+        /// Also complete every open task in this category (exact name)
+        #[arg(long, value_name = "CATEGORY")]
+        all_in_category: Option<String>,
+
+        /// Then start the suggested next task (highest-priority unblocked Todo task)
+        #[arg(long)]
+        and_start_next: bool,
+
+        /// Skip the confirmation asked for by --confirm-complete-priority
+        #[arg(short, long, visible_alias = "yes")]
+        force: bool,
+
+        /// Succeed without changes for tasks that are already done (safe to re-run in scripts)
+        #[arg(long)]
+        idempotent: bool,
     },
 
     /// Start working on a task (InProgress status)
     Start {
-        /// Task UUID (optional - triggers interactive selection if omitted)
+        /// Task UUIDs, unique UUID prefixes or #N rows from the last numbered list (optional - triggers interactive selection if omitted)
+        ids: Vec<String>,
+
+        /// Also act on the task IDs listed in this file, one per line ('#' starts a comment line)
+        #[arg(long, value_name = "PATH")]
+        id_file: Option<PathBuf>,
+
+        /// Succeed without changes for tasks that are already in progress
+        #[arg(long)]
+        idempotent: bool,
+    },
+
+    /// Mark a task as blocked on someone else (Waiting status)
+    Wait {
+        /// Task UUID, unique UUID prefix or #N from the last numbered list (optional - triggers interactive selection if omitted)
         id: Option<String>,
     },
 
+    /// Set a task's status directly, with the same checks as start/wait/complete/cancel/reopen
+    SetStatus {
+        /// Task UUID, unique UUID prefix or #N from the last numbered list
+        id: String,
+
+        /// The status to move the task to
+        #[arg(value_enum)]
+        status: StatusArg,
+    },
+
     /// Cancel a task (Cancelled status)
     Cancel {
-        /// Task UUID (optional - triggers interactive selection if omitted)
+        /// Task UUIDs, unique UUID prefixes or #N rows from the last numbered list (optional - triggers interactive selection if omitted)
+        ids: Vec<String>,
+
+        /// Also act on the task IDs listed in this file, one per line ('#' starts a comment line)
+        #[arg(long, value_name = "PATH")]
+        id_file: Option<PathBuf>,
+
+        /// Succeed without changes for tasks that are already cancelled
+        #[arg(long)]
+        idempotent: bool,
+    },
+
+    /// Shift the due dates of all matching tasks (tasks without a due date are skipped)
+    Reschedule {
+        /// Amount to shift by, e.g. +1w, -2d, 12h
+        #[arg(long, allow_hyphen_values = true, value_name = "DURATION")]
+        by: String,
+
+        /// Only tasks with this status
+        #[arg(short, long)]
+        status: Option<StatusArg>,
+
+        /// Only tasks with this priority
+        #[arg(short = 'P', long)]
+        priority: Option<PriorityArg>,
+
+        /// Only tasks in this category
+        #[arg(short, long)]
+        category: Option<String>,
+
+        /// Only overdue tasks
+        #[arg(long)]
+        overdue: bool,
+
+        /// Skip the interactive confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Reopen a completed or cancelled task (Todo status)
+    Reopen {
+        /// Task UUID, unique UUID prefix or #N from the last numbered list (optional - triggers interactive selection if omitted)
+        #[arg(conflicts_with = "completed_after")]
         id: Option<String>,
+
+        /// Reopen every task completed after this time (ISO 8601)
+        #[arg(long, value_name = "DATETIME")]
+        completed_after: Option<String>,
+
+        /// Skip the interactive confirmation prompt for bulk reopen
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Show how a task's fields and status changed over time, oldest first
+    History {
+        /// Task UUID, unique UUID prefix or #N from the last numbered list
+        id: String,
+    },
+
+    /// List every instance of a recurring task's series with completion dates
+    Series {
+        /// Task UUID (or a unique prefix) of any instance in the series
+        id: String,
+    },
+
+    /// Fold a duplicate task into another one and delete the duplicate
+    ///
+    /// Descriptions are concatenated, tags, links, notes and dependencies are
+    /// combined, and the earlier creation date and nearer due date are kept.
+    Merge {
+        /// Task to keep; its ID and title survive (UUID, unique UUID prefix or #N)
+        keep: String,
+
+        /// Task to absorb and delete (UUID, unique UUID prefix or #N)
+        from: String,
     },
 
     /// Delete a task permanently from the system
     Delete {
-        /// Task UUID (optional - triggers interactive selection if omitted)
-        id: Option<String>,
+        /// Task UUIDs, unique UUID prefixes or #N rows from the last numbered list (optional - triggers interactive selection if omitted)
+        ids: Vec<String>,
+
+        /// Also act on the task IDs listed in this file, one per line ('#' starts a comment line)
+        #[arg(long, value_name = "PATH")]
+        id_file: Option<PathBuf>,
 
         /// Skip the interactive confirmation prompt
         #[arg(short, long)]
         force: bool,
+
+        /// Also delete the task's subtasks (otherwise a task with subtasks can't be deleted)
+        #[arg(long)]
+        cascade: bool,
     },
 
     /// Bulk operation to delete ALL tasks in the system
@@ -139,10 +557,83 @@ pub enum Commands {
         /// Skip the interactive confirmation prompt
         #[arg(short, long)]
         force: bool,
+
+        /// Prompt y/N/q for each task instead of once for all of them
+        #[arg(long, conflicts_with = "force")]
+        confirm_each: bool,
+    },
+
+    /// List every tag in use with the number of tasks carrying it
+    Tags {
+        /// Sort tags alphabetically instead of by usage
+        #[arg(long)]
+        alpha: bool,
+    },
+
+    /// Emit the task dependency graph in Graphviz DOT format
+    Graph {
+        /// Write the graph to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
     },
 
     /// Display aggregate statistics about all tasks
-    Stats,
+    Stats {
+        /// Compare against a snapshot file (e.g. a previous export) and show deltas
+        #[arg(long, value_name = "SNAPSHOT_FILE")]
+        trend: Option<PathBuf>,
+
+        /// Show a bar chart of completions per UTC day (requires --since)
+        #[arg(long, requires = "since")]
+        histogram: bool,
+
+        /// First day of the histogram (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+
+        /// Break the figures down per owner, busiest first
+        #[arg(long)]
+        by_owner: bool,
+
+        /// Show a bar chart of tasks per priority, colored like the list's priority dots
+        #[arg(long)]
+        by_priority: bool,
+
+        /// Print the totals as a JSON object instead of the report
+        #[arg(long, conflicts_with_all = ["trend", "histogram", "by_owner", "by_priority"])]
+        json: bool,
+
+        /// Count archived tasks in the totals as well
+        #[arg(long)]
+        include_archived: bool,
+    },
+
+    /// Print overdue and soon-due tasks as JSON for cron jobs and notifiers
+    Reminders {
+        /// Include tasks due within this window from now (e.g. 12h, 2d, 1w)
+        #[arg(long, value_name = "DURATION", default_value = "24h")]
+        within: String,
+    },
+
+    /// Health check for monitoring; exits non-zero when the check fails
+    Check {
+        /// Fail when there are overdue tasks
+        #[arg(long, required = true)]
+        overdue: bool,
+
+        /// Only fail when the overdue count exceeds this number
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        threshold: usize,
+    },
+
+    /// Move done and cancelled tasks to the archive, out of the everyday list
+    Archive,
+
+    /// Move an archived task back into the active list
+    Unarchive {
+        /// UUID or unique UUID prefix of the archived task
+        id: String,
+    },
 
     /// Clear tasks based on their completion status
     Clear {
@@ -153,24 +644,197 @@ pub enum Commands {
         /// Skip the interactive confirmation prompt
         #[arg(short, long)]
         force: bool,
+
+        /// Prompt y/N/q for each task instead of once for all of them
+        #[arg(long, conflicts_with = "force")]
+        confirm_each: bool,
+    },
+
+    /// List deleted tasks, bring one back, or delete them for good (lists by default)
+    Trash {
+        #[command(subcommand)]
+        action: Option<TrashAction>,
+    },
+
+    /// Rewrite the data file in a normalized, sorted form and report the size change
+    Compact {
+        /// Also drop cancelled tasks not touched within this window (e.g. 30d, 12w)
+        #[arg(long, value_name = "DURATION")]
+        drop_cancelled_older_than: Option<String>,
+    },
+
+    /// List the data file's backups, or replace all tasks with one of them
+    Restore {
+        /// Backup to restore: its number in the listing (1 is the newest) or its path
+        backup: Option<String>,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// List the projects in the workspace file with their task counts
+    Projects,
+
+    /// Inspect the effective configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
     },
 
     /// Bulk import tasks from a JSON file
     Import {
-        /// Path to the JSON file to import from
+        /// Path to the JSON, YAML (.yaml/.yml) or CSV (.csv) file to import from, or `-` to read JSON from stdin
         file: PathBuf,
+
+        /// Also skip tasks that duplicate an existing task by this key
+        #[arg(long, value_enum, value_name = "KEY")]
+        dedupe_on: Option<DedupeArg>,
+
+        /// What to do with tasks whose ID already exists: skip them, merge them into the stored task, overwrite it, or import them under a new ID
+        #[arg(long, value_enum, value_name = "STRATEGY", default_value = "skip")]
+        on_conflict: ImportConflictArg,
+
+        /// Report what the import would do (new, skipped, invalid) without importing
+        #[arg(long, visible_alias = "stats-only")]
+        dry_run: bool,
+
+        /// Fail if any task contains fields this version does not understand
+        #[arg(long)]
+        strict_schema: bool,
+
+        /// Import even if the file doesn't match its .sha256 sidecar
+        #[arg(long)]
+        skip_checksum: bool,
     },
 
     /// Bulk export all tasks to a JSON file
     Export {
-        /// Path where the JSON file will be created
+        /// Path of the file to create: CSV for .csv, Markdown for .md, iCalendar for .ics, YAML for .yaml/.yml, JSON otherwise (a directory with --split-by), or `-` for stdout
         file: PathBuf,
+
+        /// Write this format regardless of the file extension
+        #[arg(long = "as", value_enum, value_name = "FORMAT")]
+        as_format: Option<ExportFormatArg>,
+
+        /// Write one file per category or status into the given directory
+        #[arg(long, value_enum, value_name = "FIELD")]
+        split_by: Option<SplitByArg>,
+
+        /// Replace titles, descriptions, categories and links with placeholders
+        #[arg(long)]
+        anonymize: bool,
+
+        /// Export only tasks changed after this time (ISO 8601, local time in --input-tz)
+        #[arg(long, value_name = "DATETIME")]
+        modified_since: Option<String>,
+
+        /// Replace the target file if it already exists
+        #[arg(long, conflicts_with = "rename_on_conflict")]
+        overwrite: bool,
+
+        /// Write to a numbered variant (e.g. tasks-1.json) if the target already exists
+        #[arg(long)]
+        rename_on_conflict: bool,
+
+        /// Also write a .sha256 file with the checksum of each exported file
+        #[arg(long)]
+        with_checksum: bool,
+
+        /// Fields to write, in order, when exporting to CSV (comma-separated)
+        #[arg(long, value_enum, value_name = "FIELDS", value_delimiter = ',')]
+        columns: Vec<ColumnArg>,
+    },
+}
+
+/// Actions for the `config` command
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print the resolved settings, the data file in use and any environment overrides
+    Show,
+
+    /// Write a config file documenting every option to the default path (or --config)
+    Init {
+        /// Replace the config file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Actions for the `track` command
+#[derive(Subcommand)]
+pub enum TrackAction {
+    /// Open a time-tracking interval
+    Start {
+        /// Task UUID, unique UUID prefix or #N from the last numbered list
+        id: String,
+    },
+
+    /// Close the running time-tracking interval
+    Stop {
+        /// Task UUID, unique UUID prefix or #N from the last numbered list
+        id: String,
+    },
+}
+
+/// Actions for the `focus` command
+#[derive(Subcommand)]
+pub enum FocusAction {
+    /// Display the focused task with its notes and time tracking
+    Show,
+
+    /// Stop focusing on the task
+    Clear,
+}
+
+/// Actions for the `trash` command
+#[derive(Subcommand)]
+pub enum TrashAction {
+    /// List the tasks in the trash
+    List,
+
+    /// Move a task out of the trash and back into the task list
+    Restore {
+        /// UUID or unique UUID prefix of the trashed task
+        id: String,
+    },
+
+    /// Permanently delete every task in the trash
+    Empty {
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+/// Actions for the `link` command
+#[derive(Subcommand)]
+pub enum LinkAction {
+    /// Attach a URL to a task
+    Add {
+        /// Task UUID or a unique prefix of it
+        id: String,
+
+        /// URL to attach
+        url: String,
+    },
+
+    /// Detach a URL from a task
+    Remove {
+        /// Task UUID or a unique prefix of it
+        id: String,
+
+        /// URL to detach
+        url: String,
     },
 }
 
 /// CLI argument variant for Priority
-#[derive(Clone, ValueEnum)]
+///
+/// Parsed leniently via `FromStr` so common abbreviations are accepted.
+#[derive(Clone, Debug, PartialEq)]
 pub enum PriorityArg {
+    None,
     Low,
     Medium,
     High,
@@ -178,14 +842,134 @@ pub enum PriorityArg {
 }
 
 /// CLI argument variant for Status
-#[derive(Clone, ValueEnum)]
+///
+/// Parsed leniently via `FromStr` so common abbreviations are accepted.
+#[derive(Clone, Debug, PartialEq)]
 pub enum StatusArg {
     Todo,
     InProgress,
+    Waiting,
     Done,
     Cancelled,
 }
 
+impl FromStr for PriorityArg {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value.parse()? {
+            crate::task::Priority::None => PriorityArg::None,
+            crate::task::Priority::Low => PriorityArg::Low,
+            crate::task::Priority::Medium => PriorityArg::Medium,
+            crate::task::Priority::High => PriorityArg::High,
+            crate::task::Priority::Critical => PriorityArg::Critical,
+        })
+    }
+}
+
+impl FromStr for StatusArg {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value.parse()? {
+            crate::task::TaskStatus::Todo => StatusArg::Todo,
+            crate::task::TaskStatus::InProgress => StatusArg::InProgress,
+            crate::task::TaskStatus::Waiting => StatusArg::Waiting,
+            crate::task::TaskStatus::Done => StatusArg::Done,
+            crate::task::TaskStatus::Cancelled => StatusArg::Cancelled,
+        })
+    }
+}
+
+/// CLI argument variant for Recurrence
+#[derive(Clone, Copy, ValueEnum)]
+pub enum RecurrenceArg {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// CLI argument variant for import de-duplication
+#[derive(Clone, Copy, ValueEnum)]
+pub enum DedupeArg {
+    Title,
+}
+
+/// CLI argument variant for handling imported tasks whose ID already exists
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportConflictArg {
+    Skip,
+    Merge,
+    Overwrite,
+    #[value(alias = "rename-duplicates")]
+    Rename,
+}
+
+/// CLI argument variant for splitting an export into several files
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SplitByArg {
+    Category,
+    Status,
+}
+
+/// CLI argument variant for the data file format
+#[derive(Clone, Copy, ValueEnum)]
+pub enum FormatArg {
+    Json,
+    Yaml,
+}
+
+/// CLI argument variant for the format `list` prints
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListFormatArg {
+    Table,
+    Json,
+    Csv,
+}
+
+/// CLI argument variant for the format `export` writes
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormatArg {
+    Json,
+    Yaml,
+    Csv,
+    #[value(alias = "md")]
+    Markdown,
+    #[value(alias = "ical")]
+    Ics,
+}
+
+/// CLI argument variant for CSV export columns
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ColumnArg {
+    Id,
+    Title,
+    Description,
+    Status,
+    Priority,
+    Category,
+    Owner,
+    Tags,
+    #[value(alias = "estimate_minutes")]
+    Estimate,
+    #[value(alias = "due_date")]
+    DueDate,
+    #[value(alias = "created_at")]
+    CreatedAt,
+    #[value(alias = "updated_at")]
+    UpdatedAt,
+    #[value(alias = "completed_at")]
+    CompletedAt,
+}
+
+/// CLI argument variant for color control
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ColorArg {
+    Auto,
+    Always,
+    Never,
+}
+
 /// CLI argument variant for Sorting
 #[derive(Clone, ValueEnum)]
 pub enum SortArg {
@@ -197,11 +981,16 @@ pub enum SortArg {
     PriorityDesc,
     TitleAsc,
     TitleDesc,
+    OverdueAmount,
+    EstimateAsc,
+    EstimateDesc,
+    Random,
 }
 
 impl From<PriorityArg> for crate::task::Priority {
     fn from(arg: PriorityArg) -> Self {
         match arg {
+            PriorityArg::None => crate::task::Priority::None,
             PriorityArg::Low => crate::task::Priority::Low,
             PriorityArg::Medium => crate::task::Priority::Medium,
             PriorityArg::High => crate::task::Priority::High,
@@ -215,12 +1004,72 @@ impl From<StatusArg> for crate::task::TaskStatus {
         match arg {
             StatusArg::Todo => crate::task::TaskStatus::Todo,
             StatusArg::InProgress => crate::task::TaskStatus::InProgress,
+            StatusArg::Waiting => crate::task::TaskStatus::Waiting,
             StatusArg::Done => crate::task::TaskStatus::Done,
             StatusArg::Cancelled => crate::task::TaskStatus::Cancelled,
         }
     }
 }
 
+impl From<RecurrenceArg> for crate::task::Recurrence {
+    fn from(arg: RecurrenceArg) -> Self {
+        match arg {
+            RecurrenceArg::Daily => crate::task::Recurrence::Daily,
+            RecurrenceArg::Weekly => crate::task::Recurrence::Weekly,
+            RecurrenceArg::Monthly => crate::task::Recurrence::Monthly,
+        }
+    }
+}
+
+impl From<FormatArg> for crate::manager::StorageFormat {
+    fn from(arg: FormatArg) -> Self {
+        match arg {
+            FormatArg::Json => crate::manager::StorageFormat::Json,
+            FormatArg::Yaml => crate::manager::StorageFormat::Yaml,
+        }
+    }
+}
+
+impl From<ColumnArg> for crate::spreadsheet::Column {
+    fn from(arg: ColumnArg) -> Self {
+        use crate::spreadsheet::Column;
+        match arg {
+            ColumnArg::Id => Column::Id,
+            ColumnArg::Title => Column::Title,
+            ColumnArg::Description => Column::Description,
+            ColumnArg::Status => Column::Status,
+            ColumnArg::Priority => Column::Priority,
+            ColumnArg::Category => Column::Category,
+            ColumnArg::Owner => Column::Owner,
+            ColumnArg::Tags => Column::Tags,
+            ColumnArg::Estimate => Column::Estimate,
+            ColumnArg::DueDate => Column::DueDate,
+            ColumnArg::CreatedAt => Column::CreatedAt,
+            ColumnArg::UpdatedAt => Column::UpdatedAt,
+            ColumnArg::CompletedAt => Column::CompletedAt,
+        }
+    }
+}
+
+impl From<ImportConflictArg> for crate::manager::ImportStrategy {
+    fn from(arg: ImportConflictArg) -> Self {
+        match arg {
+            ImportConflictArg::Skip => crate::manager::ImportStrategy::Skip,
+            ImportConflictArg::Merge => crate::manager::ImportStrategy::Merge,
+            ImportConflictArg::Overwrite => crate::manager::ImportStrategy::Overwrite,
+            ImportConflictArg::Rename => crate::manager::ImportStrategy::RenameDuplicates,
+        }
+    }
+}
+
+impl From<DedupeArg> for crate::manager::ImportDedupe {
+    fn from(arg: DedupeArg) -> Self {
+        match arg {
+            DedupeArg::Title => crate::manager::ImportDedupe::Title,
+        }
+    }
+}
+
 impl From<SortArg> for crate::manager::TaskSort {
     fn from(arg: SortArg) -> Self {
         match arg {
@@ -232,6 +1081,31 @@ impl From<SortArg> for crate::manager::TaskSort {
             SortArg::PriorityDesc => crate::manager::TaskSort::PriorityDesc,
             SortArg::TitleAsc => crate::manager::TaskSort::TitleAsc,
             SortArg::TitleDesc => crate::manager::TaskSort::TitleDesc,
+            SortArg::OverdueAmount => crate::manager::TaskSort::OverdueAmount,
+            SortArg::EstimateAsc => crate::manager::TaskSort::EstimateAsc,
+            SortArg::EstimateDesc => crate::manager::TaskSort::EstimateDesc,
+            SortArg::Random => crate::manager::TaskSort::Random { seed: None },
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_and_status_aliases() {
+        assert_eq!("HI".parse::<PriorityArg>().unwrap(), PriorityArg::High);
+        assert_eq!("crit".parse::<PriorityArg>().unwrap(), PriorityArg::Critical);
+        assert_eq!("l".parse::<PriorityArg>().unwrap(), PriorityArg::Low);
+        assert_eq!("unset".parse::<PriorityArg>().unwrap(), PriorityArg::None);
+        assert_eq!("wip".parse::<StatusArg>().unwrap(), StatusArg::InProgress);
+        assert_eq!("inprogress".parse::<StatusArg>().unwrap(), StatusArg::InProgress);
+        assert_eq!("open".parse::<StatusArg>().unwrap(), StatusArg::Todo);
+        assert_eq!("cancel".parse::<StatusArg>().unwrap(), StatusArg::Cancelled);
+        assert_eq!("blocked".parse::<StatusArg>().unwrap(), StatusArg::Waiting);
+
+        let err = "urgentish".parse::<PriorityArg>().unwrap_err();
+        assert!(err.contains("crit"));
+    }
+}
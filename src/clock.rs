@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+
+/// Source of the current time for the task manager.
+///
+/// Creation, completion and overdue checks read the time through this trait so
+/// tests can pin it instead of depending on the wall clock.
+pub trait Clock: Debug + Send + Sync {
+    /// The current instant
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The real wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that stays at a fixed instant until it is moved by hand
+#[cfg(test)]
+#[derive(Debug)]
+pub struct MockClock {
+    now: std::sync::Mutex<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    /// Create a clock frozen at `now`
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: std::sync::Mutex::new(now) }
+    }
+
+    /// Move the clock to `now`
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    /// Move the clock forward by `by`
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_moves_only_when_told() {
+        let start = DateTime::parse_from_rfc3339("2024-03-01T09:00:00Z").unwrap().with_timezone(&Utc);
+        let clock = MockClock::new(start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(chrono::Duration::hours(2));
+        assert_eq!(clock.now(), start + chrono::Duration::hours(2));
+
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::error::{Result, TaskError};
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+/// Header identifying an encrypted data file; the last byte is the layout version
+const MAGIC: &[u8] = b"TMCRYPT\x01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Environment variable read for the passphrase before prompting
+pub const KEY_ENV_VAR: &str = "TASK_MANAGER_KEY";
+
+/// A passphrase for the encrypted data file, kept out of `Debug` output
+#[derive(Clone)]
+pub struct Passphrase(String);
+
+impl Passphrase {
+    pub fn new(passphrase: String) -> Self {
+        Self(passphrase)
+    }
+}
+
+impl fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Passphrase(<redacted>)")
+    }
+}
+
+/// Whether `data` starts with the encrypted file header
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Whether the file at `path` exists and starts with the encrypted file header
+pub fn file_is_encrypted(path: &Path) -> bool {
+    let mut header = [0u8; MAGIC.len()];
+    std::fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)).is_ok() && is_encrypted(&header)
+}
+
+/// Encrypt `plaintext` with a key derived from `passphrase`.
+///
+/// The output is the header, a random salt and nonce, then the ChaCha20-Poly1305
+/// ciphertext with its authentication tag. A fresh salt and nonce are used for
+/// every call.
+pub fn encrypt(plaintext: &[u8], passphrase: &Passphrase) -> Result<Vec<u8>> {
+    let salt: [u8; SALT_LEN] = rand::random();
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(&Nonce::from(nonce), plaintext)
+        .map_err(|_| TaskError::EncryptionError("encryption failed".to_string()))?;
+
+    let mut data = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// Decrypt data written by `encrypt`.
+///
+/// Fails with `TaskError::WrongPassphrase` when authentication fails, which means
+/// the passphrase is wrong or the file was modified.
+pub fn decrypt(data: &[u8], passphrase: &Passphrase) -> Result<Vec<u8>> {
+    let body = data.strip_prefix(MAGIC)
+        .ok_or_else(|| TaskError::EncryptionError("missing encrypted file header".to_string()))?;
+    if body.len() < SALT_LEN + NONCE_LEN {
+        return Err(TaskError::EncryptionError("encrypted file is truncated".to_string()));
+    }
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("split at the nonce length");
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    cipher.decrypt(&Nonce::from(nonce), ciphertext).map_err(|_| TaskError::WrongPassphrase)
+}
+
+/// Stretch the passphrase into a cipher key with Argon2id
+fn derive_key(passphrase: &Passphrase, salt: &[u8]) -> Result<Key> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.0.as_bytes(), salt, &mut key)
+        .map_err(|e| TaskError::EncryptionError(format!("key derivation failed: {}", e)))?;
+    Ok(Key::from(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_wrong_passphrase() {
+        let passphrase = Passphrase::new("correct horse".to_string());
+        let data = encrypt(b"[{\"title\": \"secret\"}]", &passphrase).unwrap();
+        assert!(is_encrypted(&data));
+        assert!(!data.windows(6).any(|window| window == b"secret"));
+        assert_eq!(decrypt(&data, &passphrase).unwrap(), b"[{\"title\": \"secret\"}]");
+
+        let wrong = Passphrase::new("battery staple".to_string());
+        assert!(matches!(decrypt(&data, &wrong), Err(TaskError::WrongPassphrase)));
+
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(decrypt(&tampered, &passphrase), Err(TaskError::WrongPassphrase)));
+        assert!(matches!(decrypt(&data[..20], &passphrase), Err(TaskError::EncryptionError(_))));
+        assert!(!is_encrypted(b"[]"));
+        assert_eq!(format!("{:?}", passphrase), "Passphrase(<redacted>)");
+    }
+}
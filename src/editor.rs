@@ -0,0 +1,154 @@
+use crate::error::{Result, TaskError};
+use crate::task::{Task, TaskUpdate, UpdateValue};
+use chrono::{DateTime, Utc, Weekday};
+use chrono_tz::Tz;
+use std::process::Command;
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set
+const FALLBACK_EDITOR: &str = "vi";
+
+/// Instructions shown at the top of the file being edited
+const HEADER_HELP: &str = "\
+# Edit the fields below, then save and close the editor.
+# Leave a value empty to clear it. The description goes after the blank line.
+# Lines starting with '#' are ignored, except in the description.";
+
+/// Render a task's editable fields as `key: value` lines followed by the description
+pub fn render(task: &Task) -> String {
+    let due = task.due_date.map(|d| d.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    format!(
+        "{}\ntitle: {}\npriority: {}\ncategory: {}\ndue: {}\nowner: {}\n\n{}\n",
+        HEADER_HELP,
+        task.title,
+        format!("{:?}", task.priority).to_lowercase(),
+        task.category.as_deref().unwrap_or_default(),
+        due.as_deref().unwrap_or_default(),
+        task.owner.as_deref().unwrap_or_default(),
+        task.description.as_deref().unwrap_or_default(),
+    )
+}
+
+/// Parse edited text back into the changes to apply.
+///
+/// Every field is set or cleared from the text, so unchanged fields simply
+/// round-trip to their current values. Due dates accept anything `--due-date` does,
+/// with relative phrases resolved against `now`.
+pub fn parse(text: &str, input_tz: Tz, week_start: Weekday, now: DateTime<Utc>) -> Result<TaskUpdate> {
+    let mut changes = TaskUpdate {
+        description: UpdateValue::Clear,
+        category: UpdateValue::Clear,
+        due_date: UpdateValue::Clear,
+        owner: UpdateValue::Clear,
+        ..TaskUpdate::default()
+    };
+
+    let mut lines = text.lines();
+    for line in lines.by_ref() {
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+
+        let (key, value) = line.split_once(':').ok_or_else(|| {
+            TaskError::ValidationError(format!("Expected 'field: value', found '{}'", line))
+        })?;
+        let value = value.trim();
+        let optional = |value: &str| match value {
+            "" => UpdateValue::Clear,
+            v => UpdateValue::Set(v.to_string()),
+        };
+
+        match key.trim().to_ascii_lowercase().as_str() {
+            "title" => changes.title = Some(value.to_string()),
+            "priority" => {
+                let priority: crate::cli::PriorityArg = value.parse().map_err(TaskError::ValidationError)?;
+                changes.priority = Some(priority.into());
+            }
+            "category" => changes.category = optional(value),
+            "owner" => changes.owner = optional(value),
+            "due" => {
+                changes.due_date = match value {
+                    "" => UpdateValue::Clear,
+                    v => UpdateValue::Set(crate::task::parse_due_date(v, input_tz, week_start, now)?),
+                }
+            }
+            other => return Err(TaskError::ValidationError(format!("Unknown field '{}'", other))),
+        }
+    }
+
+    let description = lines.collect::<Vec<_>>().join("\n");
+    let description = description.trim();
+    if !description.is_empty() {
+        changes.description = UpdateValue::Set(description.to_string());
+    }
+
+    if changes.title.as_deref().is_none_or(str::is_empty) {
+        return Err(TaskError::ValidationError("title: must not be empty".to_string()));
+    }
+    Ok(changes)
+}
+
+/// Open `text` in the user's editor and return the saved result.
+///
+/// Uses `$VISUAL`, then `$EDITOR`, then `vi`. The editor command may include
+/// arguments (e.g. `code --wait`); the file path is appended as the last one.
+pub fn edit_text(text: &str, name: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| FALLBACK_EDITOR.to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or(FALLBACK_EDITOR);
+
+    let path = std::env::temp_dir().join(format!("task-{}-{}.txt", name, uuid::Uuid::new_v4()));
+    std::fs::write(&path, text)?;
+
+    let result = Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .map_err(|e| TaskError::FileOperationError(format!("cannot run editor '{}': {}", editor, e)))
+        .and_then(|status| match status.success() {
+            true => Ok(std::fs::read_to_string(&path)?),
+            false => Err(TaskError::OperationNotAllowed(format!("editor '{}' exited with {}", editor, status))),
+        });
+
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    #[test]
+    fn test_render_parse_round_trip() {
+        let mut task = Task::new("Write docs".to_string());
+        task.priority = Priority::High;
+        task.category = Some("work".to_string());
+
+        let edited = render(&task)
+            .replace("category: work", "category:")
+            .replace("owner:", "owner: ana")
+            .replace("due:", "due: 2024-03-01T12:00:00Z")
+            + "First line\nSecond line\n";
+        let changes = parse(&edited, Tz::UTC, Weekday::Mon, Utc::now()).unwrap();
+
+        assert_eq!(changes.title.as_deref(), Some("Write docs"));
+        assert_eq!(changes.priority, Some(Priority::High));
+        assert!(matches!(changes.category, UpdateValue::Clear));
+        assert!(matches!(changes.owner, UpdateValue::Set(ref o) if o == "ana"));
+        assert!(matches!(changes.due_date, UpdateValue::Set(_)));
+        assert!(matches!(changes.description, UpdateValue::Set(ref d) if d == "First line\nSecond line"));
+
+        assert!(parse("title:\n", Tz::UTC, Weekday::Mon, Utc::now()).is_err());
+        assert!(parse("title: x\nsize: big\n", Tz::UTC, Weekday::Mon, Utc::now()).is_err());
+
+        let changes = parse("# help\ntitle: x\n# more help\n\n# Heading\nbody\n", Tz::UTC, Weekday::Mon, Utc::now()).unwrap();
+        assert!(matches!(changes.description, UpdateValue::Set(ref d) if d == "# Heading\nbody"));
+    }
+}
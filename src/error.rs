@@ -6,6 +6,12 @@ pub enum TaskError {
     #[error("Task with ID '{0}' not found")]
     TaskNotFound(String),
 
+    #[error("'{0}' is not a valid task ID (expected a UUID or the start of one, e.g. 3f2a9c1b)")]
+    InvalidId(String),
+
+    #[error("ID prefix '{prefix}' matches {} tasks: {}", .candidates.len(), .candidates.join(", "))]
+    AmbiguousId { prefix: String, candidates: Vec<String> },
+
     #[error("Validation error: {0}")]
     ValidationError(String),
 
@@ -15,14 +21,38 @@ pub enum TaskError {
     #[error("JSON serialization error: {0}")]
     JsonError(#[from] serde_json::Error),
 
+    #[error("YAML serialization error: {0}")]
+    YamlError(#[from] serde_yaml::Error),
+
+    #[error("CSV error: {0}")]
+    CsvError(#[from] csv::Error),
+
+    #[error("Config file error: {0}")]
+    ConfigError(#[from] config::ConfigError),
+
     #[error("Date parsing error: {0}")]
     DateParseError(String),
 
     #[error("File operation failed: {0}")]
     FileOperationError(String),
 
+    #[error("Unknown project: {0}")]
+    ProjectNotFound(String),
+
+    #[error("Encryption error: {0}")]
+    EncryptionError(String),
+
+    #[error("Wrong passphrase, or the encrypted data file has been modified")]
+    WrongPassphrase,
+
     #[error("Operation not allowed: {0}")]
     OperationNotAllowed(String),
+
+    #[error("{failed} of {total} tasks could not be processed")]
+    BatchFailed { failed: usize, total: usize },
+
+    #[error("{overdue} overdue tasks exceed the threshold of {threshold}")]
+    ThresholdExceeded { overdue: usize, threshold: usize },
 }
 
 /// Result type alias for convenience
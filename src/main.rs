@@ -1,18 +1,23 @@
+mod checksum;
 mod cli;
+mod clock;
+mod crypto;
+mod editor;
 mod error;
 mod manager;
+mod settings;
+mod spreadsheet;
 mod task;
+mod workspace;
 
-use clap::Parser;
 use cli::{Cli, Commands};
 use colored::*;
 use error::{Result, TaskError};
-use manager::{TaskManager, TaskManagerConfig};
+use manager::{StorageFormat, TaskManager, TaskManagerConfig};
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::process::ExitCode;
 
-/// Maximum size for import files (10MB)
-const MAX_IMPORT_SIZE: u64 = 10 * 1024 * 1024;
 
 /// Maximum length for user input strings
 const MAX_INPUT_LENGTH: usize = 1000;
@@ -20,6 +25,8 @@ const MAX_INPUT_LENGTH: usize = 1000;
 /// Display constants for formatting
 const UUID_DISPLAY_LENGTH: usize = 8;
 const TITLE_MAX_DISPLAY: usize = 40;
+const NARROW_TITLE_MAX_DISPLAY: usize = 28;
+const CATEGORY_MAX_DISPLAY: usize = 15;
 
 /// Sanitize and validate user input
 fn sanitize_input(input: &str) -> Result<String> {
@@ -30,13 +37,50 @@ fn sanitize_input(input: &str) -> Result<String> {
     Ok(trimmed.to_string())
 }
 use tracing::{error, warn, Level};
-use tracing_subscriber;
+
+/// Force colored output on or off, or fall back to terminal/environment detection.
+///
+/// `--no-color` beats any `--color` choice. Must run before anything is printed.
+/// Integration tests that snapshot colored output should pass `--color always`,
+/// since auto-detection disables color when stdout is not a TTY.
+fn init_color(choice: cli::ColorArg, no_color: bool) {
+    use std::io::IsTerminal;
+
+    let enabled = match choice {
+        _ if no_color => false,
+        cli::ColorArg::Always => true,
+        cli::ColorArg::Never => false,
+        cli::ColorArg::Auto => auto_color(
+            std::env::var("NO_COLOR").ok().as_deref(),
+            std::env::var("CLICOLOR_FORCE").ok().as_deref(),
+            std::env::var("CLICOLOR").ok().as_deref(),
+            io::stdout().is_terminal(),
+        ),
+    };
+    colored::control::set_override(enabled);
+}
+
+/// Whether `--color auto` colors output, following the NO_COLOR and CLICOLOR conventions.
+///
+/// A non-zero `CLICOLOR_FORCE` turns color on; otherwise a non-empty `NO_COLOR` turns
+/// it off; otherwise color is used on a terminal unless `CLICOLOR=0`.
+fn auto_color(no_color: Option<&str>, clicolor_force: Option<&str>, clicolor: Option<&str>, is_terminal: bool) -> bool {
+    if clicolor_force.is_some_and(|force| force != "0") {
+        return true;
+    }
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    is_terminal && clicolor != Some("0")
+}
 
 /// Initialize logging based on verbosity level
 fn init_logging(verbose: bool) {
     let level = if verbose { Level::DEBUG } else { Level::INFO };
     tracing_subscriber::fmt()
         .with_max_level(level)
+        .with_ansi(colored::control::SHOULD_COLORIZE.should_colorize())
+        .with_writer(io::stderr)
         .with_target(false)
         .with_thread_ids(false)
         .with_thread_names(false)
@@ -44,48 +88,275 @@ fn init_logging(verbose: bool) {
         .init();
 }
 
+/// Environment variable naming the data file when `--file` isn't given
+const FILE_ENV_VAR: &str = "TASK_MANAGER_FILE";
+
+/// The data file named by `TASK_MANAGER_FILE`, if it is set.
+///
+/// A set but empty variable, a directory, or a file that exists but can't be read is
+/// an error rather than a silent fallback to another data file.
+fn file_from_env() -> Result<Option<PathBuf>> {
+    let Some(value) = std::env::var_os(FILE_ENV_VAR) else {
+        return Ok(None);
+    };
+    if value.is_empty() {
+        return Err(TaskError::FileOperationError(format!(
+            "{} is set but empty; unset it or point it at a data file", FILE_ENV_VAR
+        )));
+    }
+    let path = PathBuf::from(value);
+    if path.is_dir() {
+        return Err(TaskError::FileOperationError(format!(
+            "{} points at a directory, not a data file: {}", FILE_ENV_VAR, path.display()
+        )));
+    }
+    if path.exists() {
+        if let Err(e) = std::fs::File::open(&path) {
+            return Err(TaskError::FileOperationError(format!(
+                "cannot read {} from {}: {}", path.display(), FILE_ENV_VAR, e
+            )));
+        }
+    }
+    Ok(Some(path))
+}
+
+/// Parse the command line, with values from the config file as defaults.
+///
+/// Returns the arguments, the command definition they were parsed with, whether the
+/// data file came from the config file, and the config file that was read, if any.
+fn parse_cli() -> Result<(Cli, clap::Command, bool, Option<PathBuf>)> {
+    use clap::{CommandFactory, FromArgMatches};
+
+    // `--config` itself can't come from the config file; find it before the real parse.
+    // `config init` writes the file, so it mustn't need a readable one first
+    let pre_parsed = Cli::command().ignore_errors(true).try_get_matches().ok();
+    let named = pre_parsed.as_ref().and_then(|matches| matches.get_one::<PathBuf>("config").cloned());
+    let initializing = pre_parsed.as_ref()
+        .and_then(|matches| matches.subcommand_matches("config"))
+        .is_some_and(|config| config.subcommand_name() == Some("init"));
+    if initializing {
+        let matches = Cli::command().get_matches();
+        let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        return Ok((cli, Cli::command(), false, None));
+    }
+    if let Some(path) = named.as_ref().filter(|path| !path.exists()) {
+        return Err(TaskError::ConfigError(config::ConfigError::Message(
+            format!("config file not found: {}", path.display())
+        )));
+    }
+
+    let config_file = named.or_else(settings::default_path).filter(|path| path.exists());
+    let command = match &config_file {
+        Some(path) => settings::load(path)?.apply(path, Cli::command())?,
+        None => Cli::command(),
+    };
+    let matches = command.clone().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let file_from_config = matches.value_source("file") == Some(clap::parser::ValueSource::DefaultValue);
+    Ok((cli, command, file_from_config, config_file))
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", format!("Error: {}", e).red());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parse the command line, load the data file and run the chosen command
+async fn run() -> Result<()> {
+    let (cli, command, file_from_config, config_file) = parse_cli()?;
+
+    // Color control must be settled before any output, including log lines
+    init_color(cli.color, cli.no_color);
 
     // Initialize logging
     init_logging(cli.verbose);
 
+    // Scaffolding the config file doesn't touch the data file
+    if let Commands::Config { action: cli::ConfigAction::Init { force } } = cli.command {
+        return handle_config_init(cli.config, force);
+    }
+
+    // The data file is chosen by precedence: --file, then a named project from the
+    // workspace, then TASK_MANAGER_FILE, then the config file's `file`, then tasks.json
+    let (flag_file, config_data_file) = if file_from_config { (None, cli.file) } else { (cli.file, None) };
+    let env_file = match (&flag_file, &cli.project) {
+        (None, None) => file_from_env()?,
+        _ => None,
+    };
+    let (storage_path, storage_source) = match (flag_file, cli.project.as_deref(), env_file, config_data_file) {
+        (Some(file), _, _, _) => (file, "--file".to_string()),
+        (None, Some(project), _, _) => (
+            workspace::Workspace::load(&cli.workspace)?.resolve(project)?,
+            format!("project '{}' in {}", project, cli.workspace.display()),
+        ),
+        (None, None, Some(file), _) => (file, FILE_ENV_VAR.to_string()),
+        (None, None, None, Some(file)) => (file, "config file".to_string()),
+        (None, None, None, None) => (PathBuf::from("tasks.json"), "default".to_string()),
+    };
+
+    let validation_limits = task::ValidationLimits {
+        title_max: cli.max_title_length,
+        description_max: cli.max_description_length,
+        category_max: cli.max_category_length,
+        note_max: cli.max_note_length,
+    };
+    validation_limits.check()?;
+
+    let storage_format = cli.format.map(Into::into)
+        .or_else(|| StorageFormat::from_path(&storage_path))
+        .unwrap_or_default();
+
+    // An encrypted file needs its passphrase whether or not --encrypt was given
+    let encrypted = crypto::file_is_encrypted(&storage_path);
+    let passphrase = match cli.encrypt || encrypted {
+        true => Some(read_passphrase(encrypted)?),
+        false => None,
+    };
+    if passphrase.is_some() && cli.wal && !cli.no_save {
+        return Err(TaskError::OperationNotAllowed(
+            "--wal would journal tasks unencrypted and cannot be used with an encrypted data file".to_string()
+        ));
+    }
+
+    let compress = cli.compress || manager::is_gzip_path(&storage_path);
+
     // Create task manager with configuration
     let config = TaskManagerConfig {
-        storage_path: cli.file.unwrap_or_else(|| PathBuf::from("tasks.json")),
-        auto_save: true,
+        storage_path,
+        storage_format,
+        auto_save: !cli.no_save,
+        wal_enabled: cli.wal && !cli.no_save,
+        max_import_size: cli.max_import_size,
+        input_timezone: cli.input_tz,
+        validation_limits,
+        week_start: cli.week_start,
+        confirm_complete_priority: cli.confirm_complete_priority.map(Into::into),
+        overdue_warn_threshold: cli.overdue_warn_threshold,
+        display_timezone: cli.timezone,
+        completion_good_threshold: cli.completion_good_threshold,
+        completion_warn_threshold: cli.completion_warn_threshold,
+        clock: std::sync::Arc::new(clock::SystemClock),
+        recover: cli.recover,
+        backups: cli.backups,
+        compress,
+        passphrase,
     };
 
     let mut manager = TaskManager::with_config(config);
 
-    // Load existing tasks
+    // Load existing tasks. Carrying on without an encrypted file's tasks would overwrite
+    // them on the next save, so decryption failures stop here
     if let Err(e) = manager.load().await {
+        if matches!(e, TaskError::WrongPassphrase | TaskError::EncryptionError(_)) {
+            return Err(e);
+        }
         warn!("Failed to load tasks: {}", e);
-        println!("{}", "Warning: Could not load existing tasks. Starting with empty list.".yellow());
+        println!("{}", format!("Warning: Could not load existing tasks ({}). Starting with empty list.", e).yellow());
+        if matches!(e, TaskError::JsonError(_)) {
+            println!("{}", "If the data file was cut off, rerun with --recover to load the tasks that are still readable.".yellow());
+        }
     }
 
     // Execute command
     let result = match cli.command {
-        Commands::Add { title, description, priority, category, due_date } => {
-            handle_add(&mut manager, title, description, priority, category, due_date).await
+        Commands::Add { title, description, priority, category, due_date, links, repeat, owner, estimate, parent, tags, depends_on, then_edit } => {
+            handle_add(&mut manager, title, description, priority, category, due_date, links, repeat, owner, estimate, parent, tags, depends_on, then_edit).await
+        }
+        Commands::List { status, active, closed, open, exclude_statuses, priority, category, exclude_categories, overdue, modified_since, max_estimate, sort, seed, limit, after, before, search, blocking, numbered, narrow, category_width, absolute, focus, archived, format, json } => {
+            let cursor = match (after, before) {
+                (Some(id), _) => Some(ListCursor::After(id)),
+                (None, Some(id)) => Some(ListCursor::Before(id)),
+                (None, None) => None,
+            };
+            let format = if json { cli::ListFormatArg::Json } else { format };
+            // Status filters each narrow the set, so they combine with AND
+            let mut statuses = task::StatusSet::ALL;
+            for (enabled, set) in [(active, task::StatusSet::ACTIVE), (closed, task::StatusSet::CLOSED), (open, task::StatusSet::OPEN)] {
+                if enabled {
+                    statuses = statuses.intersect(set);
+                }
+            }
+            if let Some(status) = status {
+                statuses = statuses.intersect(task::StatusSet::of(&[status.into()]));
+            }
+            for excluded in exclude_statuses {
+                statuses = statuses.without(excluded.into());
+            }
+            handle_list(&manager, statuses, priority, category, exclude_categories, overdue, modified_since, max_estimate, sort, seed, limit, cursor, search, blocking, numbered, narrow, category_width, absolute, focus, archived, format).await
+        }
+        Commands::Show { id, max_notes, all_notes, absolute, json } => {
+            let max_notes = if all_notes { None } else { Some(max_notes) };
+            handle_show(&manager, &id, max_notes, absolute, json).await
+        }
+        Commands::Update { id, title, description, append_description, priority, category, due_date, links, owner, estimate, depends_on, tags, check } => {
+            let description = match append_description {
+                Some(text) => DescriptionChange::Append(text),
+                None => DescriptionChange::Replace(description),
+            };
+            handle_update(&mut manager, &id, title, description, priority, category, due_date, links, owner, estimate, depends_on, tags, check).await
+        }
+        Commands::Track { action } => handle_track(&mut manager, action).await,
+        Commands::Note { id, text } => handle_note(&mut manager, &id, &text).await,
+        Commands::Link { action } => handle_link(&mut manager, action).await,
+        Commands::Edit { id, last } => handle_edit(&mut manager, id, last).await,
+        Commands::Complete { ids, id_file, all_in_category, and_start_next, force, idempotent } => {
+            handle_complete(&mut manager, ids, id_file, all_in_category, and_start_next, force, idempotent).await
+        }
+        Commands::Start { ids, id_file, idempotent } => handle_start(&mut manager, ids, id_file, idempotent).await,
+        Commands::Wait { id } => handle_wait(&mut manager, id).await,
+        Commands::SetStatus { id, status } => handle_set_status(&mut manager, &id, status.into()).await,
+        Commands::Cancel { ids, id_file, idempotent } => handle_cancel(&mut manager, ids, id_file, idempotent).await,
+        Commands::Reschedule { by, status, priority, category, overdue, force } => {
+            handle_reschedule(&mut manager, by, status, priority, category, overdue, force).await
+        }
+        Commands::Reopen { id, completed_after, force } => {
+            handle_reopen(&mut manager, id, completed_after, force).await
+        }
+        Commands::History { id } => handle_history(&manager, &id).await,
+        Commands::Series { id } => handle_series(&manager, &id).await,
+        Commands::Merge { keep, from } => handle_merge(&mut manager, &keep, &from).await,
+        Commands::Delete { ids, id_file, force, cascade } => handle_delete(&mut manager, ids, id_file, force, cascade).await,
+        Commands::DeleteAll { force, confirm_each } => handle_delete_all(&mut manager, force, confirm_each).await,
+        Commands::Focus { id, action } => handle_focus(&manager, id, action).await,
+        Commands::Tags { alpha } => handle_tags(&manager, alpha).await,
+        Commands::Graph { output } => handle_graph(&manager, output).await,
+        Commands::Stats { trend, histogram, since, by_owner, by_priority, json, include_archived } => {
+            handle_stats(&manager, trend, histogram, since, by_owner, by_priority, json, include_archived).await
         }
-        Commands::List { status, priority, category, overdue, sort, limit, search } => {
-            handle_list(&manager, status, priority, category, overdue, sort, limit, search).await
+        Commands::Reminders { within } => handle_reminders(&manager, within).await,
+        Commands::Check { overdue: _, threshold } => handle_check(&manager, threshold).await,
+        Commands::Clear { all, force, confirm_each } => handle_clear(&mut manager, all, force, confirm_each).await,
+        Commands::Compact { .. } if cli.no_save => Err(TaskError::OperationNotAllowed(
+            "compact rewrites the data file and cannot run with --no-save".to_string()
+        )),
+        Commands::Compact { drop_cancelled_older_than } => handle_compact(&mut manager, drop_cancelled_older_than).await,
+        Commands::Archive => handle_archive(&mut manager).await,
+        Commands::Unarchive { id } => handle_unarchive(&mut manager, &id).await,
+        Commands::Trash { action } => handle_trash(&mut manager, action).await,
+        Commands::Restore { backup, force } => handle_restore(&mut manager, backup, force).await,
+        Commands::Projects => handle_projects(&cli.workspace).await,
+        Commands::Config { action: cli::ConfigAction::Init { .. } } => unreachable!("handled before loading"),
+        Commands::Config { action: cli::ConfigAction::Show } => {
+            handle_config_show(&manager, &storage_source, &cli.workspace, &command, config_file.as_deref()).await
         }
-        Commands::Show { id } => handle_show(&manager, &id).await,
-        Commands::Update { id, title, description, priority, category, due_date } => {
-            handle_update(&mut manager, &id, title, description, priority, category, due_date).await
+        Commands::Import { file, dedupe_on, on_conflict, dry_run, strict_schema, skip_checksum } => {
+            handle_import(&mut manager, file, dedupe_on, on_conflict.into(), dry_run, strict_schema, skip_checksum).await
+        }
+        Commands::Export { file, as_format, split_by, anonymize, modified_since, overwrite, rename_on_conflict, with_checksum, columns } => {
+            let on_conflict = match (overwrite, rename_on_conflict) {
+                (true, _) => ExportConflict::Overwrite,
+                (_, true) => ExportConflict::Rename,
+                _ => ExportConflict::Refuse,
+            };
+            let columns = columns.into_iter().map(Into::into).collect();
+            handle_export(&manager, file, as_format.map(Into::into), split_by, anonymize, modified_since, on_conflict, with_checksum, columns).await
         }
-        Commands::Complete { id } => handle_complete(&mut manager, id).await,
-        Commands::Start { id } => handle_start(&mut manager, id).await,
-        Commands::Cancel { id } => handle_cancel(&mut manager, id).await,
-        Commands::Delete { id, force } => handle_delete(&mut manager, id, force).await,
-        Commands::DeleteAll { force } => handle_delete_all(&mut manager, force).await,
-        Commands::Stats => handle_stats(&manager).await,
-        Commands::Clear { all, force } => handle_clear(&mut manager, all, force).await,
-        Commands::Import { file } => handle_import(&mut manager, file).await,
-        Commands::Export { file } => handle_export(&manager, file).await,
     };
 
     // Auto-save if enabled and operation was successful
@@ -100,6 +371,7 @@ async fn main() -> Result<()> {
 }
 
 /// Create a new task with the provided details
+#[allow(clippy::too_many_arguments)]
 async fn handle_add(
     manager: &mut TaskManager,
     title: String,
@@ -107,60 +379,200 @@ async fn handle_add(
     priority: cli::PriorityArg,
     category: Option<String>,
     due_date: Option<String>,
+    links: Vec<String>,
+    repeat: Option<cli::RecurrenceArg>,
+    owner: Option<String>,
+    estimate: Option<String>,
+    parent: Option<String>,
+    tags: Vec<String>,
+    depends_on: Vec<String>,
+    then_edit: bool,
 ) -> Result<()> {
+    let parent = match parent {
+        Some(parent) => Some(resolve_task_ref(manager, &parent).await?),
+        None => None,
+    };
+    let mut dependencies = Vec::with_capacity(depends_on.len());
+    for dependency in &depends_on {
+        dependencies.push(resolve_task_ref(manager, dependency).await?);
+    }
     let due_date_parsed = if let Some(date_str) = due_date {
         if date_str.is_empty() {
             None
         } else {
-            Some(crate::task::parse_datetime(&date_str)?)
+            Some(crate::task::parse_due_date(&date_str, manager.config.input_timezone, manager.config.week_start, manager.now())?)
         }
     } else {
         None
     };
 
-    let id = manager.add_task_detailed(
-        title.clone(),
+    let id = manager.add_task_detailed(crate::task::NewTask {
+        title: title.clone(),
         description,
-        Some(priority.into()),
+        priority: Some(priority.into()),
         category,
-        due_date_parsed,
-    )?;
+        due_date: due_date_parsed,
+        links,
+        recurrence: repeat.map(Into::into),
+        owner: owner.filter(|o| !o.is_empty()),
+        estimate_minutes: estimate.as_deref().map(crate::task::parse_estimate).transpose()?,
+        parent,
+        depends_on: dependencies,
+        tags,
+    })?;
 
     println!("{}", format!("✓ Added task '{}' with ID: {}", title, id).green());
+    if then_edit {
+        edit_task(manager, &id)?;
+    }
+    Ok(())
+}
+
+/// Edit a task in $EDITOR: the given one, the newest with `--last`, or one chosen interactively
+async fn handle_edit(manager: &mut TaskManager, id: Option<String>, last: bool) -> Result<()> {
+    let task_id = match id {
+        Some(id) => resolve_task_ref(manager, &id).await?,
+        None if last => manager.get_sorted_tasks(crate::manager::TaskSort::CreatedDesc)
+            .first()
+            .map(|task| task.id.to_string())
+            .ok_or_else(|| TaskError::ValidationError("No tasks to edit".to_string()))?,
+        None => select_task_interactive(manager).await?,
+    };
+
+    edit_task(manager, &task_id)
+}
+
+/// Round-trip a task's fields through the user's editor and apply what changed
+fn edit_task(manager: &mut TaskManager, task_id: &str) -> Result<()> {
+    let original = editor::render(manager.get_task(task_id)?);
+    let edited = editor::edit_text(&original, task_id)?;
+    if edited == original {
+        println!("{}", "No changes made.".yellow());
+        return Ok(());
+    }
+
+    let changes = editor::parse(&edited, manager.config.input_timezone, manager.config.week_start, manager.now())?;
+    manager.update_task(task_id, changes)?;
+    println!("{}", format!("✓ Updated task {}", task_id).green());
     Ok(())
 }
 
+/// Position for cursor-based paging through a sorted task list
+enum ListCursor {
+    After(String),
+    Before(String),
+}
+
 /// List tasks filtered by the provided criteria and display them in a summary table
+#[allow(clippy::too_many_arguments)]
 async fn handle_list(
     manager: &TaskManager,
-    status: Option<cli::StatusArg>,
+    statuses: task::StatusSet,
     priority: Option<cli::PriorityArg>,
     category: Option<String>,
+    exclude_categories: Vec<String>,
     overdue: bool,
+    modified_since: Option<String>,
+    max_estimate: Option<String>,
     sort: cli::SortArg,
+    seed: Option<u64>,
     limit: Option<usize>,
+    cursor: Option<ListCursor>,
     search: Option<String>,
+    blocking: Option<String>,
+    numbered: bool,
+    narrow: bool,
+    category_width: usize,
+    absolute: bool,
+    focus: bool,
+    archived: bool,
+    format: cli::ListFormatArg,
 ) -> Result<()> {
-    let query_str = search.as_deref();
-    let category_str = category.as_deref();
-
-    let mut tasks: Vec<_> = if let Some(query) = query_str {
+    // Row numbers and the narrow layout only exist in the table, so asking for them
+    // with another format is a mistake rather than something to ignore
+    if format != cli::ListFormatArg::Table && (numbered || narrow) {
+        return Err(TaskError::ValidationError(
+            "--numbered and --narrow only apply to the table format".to_string()
+        ));
+    }
+    let sort = match sort.into() {
+        crate::manager::TaskSort::Random { .. } => crate::manager::TaskSort::Random { seed },
+        sort => sort,
+    };
+    let mut tasks: Vec<_> = if let Some(blocker) = blocking.as_deref() {
+        manager.get_task(blocker)?;
+        manager.tasks_blocked_by(blocker)
+    } else if let Some(query) = search.as_deref() {
         manager.search_tasks(query).collect()
-    } else if overdue {
-        manager.get_overdue_tasks().collect()
-    } else if let Some(status) = status {
-        manager.get_tasks_by_status(status.into()).collect()
-    } else if let Some(priority) = priority {
-        manager.get_tasks_by_priority(priority.into()).collect()
-    } else if let Some(category) = category_str {
-        manager.get_tasks_by_category(category).collect()
+    } else if archived {
+        manager.get_sorted_archived(sort)
     } else {
-        manager.get_sorted_tasks(sort.into())
+        manager.get_sorted_tasks(sort)
     };
 
-    if tasks.is_empty() {
-        println!("{}", "No tasks found.".yellow());
-        return Ok(());
+    // Subtasks are shown under their parent only for the full, unfiltered list
+    let nested = blocking.is_none() && search.is_none() && statuses == task::StatusSet::ALL && priority.is_none()
+        && category.is_none() && exclude_categories.is_empty() && !overdue && modified_since.is_none()
+        && max_estimate.is_none() && cursor.is_none() && limit.is_none();
+
+    // Remaining filters narrow the selection and combine with AND
+    if overdue {
+        let now = manager.now();
+        tasks.retain(|task| task.is_overdue_at(now));
+    }
+    if let Some(since) = modified_since {
+        let since = crate::task::parse_datetime_in(&since, manager.config.input_timezone)?;
+        let changed: std::collections::HashSet<uuid::Uuid> = manager.get_tasks_modified_since(since).map(|t| t.id).collect();
+        tasks.retain(|task| changed.contains(&task.id));
+    }
+    if let Some(max_estimate) = max_estimate {
+        let max_estimate = crate::task::parse_estimate(&max_estimate)?;
+        tasks.retain(|task| task.estimate_minutes.is_some_and(|minutes| minutes <= max_estimate));
+    }
+    if focus {
+        let focused = focused_task(manager).await?.ok_or_else(no_focus_error)?;
+        let subtree: std::collections::HashSet<uuid::Uuid> = manager.get_subtree(&focused)?.into_iter().collect();
+        tasks.retain(|task| subtree.contains(&task.id));
+    }
+    if statuses != task::StatusSet::ALL {
+        tasks.retain(|task| statuses.contains(task.status));
+    }
+    if let Some(priority) = priority {
+        let priority = priority.into();
+        tasks.retain(|task| task.priority == priority);
+    }
+    if let Some(category) = category.as_deref() {
+        tasks.retain(|task| task.in_category(category));
+    }
+    if !exclude_categories.is_empty() {
+        tasks.retain(|task| !exclude_categories.iter().any(|excluded| task.in_category(excluded)));
+    }
+
+    let mut tasks: Vec<(&crate::task::Task, usize)> = if nested {
+        nest_subtasks(tasks)
+    } else {
+        tasks.into_iter().map(|task| (task, 0)).collect()
+    };
+
+    // Slice around the cursor task so pages stay stable as other tasks come and go
+    if let Some(cursor) = cursor {
+        let (ListCursor::After(id) | ListCursor::Before(id)) = &cursor;
+        let id = resolve_task_ref(manager, id).await?;
+        let position = tasks.iter().position(|(task, _)| task.id.to_string() == id).ok_or_else(|| {
+            TaskError::ValidationError(format!("Cursor task {} is not in the filtered list", id))
+        })?;
+        match cursor {
+            ListCursor::After(_) => {
+                tasks.drain(..=position);
+            }
+            ListCursor::Before(_) => {
+                tasks.truncate(position);
+                // Keep the page adjacent to the cursor rather than the start of the list
+                if let Some(limit) = limit {
+                    tasks.drain(..tasks.len().saturating_sub(limit));
+                }
+            }
+        }
     }
 
     // Apply limit if specified
@@ -168,27 +580,119 @@ async fn handle_list(
         tasks.truncate(limit);
     }
 
+    // Machine-readable formats print every match flat, without headers or colors
+    match format {
+        cli::ListFormatArg::Json => {
+            let tasks: Vec<&crate::task::Task> = tasks.iter().map(|(task, _)| *task).collect();
+            println!("{}", serde_json::to_string_pretty(&tasks)?);
+            return Ok(());
+        }
+        cli::ListFormatArg::Csv => {
+            print!("{}", spreadsheet::write_csv(tasks.iter().map(|(task, _)| *task), spreadsheet::DEFAULT_COLUMNS)?);
+            return Ok(());
+        }
+        cli::ListFormatArg::Table => {}
+    }
+
+    if tasks.is_empty() {
+        println!("{}", "No tasks found.".yellow());
+        return Ok(());
+    }
+
     println!("{}", format!("📋 Tasks ({} found):", tasks.len()).cyan().bold());
-    println!("{}", "─".repeat(80).dimmed());
+    println!("{}", "─".repeat(if narrow { 40 } else { 80 }).dimmed());
 
-    for task in tasks {
-        print_task_summary(task);
+    if numbered && manager.config.auto_save {
+        // Remember this ordering so later commands can refer to rows as #N;
+        // with --no-save nothing is written, so earlier row numbers stay in effect
+        let ids: Vec<String> = tasks.iter().map(|(t, _)| t.id.to_string()).collect();
+        tokio::fs::write(manager.config.last_list_path(), serde_json::to_string(&ids)?).await?;
+    }
+
+    for (i, (task, depth)) in tasks.iter().enumerate() {
+        if numbered {
+            print!("{}: ", format!("{:2}", i + 1).bold());
+        }
+        if *depth > 0 {
+            print!("{}{} ", "  ".repeat(*depth - 1), "↳".dimmed());
+        }
+        if narrow {
+            print_task_narrow(task);
+        } else {
+            print_task_summary(task, category_width, manager.config.display_timezone, TimeDisplay::new(manager, absolute), manager.now());
+        }
     }
 
     Ok(())
 }
 
-/// Display detailed information about a single task, including all metadata and status
-async fn handle_show(manager: &TaskManager, id: &str) -> Result<()> {
+/// Reorder tasks so each subtask follows its parent, paired with its nesting depth.
+///
+/// Sibling order is preserved from the input. Tasks whose parent isn't in the list
+/// (filtered out, deleted, or part of a parent cycle) are shown at the top level.
+fn nest_subtasks(tasks: Vec<&crate::task::Task>) -> Vec<(&crate::task::Task, usize)> {
+    use std::collections::{HashMap, HashSet};
+
+    let present: HashSet<uuid::Uuid> = tasks.iter().map(|t| t.id).collect();
+    let mut children: HashMap<uuid::Uuid, Vec<&crate::task::Task>> = HashMap::new();
+    let mut roots = Vec::new();
+    for task in &tasks {
+        match task.parent_id.filter(|parent| present.contains(parent)) {
+            Some(parent) => children.entry(parent).or_default().push(*task),
+            None => roots.push(*task),
+        }
+    }
+
+    fn visit<'a>(
+        task: &'a crate::task::Task,
+        depth: usize,
+        children: &HashMap<uuid::Uuid, Vec<&'a crate::task::Task>>,
+        placed: &mut HashSet<uuid::Uuid>,
+        rows: &mut Vec<(&'a crate::task::Task, usize)>,
+    ) {
+        if !placed.insert(task.id) {
+            return;
+        }
+        rows.push((task, depth));
+        for child in children.get(&task.id).into_iter().flatten() {
+            visit(child, depth + 1, children, placed, rows);
+        }
+    }
+
+    let mut placed = HashSet::new();
+    let mut rows = Vec::with_capacity(tasks.len());
+    for root in roots {
+        visit(root, 0, &children, &mut placed, &mut rows);
+    }
+    for task in tasks {
+        visit(task, 0, &children, &mut placed, &mut rows);
+    }
+    rows
+}
+
+/// Display detailed information about a single task, including all metadata and status.
+///
+/// Notes are listed newest first; `max_notes` caps how many are shown (`None` shows all).
+/// Times are relative ("3h ago") unless `absolute` is set.
+async fn handle_show(manager: &TaskManager, id: &str, max_notes: Option<usize>, absolute: bool, json: bool) -> Result<()> {
+    let id = &resolve_task_ref(manager, id).await?;
     let task = manager.get_task(id)?;
 
+    if json {
+        println!("{}", serde_json::to_string_pretty(task)?);
+        return Ok(());
+    }
+
     println!("{}", format!("📄 Task Details: {}", task.id).cyan().bold());
     println!("{}", "─".repeat(40).dimmed());
 
     println!("{} {}", "Title:".bold(), task.title);
     println!("{} {}", "Status:".bold(), task.status_display());
     println!("{} {}", "Priority:".bold(), task.priority_display());
-    println!("{} {}", "Created:".bold(), task.created_at.format("%Y-%m-%d %H:%M:%S UTC"));
+    let tz = manager.config.display_timezone;
+    let times = TimeDisplay::new(manager, absolute);
+    println!("{} {}", "Created:".bold(), times.format(task.created_at, tz));
+    println!("{} {}", "Updated:".bold(), times.format(task.updated_at, tz));
 
     if let Some(ref desc) = task.description {
         println!("{} {}", "Description:".bold(), desc);
@@ -198,9 +702,58 @@ async fn handle_show(manager: &TaskManager, id: &str) -> Result<()> {
         println!("{} {}", "Category:".bold(), category);
     }
 
+    if let Some(ref owner) = task.owner {
+        println!("{} {}", "Owner:".bold(), owner);
+    }
+
+    if let Some(minutes) = task.estimate_minutes {
+        println!("{} {}", "Estimate:".bold(), format_duration(chrono::Duration::minutes(minutes.into())));
+    }
+
+    // Related tasks may have been archived since; they are still shown, just marked
+    let archived_label = |related: &crate::task::Task| {
+        if related.archived_at.is_some() { format!(" {}", "(archived)".dimmed()) } else { String::new() }
+    };
+
+    if let Some(parent_id) = task.parent_id {
+        match manager.stored_task(&parent_id.to_string()) {
+            Some(parent) => println!("{} {} ({}){}", "Parent:".bold(), parent.title, parent_id, archived_label(parent)),
+            None => println!("{} {} {}", "Parent:".bold(), parent_id, "(missing)".dimmed()),
+        }
+    }
+
+    if !task.depends_on.is_empty() {
+        println!("{}", "Depends on:".bold());
+        for dep in &task.depends_on {
+            match manager.stored_task(&dep.to_string()) {
+                Some(dep_task) => println!(
+                    "  {} {} ({}){}", dep_task.status_display(), dep_task.title, dep, archived_label(dep_task)
+                ),
+                None => println!("  {} {}", dep, "(missing)".dimmed()),
+            }
+        }
+    }
+
+    let subtasks = manager.get_children(id)?;
+    if !subtasks.is_empty() {
+        println!("{}", format!("Subtasks ({}):", subtasks.len()).bold());
+        for subtask in subtasks {
+            println!("  {} {} ({})", subtask.status_display(), subtask.title, subtask.id);
+        }
+    }
+
+    if let Some(recurrence) = task.recurrence {
+        println!("{} {:?}", "Repeats:".bold(), recurrence);
+    }
+
     if let Some(due_date) = task.due_date {
-        let due_str = due_date.format("%Y-%m-%d %H:%M:%S UTC").to_string();
-        if task.is_overdue() {
+        let due_str = match times {
+            TimeDisplay::Relative(now) => {
+                format!("{} ({})", format_timestamp(due_date, tz), crate::task::humanize_relative(due_date, now))
+            }
+            TimeDisplay::Absolute => format_timestamp(due_date, tz),
+        };
+        if task.is_overdue_at(manager.now()) {
             println!("{} {} {}", "Due Date:".bold(), due_str.red(), "(OVERDUE)".red().bold());
         } else {
             println!("{} {}", "Due Date:".bold(), due_str);
@@ -208,28 +761,94 @@ async fn handle_show(manager: &TaskManager, id: &str) -> Result<()> {
     }
 
     if let Some(completed_at) = task.completed_at {
-        println!("{} {}", "Completed:".bold(), completed_at.format("%Y-%m-%d %H:%M:%S UTC"));
+        println!("{} {}", "Completed:".bold(), times.format(completed_at, tz));
+    }
+
+    if !task.time_entries.is_empty() {
+        println!("{} {}", "Time tracked:".bold(), format_duration(task.total_tracked(manager.now())));
+        for entry in &task.time_entries {
+            let end = match entry.end {
+                Some(end) => end.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string(),
+                None => "running".yellow().to_string(),
+            };
+            println!("  {} → {} ({})", entry.start.with_timezone(&tz).format("%Y-%m-%d %H:%M"), end, format_duration(entry.duration(manager.now())));
+        }
+    }
+
+    if !task.notes.is_empty() {
+        println!("{}", "Notes:".bold());
+        let shown = max_notes.unwrap_or(task.notes.len()).min(task.notes.len());
+        for note in task.notes.iter().rev().take(shown) {
+            println!("  {} {}", note.timestamp.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string().dimmed(), note.text);
+        }
+        let hidden = task.notes.len() - shown;
+        if hidden > 0 {
+            println!("  {}", format!("... and {} older notes (use --all-notes)", hidden).dimmed());
+        }
+    }
+
+    if !task.tags.is_empty() {
+        println!("{} {}", "Tags:".bold(), task.tags.join(", "));
+    }
+
+    if !task.links.is_empty() {
+        println!("{}", "Links:".bold());
+        for link in &task.links {
+            println!("  {}", format_link(link));
+        }
+    }
+
+    let blocked = manager.tasks_blocked_by(id);
+    if !blocked.is_empty() {
+        println!("{}", "Blocks:".bold());
+        for blocked_task in blocked {
+            print!("  ");
+            print_task_summary(blocked_task, CATEGORY_MAX_DISPLAY, manager.config.display_timezone, TimeDisplay::new(manager, false), manager.now());
+        }
     }
 
     Ok(())
 }
 
 /// Update an existing task's details selectively
+#[allow(clippy::too_many_arguments)]
 async fn handle_update(
     manager: &mut TaskManager,
     id: &str,
     title: Option<String>,
-    description: Option<String>,
+    description: DescriptionChange,
     priority: Option<cli::PriorityArg>,
     category: Option<String>,
     due_date: Option<String>,
+    links: Vec<String>,
+    owner: Option<String>,
+    estimate: Option<String>,
+    depends_on: Vec<String>,
+    tags: Vec<String>,
+    check: bool,
 ) -> Result<()> {
-    use crate::task::UpdateValue;
+    use crate::task::{TaskUpdate, UpdateValue};
+
+    let id = &resolve_task_ref(manager, id).await?;
+
+    let depends_on = match depends_on.as_slice() {
+        [] => None,
+        [only] if only.is_empty() => Some(Vec::new()),
+        refs => {
+            let mut ids = Vec::with_capacity(refs.len());
+            for dependency in refs {
+                let dependency = resolve_task_ref(manager, dependency).await?;
+                ids.push(manager.get_task(&dependency)?.id);
+            }
+            Some(ids)
+        }
+    };
 
-    let description = match description {
-        Some(d) if d.is_empty() => UpdateValue::Clear,
-        Some(d) => UpdateValue::Set(d),
-        None => UpdateValue::Keep,
+    let (description, append) = match description {
+        DescriptionChange::Replace(Some(d)) if d.is_empty() => (UpdateValue::Clear, None),
+        DescriptionChange::Replace(Some(d)) => (UpdateValue::Set(d), None),
+        DescriptionChange::Replace(None) => (UpdateValue::Keep, None),
+        DescriptionChange::Append(text) => (UpdateValue::Keep, Some(text)),
     };
     let category = match category {
         Some(c) if c.is_empty() => UpdateValue::Clear,
@@ -240,191 +859,1888 @@ async fn handle_update(
 
     let due_date = match due_date {
         Some(d) if d.is_empty() => UpdateValue::Clear,
-        Some(d) => UpdateValue::Set(crate::task::parse_datetime(&d)?),
+        Some(d) => UpdateValue::Set(crate::task::parse_due_date(&d, manager.config.input_timezone, manager.config.week_start, manager.now())?),
         None => UpdateValue::Keep,
     };
 
-    manager.update_task(id, title, description, priority, category, due_date)?;
-    println!("{}", format!("✓ Updated task {}", id).green());
-    Ok(())
-}
+    let links = if links.is_empty() { None } else { Some(links) };
 
-/// Mark a task as completed, recording completion time
-async fn handle_complete(manager: &mut TaskManager, id: Option<String>) -> Result<()> {
-    let task_id = match id {
-        Some(id) => id,
-        None => select_task_interactive(manager).await?,
+    let owner = match owner {
+        Some(o) if o.is_empty() => UpdateValue::Clear,
+        Some(o) => UpdateValue::Set(o),
+        None => UpdateValue::Keep,
+    };
+
+    let estimate_minutes = match estimate {
+        Some(e) if e.is_empty() => UpdateValue::Clear,
+        Some(e) => UpdateValue::Set(crate::task::parse_estimate(&e)?),
+        None => UpdateValue::Keep,
+    };
+
+    let tags = match tags.as_slice() {
+        [] => None,
+        [only] if only.is_empty() => Some(Vec::new()),
+        _ => Some(crate::task::normalize_tags(tags)),
     };
 
-    manager.complete_task(&task_id)?;
-    println!("{}", format!("✓ Completed task {}", task_id).green());
+    let changes = TaskUpdate { title, description, priority, category, due_date, links, owner, estimate_minutes, depends_on, tags };
+
+    // Validate the combined result up front so an append never lands on a half-applied update
+    let mut preview = changes.clone();
+    if let Some(text) = &append {
+        preview.description = UpdateValue::Set(manager.get_task(id)?.appended_description(text));
+    }
+    manager.preview_update(id, preview)?;
+
+    if check {
+        println!("{}", format!("✓ Update to task {} passes validation (not saved)", id).green());
+        return Ok(());
+    }
+
+    manager.update_task(id, changes)?;
+    if let Some(text) = append {
+        manager.append_description(id, &text)?;
+    }
+    println!("{}", format!("✓ Updated task {}", id).green());
     Ok(())
 }
 
-/// Mark a task as being worked on (In Progress)
-async fn handle_start(manager: &mut TaskManager, id: Option<String>) -> Result<()> {
-    let task_id = match id {
-        Some(id) => id,
-        None => select_task_interactive(manager).await?,
-    };
+/// How `update` should change a task's description
+enum DescriptionChange {
+    /// Replace it; `Some("")` clears it and `None` leaves it alone
+    Replace(Option<String>),
+    /// Add text on a new line after the existing description
+    Append(String),
+}
 
-    manager.start_task(&task_id)?;
-    println!("{}", format!("▶ Started working on task {}", task_id).green());
+/// Open or close a manual time-tracking interval on a task
+async fn handle_track(manager: &mut TaskManager, action: cli::TrackAction) -> Result<()> {
+    match action {
+        cli::TrackAction::Start { id } => {
+            let id = resolve_task_ref(manager, &id).await?;
+            manager.start_time_entry(&id)?;
+            println!("{}", format!("⏱ Started tracking time on task {}", id).green());
+        }
+        cli::TrackAction::Stop { id } => {
+            let id = resolve_task_ref(manager, &id).await?;
+            let elapsed = manager.stop_time_entry(&id)?;
+            let total = manager.get_task(&id)?.total_tracked(manager.now());
+            println!("{}", format!(
+                "⏹ Stopped tracking task {} after {} (total {})", id, format_duration(elapsed), format_duration(total)
+            ).green());
+        }
+    }
     Ok(())
 }
 
-/// Mark a task as cancelled
-async fn handle_cancel(manager: &mut TaskManager, id: Option<String>) -> Result<()> {
-    let task_id = match id {
-        Some(id) => id,
-        None => select_task_interactive(manager).await?,
-    };
+/// Format a duration compactly, e.g. "2h 05m" or "45m"
+fn format_duration(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
 
-    manager.cancel_task(&task_id)?;
-    println!("{}", format!("❌ Cancelled task {}", task_id).yellow());
+/// Attach or detach a URL on a task
+async fn handle_link(manager: &mut TaskManager, action: cli::LinkAction) -> Result<()> {
+    match action {
+        cli::LinkAction::Add { id, url } => {
+            let id = resolve_task_ref(manager, &id).await?;
+            let url = sanitize_input(&url)?;
+            manager.add_link(&id, url.clone())?;
+            println!("{}", format!("🔗 Linked {} to task {}", url, id).green());
+        }
+        cli::LinkAction::Remove { id, url } => {
+            let id = resolve_task_ref(manager, &id).await?;
+            manager.remove_link(&id, &url)?;
+            println!("{}", format!("🔗 Unlinked {} from task {}", url, id).yellow());
+        }
+    }
     Ok(())
 }
 
-/// Delete a task permanently, with a confirmation prompt unless forced
-async fn handle_delete(manager: &mut TaskManager, id: Option<String>, force: bool) -> Result<()> {
-    let task_id = match id {
-        Some(id) => id,
-        None => select_task_interactive(manager).await?,
-    };
+/// Append a progress note to a task
+async fn handle_note(manager: &mut TaskManager, id: &str, text: &str) -> Result<()> {
+    let id = resolve_task_ref(manager, id).await?;
+    // Length is left to the configurable note limit rather than the generic input cap
+    manager.add_note(&id, text.trim().to_string())?;
+    println!("{}", format!("📝 Added note to task {}", id).green());
+    Ok(())
+}
 
-    if !force {
-        print!("Are you sure you want to delete task {}? (y/N): ", task_id);
-        io::stdout().flush()?;
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let input = sanitize_input(&input)?;
-        if !input.eq_ignore_ascii_case("y") && !input.eq_ignore_ascii_case("yes") {
-            println!("{}", "Operation cancelled.".yellow());
+/// Mark a task as completed, recording completion time
+async fn handle_complete(
+    manager: &mut TaskManager,
+    ids: Vec<String>,
+    id_file: Option<PathBuf>,
+    all_in_category: Option<String>,
+    and_start_next: bool,
+    force: bool,
+    idempotent: bool,
+) -> Result<()> {
+    let mut ids = with_id_file(ids, id_file).await?;
+    if let Some(category) = all_in_category {
+        let mut open: Vec<&crate::task::Task> = manager.get_all_tasks()
+            .filter(|task| task.in_category(&category) && !task.status.is_closed())
+            .collect();
+        if open.is_empty() && ids.is_empty() {
+            println!("{}", format!("No open tasks in category '{}'.", category).yellow());
             return Ok(());
         }
+        open.sort_by_key(|task| task.created_at);
+        ids.extend(open.into_iter().map(|task| task.id.to_string()));
     }
+    for_each_task(manager, ids, "completed", |manager, task_id| {
+        if idempotent && already_in_status(manager, task_id, crate::task::TaskStatus::Done)? {
+            return Ok(());
+        }
+        let task = manager.get_task(task_id)?;
+        let needs_confirmation = manager.config.confirm_complete_priority.is_some_and(|p| task.priority >= p);
+        if needs_confirmation && !force {
+            let prompt = format!("Complete {} task '{}'? (y/N): ", task.priority_display(), task.title);
+            if !confirm(&prompt)? {
+                println!("{}", format!("Skipped task {}", task_id).yellow());
+                return Ok(());
+            }
+        }
 
-    manager.delete_task(&task_id)?;
-    println!("{}", format!("🗑 Deleted task {}", task_id).red());
+        let next = manager.complete_task(task_id)?;
+        println!("{}", format!("✓ Completed task {}", task_id).green());
+        if let Some(next_id) = next {
+            println!("{}", format!("🔁 Next occurrence created: {}", next_id).cyan());
+        }
+        Ok(())
+    })
+    .await?;
+
+    if and_start_next {
+        match manager.suggest_next().map(|task| (task.id.to_string(), task.title.clone())) {
+            Some((next_id, title)) => {
+                manager.start_task(&next_id)?;
+                println!("{}", format!("▶ Now working on '{}' ({})", title, next_id).green());
+            }
+            None => println!("{}", "Nothing left to start. Nice work!".cyan()),
+        }
+    }
     Ok(())
 }
 
-/// Bulk delete operation for all tasks with a double-confirmation prompt
-async fn handle_delete_all(manager: &mut TaskManager, force: bool) -> Result<()> {
-    let count = manager.get_all_tasks().count();
+/// Shift the due dates of all tasks matching the filters, after confirmation
+async fn handle_reschedule(
+    manager: &mut TaskManager,
+    by: String,
+    status: Option<cli::StatusArg>,
+    priority: Option<cli::PriorityArg>,
+    category: Option<String>,
+    overdue: bool,
+    force: bool,
+) -> Result<()> {
+    let delta = crate::task::parse_duration_offset(&by)?;
+    let status: Option<crate::task::TaskStatus> = status.map(Into::into);
+    let priority: Option<crate::task::Priority> = priority.map(Into::into);
+
+    let now = manager.now();
+    let matches = |task: &crate::task::Task| {
+        status.is_none_or(|s| task.status == s)
+            && priority.is_none_or(|p| task.priority == p)
+            && category.as_ref().is_none_or(|c| task.category.as_ref() == Some(c))
+            && (!overdue || task.is_overdue_at(now))
+    };
 
+    let count = manager.get_all_tasks().filter(|t| t.due_date.is_some() && matches(t)).count();
     if count == 0 {
-        println!("{}", "No tasks to delete.".yellow());
+        println!("{}", "No matching tasks with a due date.".yellow());
         return Ok(());
     }
 
-    if !force {
-        print!("Are you sure you want to delete ALL {} tasks? This action cannot be undone. (y/N): ", count);
-        io::stdout().flush()?;
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let input = sanitize_input(&input)?;
-        if !input.eq_ignore_ascii_case("y") && !input.eq_ignore_ascii_case("yes") {
+    if !force && !confirm(&format!("Shift the due date of {} tasks by {}? (y/N): ", count, by))? {
+        println!("{}", "Operation cancelled.".yellow());
+        return Ok(());
+    }
+
+    let shifted = manager.shift_due_dates(matches, delta)?;
+    println!("{}", format!("📅 Rescheduled {} tasks by {}", shifted, by).green());
+    Ok(())
+}
+
+/// Reopen a single task, or every task completed after a cutoff (with confirmation)
+async fn handle_reopen(
+    manager: &mut TaskManager,
+    id: Option<String>,
+    completed_after: Option<String>,
+    force: bool,
+) -> Result<()> {
+    if let Some(cutoff) = completed_after {
+        let cutoff = crate::task::parse_datetime_in(&cutoff, manager.config.input_timezone)?;
+        let count = manager.get_tasks_completed_after(cutoff).count();
+        if count == 0 {
+            println!("{}", "No tasks were completed after that time.".yellow());
+            return Ok(());
+        }
+
+        let prompt = format!("Reopen {} tasks completed after {}? (y/N): ", count, format_timestamp(cutoff, manager.config.display_timezone));
+        if !force && !confirm(&prompt)? {
             println!("{}", "Operation cancelled.".yellow());
             return Ok(());
         }
+
+        let reopened = manager.reopen_completed_after(cutoff)?;
+        println!("{}", format!("↩ Reopened {} tasks", reopened).green());
+        return Ok(());
     }
 
-    let removed = manager.clear_all();
-    println!("{}", format!("🗑 Deleted all {} tasks", removed).red().bold());
+    let task_id = match id {
+        Some(id) => resolve_task_ref(manager, &id).await?,
+        None => select_task_interactive(manager).await?,
+    };
+
+    manager.reopen_task(&task_id)?;
+    println!("{}", format!("↩ Reopened task {}", task_id).green());
     Ok(())
 }
 
-/// Display aggregate task statistics including completion rate and status counts
-async fn handle_stats(manager: &TaskManager) -> Result<()> {
-    let stats = manager.get_stats();
+/// Print the recorded field changes of a task, oldest first
+async fn handle_history(manager: &TaskManager, id: &str) -> Result<()> {
+    let id = resolve_task_ref(manager, id).await?;
+    let task = manager.get_task(&id)?;
 
-    println!("{}", "📊 Task Statistics".cyan().bold());
-    println!("{}", "─".repeat(30).dimmed());
+    println!("{}", format!("📜 History of '{}' ({} changes):", task.title, task.history.len()).cyan().bold());
+    println!("{}", "─".repeat(80).dimmed());
+    if task.history.is_empty() {
+        println!("{}", "No changes recorded yet.".dimmed());
+    }
 
-    println!("{} {}", "Total tasks:".bold(), stats.total);
-    println!("{} {}", "Completed:".bold(), stats.completed);
-    println!("{} {}", "In progress:".bold(), stats.in_progress);
-    println!("{} {}", "Overdue:".bold(), stats.overdue);
-    println!("{} {:.1}%", "Completion rate:".bold(), stats.completion_rate);
+    let tz = manager.config.display_timezone;
+    for event in &task.history {
+        let when = event.timestamp.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string();
+        let old = event.old.as_deref().unwrap_or("(none)");
+        let new = event.new.as_deref().unwrap_or("(none)");
+        println!("{}  {}: {} → {}", when.dimmed(), event.field.bold(), old, new);
+    }
 
     Ok(())
 }
 
-/// Clear tasks based on status, supporting both completed-only and all tasks
-async fn handle_clear(manager: &mut TaskManager, all: bool, force: bool) -> Result<()> {
-    let count = if all { manager.get_all_tasks().count() } else {
-        manager.get_tasks_by_status(crate::task::TaskStatus::Done).count()
-    };
+/// List all instances of a recurring task's series with their completion dates
+async fn handle_series(manager: &TaskManager, id: &str) -> Result<()> {
+    let id = resolve_task_ref(manager, id).await?;
+    let tasks = manager.series_tasks(&id)?;
+    let completed = tasks.iter().filter(|t| t.status == crate::task::TaskStatus::Done).count();
 
-    if !force {
-        let prompt = if all {
-            format!("Are you sure you want to delete ALL {} tasks? (y/N): ", count)
+    println!("{}", format!("🔁 Series ({} of {} instances completed):", completed, tasks.len()).cyan().bold());
+    println!("{}", "─".repeat(80).dimmed());
+
+    for task in tasks {
+        match task.completed_at {
+            Some(completed_at) => {
+                let completed_on = completed_at.with_timezone(&manager.config.display_timezone).format("%Y-%m-%d");
+                print!("{} ", completed_on.to_string().green())
+            }
+            None => print!("{} ", "pending   ".dimmed()),
+        }
+        print_task_summary(task, CATEGORY_MAX_DISPLAY, manager.config.display_timezone, TimeDisplay::new(manager, false), manager.now());
+    }
+
+    Ok(())
+}
+
+/// Fold one task into another, keeping the first task's ID
+async fn handle_merge(manager: &mut TaskManager, keep: &str, from: &str) -> Result<()> {
+    let keep = resolve_task_ref(manager, keep).await?;
+    let from = resolve_task_ref(manager, from).await?;
+    manager.merge_tasks(&keep, &from)?;
+    println!("{}", format!("✓ Merged task {} into {}", from, keep).green());
+    Ok(())
+}
+
+/// Mark a task as being worked on (In Progress)
+async fn handle_start(manager: &mut TaskManager, ids: Vec<String>, id_file: Option<PathBuf>, idempotent: bool) -> Result<()> {
+    let ids = with_id_file(ids, id_file).await?;
+    for_each_task(manager, ids, "started", |manager, task_id| {
+        if idempotent && already_in_status(manager, task_id, crate::task::TaskStatus::InProgress)? {
+            return Ok(());
+        }
+        manager.start_task(task_id)?;
+        println!("{}", format!("▶ Started working on task {}", task_id).green());
+        Ok(())
+    })
+    .await
+}
+
+/// Mark a task as waiting on someone or something external
+async fn handle_wait(manager: &mut TaskManager, id: Option<String>) -> Result<()> {
+    let task_id = match id {
+        Some(id) => resolve_task_ref(manager, &id).await?,
+        None => select_task_interactive(manager).await?,
+    };
+
+    manager.wait_task(&task_id)?;
+    println!("{}", format!("⏳ Task {} is now waiting", task_id).yellow());
+    Ok(())
+}
+
+/// Move a task to any status through the same lifecycle checks as the dedicated commands
+async fn handle_set_status(manager: &mut TaskManager, id: &str, status: crate::task::TaskStatus) -> Result<()> {
+    let task_id = resolve_task_ref(manager, id).await?;
+    let next = manager.set_status(&task_id, status)?;
+    println!("{}", format!("✓ Task {} is now {}", task_id, status.label()).green());
+    if let Some(next_id) = next {
+        println!("{}", format!("🔁 Next occurrence created: {}", next_id).cyan());
+    }
+    Ok(())
+}
+
+/// Mark a task as cancelled
+async fn handle_cancel(manager: &mut TaskManager, ids: Vec<String>, id_file: Option<PathBuf>, idempotent: bool) -> Result<()> {
+    let ids = with_id_file(ids, id_file).await?;
+    for_each_task(manager, ids, "cancelled", |manager, task_id| {
+        if idempotent && already_in_status(manager, task_id, crate::task::TaskStatus::Cancelled)? {
+            return Ok(());
+        }
+        manager.cancel_task(task_id)?;
+        println!("{}", format!("❌ Cancelled task {}", task_id).yellow());
+        Ok(())
+    })
+    .await
+}
+
+/// Report and return true if the task already has `status`, so `--idempotent` can skip it
+fn already_in_status(manager: &TaskManager, task_id: &str, status: crate::task::TaskStatus) -> Result<bool> {
+    if manager.get_task(task_id)?.status != status {
+        return Ok(false);
+    }
+    println!("{}", format!("ℹ Task {} is already {}", task_id, status.label()).cyan());
+    Ok(true)
+}
+
+/// Delete tasks permanently, with one confirmation prompt unless forced.
+///
+/// Tasks with subtasks are only deleted with `cascade`, which removes the subtasks too.
+async fn handle_delete(manager: &mut TaskManager, ids: Vec<String>, id_file: Option<PathBuf>, force: bool, cascade: bool) -> Result<()> {
+    let ids = with_id_file(ids, id_file).await?;
+    let ids = match ids.as_slice() {
+        [] => vec![select_task_interactive(manager).await?],
+        _ => ids,
+    };
+    let total = ids.len();
+
+    // Resolve everything up front so one prompt covers exactly the tasks that exist
+    let mut found: Vec<String> = Vec::new();
+    let mut failures = Vec::new();
+    for id in &ids {
+        match resolve_task_ref(manager, id).await {
+            Ok(task_id) if !found.contains(&task_id) => found.push(task_id),
+            Ok(_) => {}
+            Err(e) if total == 1 => return Err(e),
+            Err(e) => {
+                eprintln!("{}", format!("✗ {}: {}", id, e).red());
+                failures.push(failure_reason(&e));
+            }
+        }
+    }
+    if found.is_empty() {
+        println!("{}", batch_summary("deleted", 0, &failures).yellow());
+        return Err(TaskError::BatchFailed { failed: failures.len(), total });
+    }
+
+    let prompt = match found.as_slice() {
+        [id] if total == 1 => format!("Are you sure you want to delete task {}? (y/N): ", id),
+        _ => {
+            for id in &found {
+                println!("  {} {}", id.get(..UUID_DISPLAY_LENGTH).unwrap_or(id).dimmed(), manager.get_task(id)?.title);
+            }
+            format!("Delete these {} tasks? (y/N): ", found.len())
+        }
+    };
+    if !force && !confirm(&prompt)? {
+        println!("{}", "Operation cancelled.".yellow());
+        return Ok(());
+    }
+
+    let mut deleted = 0;
+    // Subtasks already trashed along with a parent listed earlier in this batch
+    let mut cascaded: Vec<String> = Vec::new();
+    for task_id in &found {
+        if cascaded.contains(task_id) {
+            println!("{}", format!("🗑 Task {} was moved to the trash with its parent", task_id).dimmed());
+            deleted += 1;
+            continue;
+        }
+        let outcome = if cascade {
+            manager.delete_task_cascade(task_id).map(|removed| {
+                println!("{}", format!("🗑 Moved task {} and {} subtask(s) to the trash", task_id, removed.len() - 1).red());
+                cascaded.extend(removed.iter().skip(1).map(|task| task.id.to_string()));
+            })
+        } else {
+            manager.delete_task(task_id).map(|_| println!("{}", format!("🗑 Moved task {} to the trash", task_id).red()))
+        };
+        match outcome {
+            Ok(()) => deleted += 1,
+            Err(e) if total == 1 => return Err(e),
+            Err(e) => {
+                eprintln!("{}", format!("✗ {}: {}", task_id, e).red());
+                failures.push(failure_reason(&e));
+            }
+        }
+    }
+
+    if total == 1 {
+        return Ok(());
+    }
+    let summary = batch_summary("deleted", deleted, &failures);
+    if failures.is_empty() {
+        println!("{}", summary.green());
+        Ok(())
+    } else {
+        println!("{}", summary.yellow());
+        Err(TaskError::BatchFailed { failed: failures.len(), total })
+    }
+}
+
+/// Append the task IDs listed in `id_file`, one per line, to those given as arguments.
+///
+/// Blank lines and lines starting with `#` are skipped. A file without any IDs is an
+/// error rather than falling back to interactive selection.
+async fn with_id_file(mut ids: Vec<String>, id_file: Option<PathBuf>) -> Result<Vec<String>> {
+    let Some(path) = id_file else {
+        return Ok(ids);
+    };
+    let contents = tokio::fs::read_to_string(&path).await.map_err(|e| TaskError::FileOperationError(
+        format!("Could not read ID file {}: {}", path.display(), e)
+    ))?;
+    let listed = parse_id_list(&contents);
+    if listed.is_empty() {
+        return Err(TaskError::ValidationError(format!("ID file {} lists no task IDs", path.display())));
+    }
+    ids.extend(listed);
+    Ok(ids)
+}
+
+/// Task IDs from the lines of an ID file, skipping blank lines and `#` comments
+fn parse_id_list(contents: &str) -> Vec<String> {
+    contents.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Apply `action` to each referenced task, or to one chosen interactively if none are given.
+///
+/// A single task's error is returned as is. With several, each failure is reported
+/// and the rest still run, then a summary such as "completed 8, 2 failed (not found)"
+/// is printed using `verb`; the result is an error if any of them failed.
+async fn for_each_task<F>(manager: &mut TaskManager, ids: Vec<String>, verb: &str, mut action: F) -> Result<()>
+where
+    F: FnMut(&mut TaskManager, &str) -> Result<()>,
+{
+    if ids.len() <= 1 {
+        let task_id = match ids.into_iter().next() {
+            Some(id) => resolve_task_ref(manager, &id).await?,
+            None => select_task_interactive(manager).await?,
+        };
+        return action(manager, &task_id);
+    }
+
+    let total = ids.len();
+    let mut failures = Vec::new();
+    for id in ids {
+        let outcome = match resolve_task_ref(manager, &id).await {
+            Ok(task_id) => action(manager, &task_id),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = outcome {
+            eprintln!("{}", format!("✗ {}: {}", id, e).red());
+            failures.push(failure_reason(&e));
+        }
+    }
+
+    let summary = batch_summary(verb, total - failures.len(), &failures);
+    if failures.is_empty() {
+        println!("{}", summary.green());
+        Ok(())
+    } else {
+        println!("{}", summary.yellow());
+        Err(TaskError::BatchFailed { failed: failures.len(), total })
+    }
+}
+
+/// Short reason for a failed task in a batch summary
+fn failure_reason(error: &TaskError) -> &'static str {
+    match error {
+        TaskError::TaskNotFound(_) => "not found",
+        TaskError::InvalidId(_) => "invalid ID",
+        TaskError::AmbiguousId { .. } => "ambiguous ID",
+        TaskError::ValidationError(_) => "invalid",
+        TaskError::OperationNotAllowed(_) => "not allowed",
+        _ => "error",
+    }
+}
+
+/// "completed 8, 2 failed (not found)", or with mixed reasons
+/// "completed 7, 3 failed (2 not found, 1 not allowed)"
+fn batch_summary(verb: &str, succeeded: usize, failures: &[&str]) -> String {
+    if failures.is_empty() {
+        return format!("{} {}", verb, succeeded);
+    }
+    let mut reasons: Vec<(&str, usize)> = Vec::new();
+    for reason in failures {
+        match reasons.iter_mut().find(|(seen, _)| seen == reason) {
+            Some((_, count)) => *count += 1,
+            None => reasons.push((reason, 1)),
+        }
+    }
+    let reasons = match reasons.as_slice() {
+        [(reason, _)] => reason.to_string(),
+        _ => reasons.iter().map(|(reason, count)| format!("{} {}", count, reason)).collect::<Vec<_>>().join(", "),
+    };
+    format!("{} {}, {} failed ({})", verb, succeeded, failures.len(), reasons)
+}
+
+/// Bulk delete operation for all tasks with a double-confirmation prompt
+async fn handle_delete_all(manager: &mut TaskManager, force: bool, confirm_each: bool) -> Result<()> {
+    let count = manager.get_all_tasks().count();
+
+    if count == 0 {
+        println!("{}", "No tasks to delete.".yellow());
+        return Ok(());
+    }
+
+    if confirm_each {
+        let ids = manager.get_sorted_tasks(crate::manager::TaskSort::CreatedDesc)
+            .iter()
+            .map(|t| t.id.to_string())
+            .collect();
+        return delete_each_with_confirmation(manager, ids);
+    }
+
+    if !force {
+        let prompt = format!("Are you sure you want to delete ALL {} tasks? They can be restored from the trash. (y/N): ", count);
+        if !confirm(&prompt)? {
+            println!("{}", "Operation cancelled.".yellow());
+            return Ok(());
+        }
+    }
+
+    let removed = manager.clear_all();
+    println!("{}", format!("🗑 Moved all {} tasks to the trash", removed).red().bold());
+    Ok(())
+}
+
+/// Display aggregate task statistics including completion rate and status counts
+///
+/// With a trend snapshot, each figure is followed by its change since the snapshot.
+#[allow(clippy::too_many_arguments)]
+async fn handle_stats(
+    manager: &TaskManager,
+    trend: Option<PathBuf>,
+    histogram: bool,
+    since: Option<String>,
+    by_owner: bool,
+    by_priority: bool,
+    json: bool,
+    include_archived: bool,
+) -> Result<()> {
+    let stats = if include_archived { manager.get_stats_including_archived() } else { manager.get_stats() };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    let previous = match trend {
+        Some(path) => {
+            let data = tokio::fs::read(&path).await.map_err(|e| TaskError::FileOperationError(
+                format!("Could not read snapshot {}: {}", path.display(), e)
+            ))?;
+            let snapshot: Vec<crate::task::Task> = serde_json::from_slice(&data)?;
+            Some(crate::manager::TaskStats::from_tasks(&snapshot, manager.now()))
+        }
+        None => None,
+    };
+
+    println!("{}", "📊 Task Statistics".cyan().bold());
+    println!("{}", "─".repeat(30).dimmed());
+
+    let delta = |current: usize, before: fn(&crate::manager::TaskStats) -> usize| {
+        previous.as_ref().map_or(String::new(), |p| format_delta(current as f64 - before(p) as f64, ""))
+    };
+
+    println!("{} {}{}", "Total tasks:".bold(), stats.total, delta(stats.total, |p| p.total));
+    println!("{} {}{}", "Completed:".bold(), stats.completed, delta(stats.completed, |p| p.completed));
+    println!("{} {}{}", "In progress:".bold(), stats.in_progress, delta(stats.in_progress, |p| p.in_progress));
+    println!("{} {}{}", "Waiting:".bold(), stats.waiting, delta(stats.waiting, |p| p.waiting));
+    println!("{} {}{}", "Overdue:".bold(), stats.overdue, delta(stats.overdue, |p| p.overdue));
+    let rate_delta = previous.as_ref()
+        .map_or(String::new(), |p| format_delta(stats.completion_rate - p.completion_rate, "%"));
+    let bar = completion_bar(stats.completion_rate);
+    let bar = if stats.completion_rate >= manager.config.completion_good_threshold {
+        bar.green()
+    } else if stats.completion_rate >= manager.config.completion_warn_threshold {
+        bar.yellow()
+    } else {
+        bar.red()
+    };
+    println!("{} {}{}", "Completion rate:".bold(), bar, rate_delta);
+
+    if !stats.open_effort.is_empty() || !stats.completed_effort.is_empty() {
+        println!();
+        println!("{}", "Estimated vs tracked".bold());
+        for (label, effort) in [("Open:", stats.open_effort), ("Completed:", stats.completed_effort)] {
+            println!("  {:<11} {}", label, format_effort(effort));
+        }
+    }
+
+    if let Some(warning) = overdue_warning(stats.overdue, manager.config.overdue_warn_threshold) {
+        println!();
+        println!("{}", warning.red().bold());
+    }
+
+    if let (true, Some(since)) = (histogram, since) {
+        let since = chrono::NaiveDate::parse_from_str(&since, "%Y-%m-%d").map_err(|_| {
+            TaskError::DateParseError(format!("Invalid --since date '{}': expected YYYY-MM-DD", since))
+        })?;
+        print_completion_histogram(&manager.completions_by_day(since));
+    }
+
+    if by_priority {
+        print_priority_histogram(&manager.priority_counts());
+    }
+
+    if by_owner {
+        print_owner_breakdown(manager);
+    }
+
+    Ok(())
+}
+
+/// Format a stored UTC timestamp in the display timezone, naming the zone and its offset.
+///
+/// UTC keeps the plain `2024-02-01 14:30:00 UTC` form; other zones read like
+/// `2024-02-01 09:30:00 EST (UTC-05:00)`.
+fn format_timestamp(timestamp: chrono::DateTime<chrono::Utc>, tz: chrono_tz::Tz) -> String {
+    let local = timestamp.with_timezone(&tz);
+    match tz {
+        chrono_tz::Tz::UTC => local.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        _ => local.format("%Y-%m-%d %H:%M:%S %Z (UTC%:z)").to_string(),
+    }
+}
+
+/// Fixed-width progress bar for a percentage, e.g. `[███████████████░░░░░] 78.5%`
+fn completion_bar(rate: f64) -> String {
+    const BAR_WIDTH: usize = 20;
+
+    let filled = ((rate.clamp(0.0, 100.0) / 100.0) * BAR_WIDTH as f64).round() as usize;
+    format!("[{}{}] {:.1}%", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled), rate)
+}
+
+/// `2h 00m estimated, 2h 30m tracked (125%)`; the percentage needs an estimate
+fn format_effort(effort: crate::manager::Effort) -> String {
+    let minutes = |m: u64| format_duration(chrono::Duration::minutes(m as i64));
+    let mut line = format!("{} estimated, {} tracked", minutes(effort.estimated_minutes), minutes(effort.tracked_minutes));
+    if effort.estimated_minutes > 0 {
+        line.push_str(&format!(" ({:.0}%)", effort.tracked_minutes as f64 / effort.estimated_minutes as f64 * 100.0));
+    }
+    line
+}
+
+/// Escalation line for `stats` once the overdue count exceeds the threshold
+fn overdue_warning(overdue: usize, threshold: usize) -> Option<String> {
+    (overdue > threshold).then(|| format!("⚠️ {} overdue tasks need attention!", overdue))
+}
+
+/// Print per-owner figures, owners with the most outstanding tasks first
+fn print_owner_breakdown(manager: &TaskManager) {
+    let mut owners: Vec<_> = manager.get_stats_by_owner().into_iter().collect();
+    owners.sort_by(|(a_name, a), (b_name, b)| b.outstanding().cmp(&a.outstanding()).then_with(|| a_name.cmp(b_name)));
+
+    println!();
+    println!("{}", "By owner".bold());
+    println!(
+        "{}",
+        format!("{:<20} {:>5} {:>5} {:>8} {:>7} {:>7} {:>6}", "Owner", "Open", "Done", "Active", "Waiting", "Overdue", "Rate").dimmed()
+    );
+    for (owner, stats) in owners {
+        println!(
+            "{:<20} {:>5} {:>5} {:>8} {:>7} {:>7} {:>5.1}%",
+            truncate_display(&owner, 20), stats.outstanding(), stats.completed,
+            stats.in_progress, stats.waiting, stats.overdue, stats.completion_rate
+        );
+    }
+}
+
+/// Print one bar per day, scaled so the busiest day spans the full width
+fn print_completion_histogram(buckets: &[(chrono::NaiveDate, usize)]) {
+    const BAR_WIDTH: usize = 40;
+
+    println!();
+    println!("{}", "Completions per day (UTC)".bold());
+    if buckets.is_empty() {
+        println!("{}", "No days in range.".yellow());
+        return;
+    }
+
+    let max = buckets.iter().map(|&(_, count)| count).max().unwrap_or(0).max(1);
+    for (day, count) in buckets {
+        // Any completion gets at least one block so it stays visible next to busy days
+        let width = (count * BAR_WIDTH).div_ceil(max);
+        println!("{} {} {}", day.format("%Y-%m-%d"), "█".repeat(width).green(), count);
+    }
+}
+
+/// Print one bar per priority level, scaled so the most common level spans the full
+/// width and colored to match the priority dots in task lists
+fn print_priority_histogram(counts: &[(crate::task::Priority, usize)]) {
+    use crate::task::Priority;
+    const BAR_WIDTH: usize = 30;
+
+    println!();
+    println!("{}", "Tasks by priority".bold());
+    let max = counts.iter().map(|&(_, count)| count).max().unwrap_or(0).max(1);
+    for &(priority, count) in counts {
+        let bar = "█".repeat((count * BAR_WIDTH).div_ceil(max));
+        let bar = match priority {
+            Priority::None => bar.dimmed(),
+            Priority::Low => bar.green(),
+            Priority::Medium => bar.yellow(),
+            Priority::High => bar.truecolor(255, 165, 0),
+            Priority::Critical => bar.red(),
+        };
+        println!("{:<8} {} {}", format!("{:?}", priority), bar, count);
+    }
+}
+
+/// Format a signed change for trend output, e.g. " (+3)" or " (-1.5%)"
+fn format_delta(change: f64, unit: &str) -> String {
+    let text = if change.fract() == 0.0 {
+        format!(" ({:+}{})", change as i64, unit)
+    } else {
+        format!(" ({:+.1}{})", change, unit)
+    };
+    text.dimmed().to_string()
+}
+
+/// Print overdue and soon-due tasks as a JSON array with a stable schema
+async fn handle_reminders(manager: &TaskManager, within: String) -> Result<()> {
+    let within = crate::task::parse_duration_offset(&within)?;
+    let reminders = manager.get_reminders(within);
+    println!("{}", serde_json::to_string_pretty(&reminders)?);
+    Ok(())
+}
+
+/// Fail with `ThresholdExceeded` (exit status 1) if the overdue count exceeds the threshold, for cron/CI probes
+async fn handle_check(manager: &TaskManager, threshold: usize) -> Result<()> {
+    let overdue = manager.get_overdue_tasks().count();
+
+    if overdue > threshold {
+        eprintln!("{}", format!("✗ {} overdue tasks (threshold: {})", overdue, threshold).red());
+        return Err(TaskError::ThresholdExceeded { overdue, threshold });
+    }
+
+    println!("{}", format!("✓ {} overdue tasks (threshold: {})", overdue, threshold).green());
+    Ok(())
+}
+
+/// Normalize the data file on disk and report how much space was reclaimed
+async fn handle_compact(manager: &mut TaskManager, drop_cancelled_older_than: Option<String>) -> Result<()> {
+    let cutoff = match drop_cancelled_older_than {
+        Some(window) => Some(manager.now() - crate::task::parse_duration_offset(&window)?),
+        None => None,
+    };
+
+    let report = manager.compact(cutoff).await?;
+    println!("{}", format!(
+        "✓ Compacted {}: {} → {} bytes",
+        manager.config.storage_path.display(), report.bytes_before, report.bytes_after
+    ).green());
+    if report.removed > 0 {
+        println!("{}", format!("  Dropped {} old cancelled task(s)", report.removed).dimmed());
+    }
+    Ok(())
+}
+
+/// List the data file's backups, or restore the chosen one after confirmation
+async fn handle_restore(manager: &mut TaskManager, backup: Option<String>, force: bool) -> Result<()> {
+    let backups = manager.list_backups().await?;
+    let tz = manager.config.display_timezone;
+
+    let Some(backup) = backup else {
+        if backups.is_empty() {
+            println!("{}", format!("No backups of {} found.", manager.config.storage_path.display()).yellow());
+            return Ok(());
+        }
+        println!("{}", format!("🗄 Backups of {} ({}):", manager.config.storage_path.display(), backups.len()).cyan().bold());
+        for (index, backup) in backups.iter().enumerate() {
+            println!(
+                "  {:>2}. {} {}",
+                index + 1,
+                backup.taken_at.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"),
+                backup.path.display().to_string().dimmed()
+            );
+        }
+        println!("{}", "Restore one with `task-manager restore <N>`.".dimmed());
+        return Ok(());
+    };
+
+    let path = match backup.parse::<usize>() {
+        Ok(number) => backups.get(number.wrapping_sub(1)).map(|b| b.path.clone()).ok_or_else(|| {
+            TaskError::ValidationError(format!("No backup #{}; there are {}", number, backups.len()))
+        })?,
+        Err(_) => PathBuf::from(backup),
+    };
+
+    if !force {
+        let prompt = format!(
+            "Replace all {} current tasks with the contents of {}? (y/N): ",
+            manager.get_all_tasks().count(), path.display()
+        );
+        if !confirm(&prompt)? {
+            println!("{}", "Operation cancelled.".yellow());
+            return Ok(());
+        }
+    }
+
+    let restored = manager.restore_backup(&path).await?;
+    println!("{}", format!("♻ Restored {} tasks from {}", restored, path.display()).green());
+    Ok(())
+}
+
+/// List workspace projects with the number of tasks in each project's file
+async fn handle_projects(workspace_path: &std::path::Path) -> Result<()> {
+    let workspace = workspace::Workspace::load(workspace_path)?;
+    if workspace.projects.is_empty() {
+        println!("{}", "No projects configured.".yellow());
+        return Ok(());
+    }
+
+    println!("{}", format!("📁 Projects ({}):", workspace.projects.len()).cyan().bold());
+    for (name, path) in workspace.projects() {
+        let mut project = TaskManager::with_config(TaskManagerConfig {
+            storage_path: path.clone(),
+            storage_format: StorageFormat::from_path(&path).unwrap_or_default(),
+            auto_save: false,
+            ..Default::default()
+        });
+        let count = match project.load().await {
+            Ok(()) => format!("{} task(s)", project.get_all_tasks().count()),
+            Err(e) => format!("unreadable: {}", e).red().to_string(),
+        };
+        println!("  {} {} {}", name.bold(), format!("({})", path.display()).dimmed(), count);
+    }
+    Ok(())
+}
+
+/// Clear tasks based on status, supporting both completed-only and all tasks
+async fn handle_clear(manager: &mut TaskManager, all: bool, force: bool, confirm_each: bool) -> Result<()> {
+    let count = if all { manager.get_all_tasks().count() } else {
+        manager.get_tasks_by_status(crate::task::TaskStatus::Done).count()
+    };
+
+    if confirm_each {
+        let ids = manager.get_sorted_tasks(crate::manager::TaskSort::CreatedDesc)
+            .iter()
+            .filter(|t| all || t.status == crate::task::TaskStatus::Done)
+            .map(|t| t.id.to_string())
+            .collect();
+        return delete_each_with_confirmation(manager, ids);
+    }
+
+    if !force {
+        let prompt = if all {
+            format!("Are you sure you want to delete ALL {} tasks? (y/N): ", count)
         } else {
             format!("Are you sure you want to delete {} completed tasks? (y/N): ", count)
         };
 
-        print!("{}", prompt);
-        io::stdout().flush()?;
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let input = sanitize_input(&input)?;
-        if !input.eq_ignore_ascii_case("y") && !input.eq_ignore_ascii_case("yes") {
-            println!("{}", "Operation cancelled.".yellow());
-            return Ok(());
+        if !confirm(&prompt)? {
+            println!("{}", "Operation cancelled.".yellow());
+            return Ok(());
+        }
+    }
+
+    let removed = if all {
+        manager.clear_all()
+    } else {
+        manager.clear_completed()
+    };
+
+    println!("{}", format!("🧹 Cleared {} tasks (moved to the trash)", removed).green());
+    Ok(())
+}
+
+/// Walk through tasks one at a time, deleting only those the user approves.
+///
+/// Answering 'q' stops early and keeps every remaining task.
+fn delete_each_with_confirmation(manager: &mut TaskManager, ids: Vec<String>) -> Result<()> {
+    if ids.is_empty() {
+        println!("{}", "No tasks to delete.".yellow());
+        return Ok(());
+    }
+
+    let total = ids.len();
+    let mut deleted = 0;
+    for (i, id) in ids.iter().enumerate() {
+        print_task_summary(manager.get_task(id)?, CATEGORY_MAX_DISPLAY, manager.config.display_timezone, TimeDisplay::new(manager, false), manager.now());
+        let answer = confirmation_answer(&format!("Delete this task? ({}/{}) (y/N/q): ", i + 1, total))?;
+        if answer.eq_ignore_ascii_case("q") || answer.eq_ignore_ascii_case("quit") {
+            println!("{}", "Stopped; remaining tasks kept.".yellow());
+            break;
+        }
+        if answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes") {
+            match manager.delete_task(id) {
+                Ok(_) => deleted += 1,
+                // Keeping a subtask keeps its parent too
+                Err(TaskError::OperationNotAllowed(reason)) => println!("{}", reason.yellow()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    println!("{}", format!("🗑 Moved {} tasks to the trash, kept {}", deleted, total - deleted).green());
+    Ok(())
+}
+
+/// Move every finished task into the archive
+async fn handle_archive(manager: &mut TaskManager) -> Result<()> {
+    let archived = manager.archive_completed();
+    if archived == 0 {
+        println!("{}", "No finished tasks to archive.".yellow());
+    } else {
+        println!("{}", format!("📦 Archived {} tasks (see them with `list --archived`)", archived).green());
+    }
+    Ok(())
+}
+
+/// Bring an archived task back into the active list
+async fn handle_unarchive(manager: &mut TaskManager, id: &str) -> Result<()> {
+    let id = manager.get_archived_by_prefix(id)?.id.to_string();
+    manager.unarchive(&id)?;
+    println!("{}", format!("📦 Unarchived task {}", id).green());
+    Ok(())
+}
+
+/// List, restore from or empty the trash of deleted tasks
+async fn handle_trash(manager: &mut TaskManager, action: Option<cli::TrashAction>) -> Result<()> {
+    match action.unwrap_or(cli::TrashAction::List) {
+        cli::TrashAction::List => {
+            let trashed = manager.trashed_tasks();
+            if trashed.is_empty() {
+                println!("{}", "The trash is empty.".yellow());
+                return Ok(());
+            }
+            println!("{}", format!("🗑 Trash ({} tasks):", trashed.len()).cyan().bold());
+            println!("{}", "─".repeat(80).dimmed());
+            for task in trashed {
+                print_task_summary(task, CATEGORY_MAX_DISPLAY, manager.config.display_timezone, TimeDisplay::new(manager, false), manager.now());
+            }
+        }
+        cli::TrashAction::Restore { id } => {
+            let id = manager.get_trashed_by_prefix(&id)?.id.to_string();
+            manager.restore_from_trash(&id)?;
+            println!("{}", format!("♻ Restored task {} from the trash", id).green());
+        }
+        cli::TrashAction::Empty { force } => {
+            let count = manager.trash.len();
+            if count == 0 {
+                println!("{}", "The trash is empty.".yellow());
+                return Ok(());
+            }
+            let prompt = format!("Permanently delete {} tasks in the trash? This cannot be undone. (y/N): ", count);
+            if !force && !confirm(&prompt)? {
+                println!("{}", "Operation cancelled.".yellow());
+                return Ok(());
+            }
+            let removed = manager.empty_trash()?;
+            println!("{}", format!("🗑 Permanently deleted {} tasks", removed).red().bold());
+        }
+    }
+    Ok(())
+}
+
+/// Import tasks from a JSON, YAML or CSV file with validation and duplicate skipping
+async fn handle_import(
+    manager: &mut TaskManager,
+    file: PathBuf,
+    dedupe_on: Option<cli::DedupeArg>,
+    strategy: manager::ImportStrategy,
+    dry_run: bool,
+    strict_schema: bool,
+    skip_checksum: bool,
+) -> Result<()> {
+    let max_size = manager.config.max_import_size;
+
+    // `-` reads JSON from stdin. There's no file to canonicalize, size up front or
+    // re-open, so the input is buffered (up to the size cap) instead
+    let stdin_data = match file.as_os_str() == STREAM_SENTINEL {
+        true => Some(read_stdin(max_size)?),
+        false => None,
+    };
+    let file = match stdin_data {
+        Some(_) => file,
+        None => {
+            // Canonicalize path to prevent directory traversal
+            let file = file.canonicalize().map_err(|e| TaskError::FileOperationError(
+                format!("Invalid file path: {}", e)
+            ))?;
+
+            // Check file size before reading
+            let metadata: std::fs::Metadata = tokio::fs::metadata(&file).await?;
+            if metadata.len() > max_size {
+                return Err(TaskError::FileOperationError(format!(
+                    "File too large: {} bytes (max: {} bytes; raise it with --max-import-size)",
+                    metadata.len(), max_size
+                )));
+            }
+
+            if !skip_checksum && crate::checksum::verify_sidecar(&file).await? {
+                println!("{}", "Checksum verified".dimmed());
+            }
+            file
+        }
+    };
+    let source = match stdin_data {
+        Some(_) => "stdin".to_string(),
+        None => file.display().to_string(),
+    };
+
+    let format = StorageFormat::from_path(&file).unwrap_or_default();
+    let open = || -> Result<Box<dyn std::io::BufRead + '_>> {
+        match &stdin_data {
+            Some(data) => Ok(Box::new(data.as_slice())),
+            None => Ok(Box::new(std::io::BufReader::new(std::fs::File::open(&file)?))),
+        }
+    };
+
+    // CSV rows are parsed up front; rows that can't be read are reported after the import
+    let csv = match file.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) {
+        true => Some(spreadsheet::read_csv(open()?, manager.config.input_timezone, manager.now())?),
+        false => None,
+    };
+    let row_errors = csv.as_ref().map(|csv| csv.errors.clone()).unwrap_or_default();
+
+    if strict_schema {
+        match &csv {
+            Some(csv) if !csv.unknown_columns.is_empty() => {
+                return Err(TaskError::ValidationError(format!(
+                    "Strict schema check failed: unknown column(s) {}", csv.unknown_columns.join(", ")
+                )));
+            }
+            Some(_) => {}
+            None => check_strict_schema(open()?, format)?,
+        }
+    }
+
+    if dry_run {
+        let imported_tasks: Vec<crate::task::Task> = match (csv, format) {
+            (Some(csv), _) => csv.tasks,
+            (None, StorageFormat::Json) => serde_json::from_reader(open()?)?,
+            (None, StorageFormat::Yaml) => serde_yaml::from_reader(open()?)?,
+        };
+        let preview = manager.preview_import(&imported_tasks, dedupe_on.map(Into::into), strategy);
+        println!("{}", format!("🔍 Import preview for {} (nothing imported)", source).cyan().bold());
+        println!("{} {}", "Tasks in file:".bold(), preview.total);
+        println!("{} {}", "Would import:".bold(), preview.summary.imported);
+        match strategy {
+            manager::ImportStrategy::Skip => println!("{} {}", "Skipped (same ID):".bold(), preview.summary.skipped_existing),
+            manager::ImportStrategy::Merge => println!("{} {}", "Would merge (same ID):".bold(), preview.summary.merged),
+            manager::ImportStrategy::Overwrite => println!("{} {}", "Would overwrite (same ID):".bold(), preview.summary.overwritten),
+            manager::ImportStrategy::RenameDuplicates => println!("{} {}", "Would rename (same ID):".bold(), preview.summary.renamed),
+        }
+        if dedupe_on.is_some() {
+            println!("{} {}", "Skipped (duplicate title):".bold(), preview.summary.skipped_duplicate_title);
+        }
+        println!("{} {}", "Invalid:".bold(), row_errors.len() + preview.errors.len());
+        for error in row_errors.iter().chain(&preview.errors) {
+            println!("  {}", error.red());
+        }
+        return Ok(());
+    }
+
+    let summary = match (csv, format) {
+        (Some(csv), _) => manager.import_tasks(csv.tasks, dedupe_on.map(Into::into), strategy)?,
+        // Stream tasks into the manager so memory use stays flat for large files
+        (None, StorageFormat::Json) => manager.import_reader(open()?, dedupe_on.map(Into::into), strategy)?,
+        (None, StorageFormat::Yaml) => manager.import_tasks(serde_yaml::from_reader(open()?)?, dedupe_on.map(Into::into), strategy)?,
+    };
+
+    println!("{}", format!("📥 Imported {} tasks from {}", summary.imported, source).green());
+    if summary.total() > summary.imported {
+        println!("{}", summary.to_string().dimmed());
+    }
+
+    if !row_errors.is_empty() {
+        for error in &row_errors {
+            eprintln!("{}", format!("✗ {}", error).red());
+        }
+        let total = row_errors.len() + summary.total();
+        return Err(TaskError::BatchFailed { failed: row_errors.len(), total });
+    }
+    Ok(())
+}
+
+/// Path argument that makes `import` read stdin and `export` write stdout
+const STREAM_SENTINEL: &str = "-";
+
+/// Read all of stdin, failing once it grows past `max_size` bytes
+fn read_stdin(max_size: u64) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut data = Vec::new();
+    std::io::stdin().lock().take(max_size + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > max_size {
+        return Err(TaskError::FileOperationError(format!(
+            "Input too large: more than {} bytes (raise the limit with --max-import-size)", max_size
+        )));
+    }
+    Ok(data)
+}
+
+/// Reject import data containing task fields this version does not know about.
+///
+/// The known fields are taken from how `Task` itself serializes, so the check
+/// stays in sync with the model as fields are added.
+fn check_strict_schema(reader: impl std::io::Read, format: StorageFormat) -> Result<()> {
+    let known = match serde_json::to_value(crate::task::Task::default())? {
+        serde_json::Value::Object(fields) => fields,
+        _ => unreachable!("Task serializes to a JSON object"),
+    };
+
+    let mut unknown = Vec::new();
+    let mut index = 0;
+    let mut check = |task: serde_json::Map<String, serde_json::Value>| {
+        index += 1;
+        unknown.extend(
+            task.keys()
+                .filter(|field| !known.contains_key(*field))
+                .map(|field| format!("task #{}: unknown field `{}`", index, field)),
+        );
+        Ok(())
+    };
+    match format {
+        StorageFormat::Json => crate::manager::for_each_json_array_item(reader, check)?,
+        StorageFormat::Yaml => {
+            let tasks: Vec<serde_json::Map<String, serde_json::Value>> = serde_yaml::from_reader(reader)?;
+            tasks.into_iter().try_for_each(&mut check)?;
         }
     }
 
-    let removed = if all {
-        manager.clear_all()
+    if unknown.is_empty() {
+        Ok(())
     } else {
-        manager.clear_completed()
-    };
+        Err(TaskError::ValidationError(format!("Strict schema check failed: {}", unknown.join("; "))))
+    }
+}
 
-    println!("{}", format!("🧹 Cleared {} tasks", removed).green());
-    Ok(())
+/// The data file passphrase from `TASK_MANAGER_KEY`, or else typed at a prompt.
+///
+/// A new passphrase (for a file that isn't encrypted yet) is asked for twice to
+/// catch typos that would lock the tasks away.
+fn read_passphrase(existing: bool) -> Result<crypto::Passphrase> {
+    if let Ok(key) = std::env::var(crypto::KEY_ENV_VAR) {
+        if !key.is_empty() {
+            return Ok(crypto::Passphrase::new(key));
+        }
+    }
+
+    let prompt = |text: &str| rpassword::prompt_password(text).map_err(|e| TaskError::EncryptionError(
+        format!("could not read the passphrase ({}); set {} instead", e, crypto::KEY_ENV_VAR)
+    ));
+    let passphrase = prompt("Passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(TaskError::ValidationError("Passphrase cannot be empty".to_string()));
+    }
+    if !existing && prompt("Repeat passphrase: ")? != passphrase {
+        return Err(TaskError::ValidationError("Passphrases do not match".to_string()));
+    }
+    Ok(crypto::Passphrase::new(passphrase))
 }
 
-/// Import tasks from a JSON file with validation and duplicate skipping
-async fn handle_import(manager: &mut TaskManager, file: PathBuf) -> Result<()> {
-    // Canonicalize path to prevent directory traversal
-    let file = file.canonicalize().map_err(|e| TaskError::FileOperationError(
-        format!("Invalid file path: {}", e)
+/// Environment variables that change how the tool behaves, shown by `config show` when set
+const RELEVANT_ENV_VARS: &[&str] = &["TASK_MANAGER_FILE", "TASK_PROJECT", "TASK_MANAGER_CONFIG", "TASK_MANAGER_KEY", "VISUAL", "EDITOR", "NO_COLOR", "CLICOLOR", "CLICOLOR_FORCE"];
+
+/// Write the commented config template to `path`, or the default config path
+fn handle_config_init(path: Option<PathBuf>, force: bool) -> Result<()> {
+    let path = path.or_else(settings::default_path).ok_or_else(|| TaskError::ConfigError(
+        config::ConfigError::Message("no home directory to put the config file in; pass --config".to_string())
     ))?;
+    settings::init(&path, force)?;
+    println!("{}", format!("✓ Wrote config file to {}", path.display()).green());
+    println!("{}", "Uncomment the options you want to set.".dimmed());
+    Ok(())
+}
 
-    // Check file size before reading
-    let metadata: std::fs::Metadata = tokio::fs::metadata(&file).await?;
-    if metadata.len() > MAX_IMPORT_SIZE {
-        return Err(TaskError::FileOperationError(
-            format!("File too large: {} bytes (max: {} bytes)", metadata.len(), MAX_IMPORT_SIZE)
-        ));
-    }
+/// Print the effective configuration after flags, environment and defaults are merged
+async fn handle_config_show(
+    manager: &TaskManager,
+    storage_source: &str,
+    workspace: &std::path::Path,
+    command: &clap::Command,
+    config_file: Option<&std::path::Path>,
+) -> Result<()> {
+    let config = &manager.config;
+    let on_off = |enabled: bool| if enabled { "on".green() } else { "off".dimmed() };
+
+    println!("{}", "⚙ Effective configuration".cyan().bold());
+    println!("{}", "─".repeat(40).dimmed());
 
-    // Read file data
-    let data = tokio::fs::read(&file).await?;
-    let imported_tasks: Vec<crate::task::Task> = serde_json::from_slice(&data)?;
+    let exists = if config.storage_path.exists() { "exists" } else { "not created yet" };
+    println!("{} {} {}", "Data file:".bold(), config.storage_path.display(), format!("({}, {})", storage_source, exists).dimmed());
+    println!("{} {}", "Data format:".bold(), config.storage_format);
+    println!("{} {}", "Tasks loaded:".bold(), manager.get_all_tasks().count());
+    println!("{} {}", "Compression:".bold(), on_off(config.compress));
+    println!("{} {}", "Encryption:".bold(), on_off(config.passphrase.is_some()));
+    println!("{} {}", "Auto-save:".bold(), on_off(config.auto_save));
+    match config.wal_enabled {
+        true => println!("{} {} {}", "Write-ahead log:".bold(), on_off(true), config.wal_path().display()),
+        false => println!("{} {}", "Write-ahead log:".bold(), on_off(false)),
+    }
+    println!("{} {}", "Workspace file:".bold(), workspace.display());
+    match config_file {
+        Some(path) => println!("{} {}", "Config file:".bold(), path.display()),
+        None => println!("{} {}", "Config file:".bold(), "none (settings come from flags and environment)".dimmed()),
+    }
 
-    // Use the manager's import method for validation and safe insertion
-    let imported_count = manager.import_tasks(imported_tasks)?;
+    println!();
+    println!("{} {}", "Default priority:".bold(), subcommand_default(command, "add", "priority"));
+    println!("{} {}", "Default sort:".bold(), subcommand_default(command, "list", "sort"));
+    println!("{} {}", "Input timezone:".bold(), config.input_timezone);
+    println!("{} {}", "Display timezone:".bold(), config.display_timezone);
+    println!("{} {}", "Week starts on:".bold(), config.week_start);
+    let limits = &config.validation_limits;
+    println!(
+        "{} title {}, description {}, category {}, note {}",
+        "Length limits:".bold(), limits.title_max, limits.description_max, limits.category_max, limits.note_max
+    );
+    println!("{} {} bytes", "Import size cap:".bold(), config.max_import_size);
+    match config.backups {
+        0 => println!("{} {}", "Backups:".bold(), on_off(false)),
+        keep => println!("{} last {} kept", "Backups:".bold(), keep),
+    }
+    println!("{} more than {} overdue", "Stats warning:".bold(), config.overdue_warn_threshold);
+    println!(
+        "{} green from {}%, red below {}%",
+        "Completion colors:".bold(), config.completion_good_threshold, config.completion_warn_threshold
+    );
+    match config.confirm_complete_priority {
+        Some(priority) => println!("{} {:?} and above", "Confirm completing:".bold(), priority),
+        None => println!("{} {}", "Confirm completing:".bold(), "never".dimmed()),
+    }
 
-    println!("{}", format!("📥 Imported {} tasks from {}", imported_count, file.display()).green());
+    println!();
+    let overrides: Vec<(&str, String)> = RELEVANT_ENV_VARS.iter()
+        .filter_map(|name| std::env::var(name).ok().map(|value| (*name, value)))
+        .collect();
+    if overrides.is_empty() {
+        println!("{} {}", "Environment:".bold(), "no overrides set".dimmed());
+    } else {
+        println!("{}", "Environment:".bold());
+        for (name, value) in overrides {
+            let value = if name == crypto::KEY_ENV_VAR { "<redacted>".dimmed().to_string() } else { value };
+            println!("  {}={}", name, value);
+        }
+    }
     Ok(())
 }
 
-/// Export all tasks currently in memory to a JSON file
-async fn handle_export(manager: &TaskManager, file: PathBuf) -> Result<()> {
-    let tasks: Vec<&crate::task::Task> = manager.get_all_tasks().collect();
-    let data = serde_json::to_string_pretty(&tasks)?;
+/// The default value clap uses for a subcommand's argument, read from the CLI definition
+/// (including any default from the config file)
+fn subcommand_default(command: &clap::Command, subcommand: &str, arg: &str) -> String {
+    command
+        .find_subcommand(subcommand)
+        .and_then(|cmd| cmd.get_arguments().find(|a| a.get_id() == arg).cloned())
+        .and_then(|a| a.get_default_values().first().map(|v| v.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "none".to_string())
+}
+
+/// Export all tasks currently in memory to a JSON, YAML, CSV, Markdown or iCalendar file,
+/// chosen by `--as` or else by the file extension
+#[allow(clippy::too_many_arguments)]
+async fn handle_export(
+    manager: &TaskManager,
+    file: PathBuf,
+    format: Option<ExportFormat>,
+    split_by: Option<cli::SplitByArg>,
+    anonymize: bool,
+    modified_since: Option<String>,
+    on_conflict: ExportConflict,
+    with_checksum: bool,
+    columns: Vec<spreadsheet::Column>,
+) -> Result<()> {
+    // Split files follow the data file's format unless one is chosen
+    let format = match split_by {
+        Some(_) => format.unwrap_or(manager.config.storage_format.into()),
+        None => format.or_else(|| ExportFormat::from_path(&file)).unwrap_or(ExportFormat::Json),
+    };
+    if !columns.is_empty() && format != ExportFormat::Csv {
+        return Err(TaskError::ValidationError("--columns applies only to CSV exports".to_string()));
+    }
+    let to_stdout = file.as_os_str() == STREAM_SENTINEL;
+    if to_stdout && (split_by.is_some() || with_checksum) {
+        return Err(TaskError::ValidationError(
+            "--split-by and --with-checksum need a file or directory, not stdout".to_string()
+        ));
+    }
+
+    let tasks: Vec<&crate::task::Task> = match modified_since {
+        Some(since) => {
+            let since = crate::task::parse_datetime_in(&since, manager.config.input_timezone)?;
+            manager.get_tasks_modified_since(since).collect()
+        }
+        None => manager.stored_tasks(),
+    };
+
+    if let Some(split_by) = split_by {
+        // Split after anonymizing so file names never reveal category names
+        let tasks = if anonymize { anonymize_tasks(&tasks) } else { tasks.into_iter().cloned().collect() };
+        tokio::fs::create_dir_all(&file).await?;
+        let groups = split_tasks(tasks, split_by);
+        for (name, group) in &groups {
+            let path = export_target(file.join(format!("{}.{}", name, format.extension())), on_conflict)?;
+            let data = render_export(manager, format, &group.iter().collect::<Vec<_>>(), &columns)?;
+            tokio::fs::write(&path, &data).await?;
+            if with_checksum {
+                crate::checksum::write_sidecar(&path, data.as_bytes()).await?;
+            }
+            println!("  {} {}", path.display(), format!("({} tasks)", group.len()).dimmed());
+        }
+        println!("{}", format!("📤 Exported {} files to {}", groups.len(), file.display()).green());
+        return Ok(());
+    }
+
+    let data = match anonymize {
+        true => render_export(manager, format, &anonymize_tasks(&tasks).iter().collect::<Vec<_>>(), &columns)?,
+        false => render_export(manager, format, &tasks, &columns)?,
+    };
+
+    // Keep stdout to the exported data alone so it can be piped
+    if to_stdout {
+        use std::io::Write;
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(data.as_bytes())?;
+        if !data.ends_with('\n') {
+            writeln!(stdout)?;
+        }
+        stdout.flush()?;
+        eprintln!("{}", format!("📤 Exported {} tasks to stdout", tasks.len()).green());
+        return Ok(());
+    }
 
     if let Some(parent) = file.parent() {
         tokio::fs::create_dir_all(parent).await?;
     }
 
-    tokio::fs::write(&file, data).await?;
-    println!("{}", format!("📤 Exported {} tasks to {}", tasks.len(), file.display()).green());
+    let file = export_target(file, on_conflict)?;
+    tokio::fs::write(&file, &data).await?;
+    if format == ExportFormat::Ics {
+        let undated = tasks.iter().filter(|t| t.due_date.is_none()).count();
+        println!("{}", format!("📤 Exported {} tasks to {}", tasks.len() - undated, file.display()).green());
+        if undated > 0 {
+            println!("{}", format!("Skipped {} tasks without a due date", undated).dimmed());
+        }
+    } else {
+        println!("{}", format!("📤 Exported {} tasks to {}", tasks.len(), file.display()).green());
+    }
+    if with_checksum {
+        let sidecar = crate::checksum::write_sidecar(&file, data.as_bytes()).await?;
+        println!("{}", format!("Checksum written to {}", sidecar.display()).dimmed());
+    }
+    Ok(())
+}
+
+/// File format written by `export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Json,
+    Yaml,
+    Csv,
+    Markdown,
+    Ics,
+}
+
+impl ExportFormat {
+    /// Guess the format from a file extension (`.json`, `.yaml`/`.yml`, `.csv`, `.md`, `.ics`)
+    fn from_path(path: &std::path::Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            "ics" => Some(ExportFormat::Ics),
+            _ => StorageFormat::from_path(path).map(Into::into),
+        }
+    }
+
+    /// The file extension written for this format
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Yaml => "yaml",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Ics => "ics",
+        }
+    }
+}
+
+impl From<StorageFormat> for ExportFormat {
+    fn from(format: StorageFormat) -> Self {
+        match format {
+            StorageFormat::Json => ExportFormat::Json,
+            StorageFormat::Yaml => ExportFormat::Yaml,
+        }
+    }
+}
+
+impl From<cli::ExportFormatArg> for ExportFormat {
+    fn from(arg: cli::ExportFormatArg) -> Self {
+        match arg {
+            cli::ExportFormatArg::Json => ExportFormat::Json,
+            cli::ExportFormatArg::Yaml => ExportFormat::Yaml,
+            cli::ExportFormatArg::Csv => ExportFormat::Csv,
+            cli::ExportFormatArg::Markdown => ExportFormat::Markdown,
+            cli::ExportFormatArg::Ics => ExportFormat::Ics,
+        }
+    }
+}
+
+/// Serialize tasks in an export format
+fn render_export(
+    manager: &TaskManager,
+    format: ExportFormat,
+    tasks: &[&crate::task::Task],
+    columns: &[spreadsheet::Column],
+) -> Result<String> {
+    match format {
+        ExportFormat::Json => StorageFormat::Json.render(tasks),
+        ExportFormat::Yaml => StorageFormat::Yaml.render(tasks),
+        ExportFormat::Csv => spreadsheet::write_csv(tasks.iter().copied(), csv_columns(columns)),
+        ExportFormat::Markdown => Ok(render_markdown(tasks, manager.now(), manager.config.display_timezone)),
+        ExportFormat::Ics => Ok(render_ics(tasks, manager.now())),
+    }
+}
+
+/// Render tasks with a due date as an iCalendar file of VTODO entries, so due dates
+/// show up in calendar apps.
+///
+/// Tasks without a due date are left out. Lines end in CRLF and are folded at 75
+/// bytes as RFC 5545 requires; `now` is the DTSTAMP of every entry.
+fn render_ics(tasks: &[&crate::task::Task], now: chrono::DateTime<chrono::Utc>) -> String {
+    use crate::task::{Priority, TaskStatus};
+
+    let stamp = |ts: chrono::DateTime<chrono::Utc>| ts.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//task-manager//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    let mut due: Vec<&&crate::task::Task> = tasks.iter().filter(|t| t.due_date.is_some()).collect();
+    due.sort_by_key(|t| (t.due_date, t.id));
+    for task in due {
+        lines.push("BEGIN:VTODO".to_string());
+        lines.push(format!("UID:{}", task.id));
+        lines.push(format!("DTSTAMP:{}", stamp(now)));
+        lines.push(format!("CREATED:{}", stamp(task.created_at)));
+        lines.push(format!("LAST-MODIFIED:{}", stamp(task.updated_at)));
+        lines.push(format!("SUMMARY:{}", escape_ics_text(&task.title)));
+        if let Some(description) = &task.description {
+            lines.push(format!("DESCRIPTION:{}", escape_ics_text(description)));
+        }
+        if let Some(category) = &task.category {
+            lines.push(format!("CATEGORIES:{}", escape_ics_text(category)));
+        }
+        if let Some(due_date) = task.due_date {
+            lines.push(format!("DUE:{}", stamp(due_date)));
+        }
+        let status = match task.status {
+            TaskStatus::Todo | TaskStatus::Waiting => "NEEDS-ACTION",
+            TaskStatus::InProgress => "IN-PROCESS",
+            TaskStatus::Done => "COMPLETED",
+            TaskStatus::Cancelled => "CANCELLED",
+        };
+        lines.push(format!("STATUS:{}", status));
+        if let Some(completed_at) = task.completed_at {
+            lines.push(format!("COMPLETED:{}", stamp(completed_at)));
+        }
+        // iCalendar ranks 1 (highest) to 9 (lowest); 0 means undefined
+        let priority = match task.priority {
+            Priority::Critical => 1,
+            Priority::High => 3,
+            Priority::Medium => 5,
+            Priority::Low => 7,
+            Priority::None => 0,
+        };
+        lines.push(format!("PRIORITY:{}", priority));
+        lines.push("END:VTODO".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_ics_line(line)).collect()
+}
+
+/// Escape a TEXT value: backslashes, commas, semicolons and newlines
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Terminate a content line with CRLF, folding it onto continuation lines (which
+/// start with a space) so no line exceeds 75 bytes. Never splits a UTF-8 character.
+fn fold_ics_line(line: &str) -> String {
+    const MAX_BYTES: usize = 75;
+
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_BYTES {
+            folded.push_str("\r\n ");
+            // The leading space counts toward the continuation line's length
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Render tasks as a Markdown checklist with one section per status, for pasting
+/// into READMEs and wikis.
+///
+/// Done tasks are checked and cancelled ones struck through. Each item shows its
+/// priority and a compact due date (`Mar 5`, with the year only when it isn't the
+/// current one), and overdue tasks are flagged.
+fn render_markdown(tasks: &[&crate::task::Task], now: chrono::DateTime<chrono::Utc>, tz: chrono_tz::Tz) -> String {
+    use crate::task::TaskStatus;
+    use chrono::Datelike;
+    use std::fmt::Write as _;
+
+    let mut out = String::from("# Tasks\n");
+    let sections = [
+        (TaskStatus::InProgress, "In progress"),
+        (TaskStatus::Todo, "To do"),
+        (TaskStatus::Waiting, "Waiting"),
+        (TaskStatus::Done, "Done"),
+        (TaskStatus::Cancelled, "Cancelled"),
+    ];
+    for (status, heading) in sections {
+        let mut group: Vec<&&crate::task::Task> = tasks.iter().filter(|t| t.status == status).collect();
+        if group.is_empty() {
+            continue;
+        }
+        group.sort_by_key(|t| (std::cmp::Reverse(t.priority), t.due_date.is_none(), t.due_date, t.created_at));
+
+        let _ = write!(out, "\n## {} ({})\n\n", heading, group.len());
+        for task in group {
+            let checkbox = if status == TaskStatus::Done { "[x]" } else { "[ ]" };
+            let title = escape_markdown(&task.title);
+            let title = if status == TaskStatus::Cancelled { format!("~~{}~~", title) } else { title };
+            let mut details = vec![format!("{:?}", task.priority).to_lowercase()];
+            if let Some(due) = task.due_date {
+                let local = due.with_timezone(&tz);
+                let date = if local.year() == now.with_timezone(&tz).year() {
+                    local.format("%b %-d").to_string()
+                } else {
+                    local.format("%b %-d, %Y").to_string()
+                };
+                details.push(format!("due {}", date));
+            }
+            if task.is_overdue_at(now) {
+                details.push("**⚠️ overdue**".to_string());
+            }
+            let _ = writeln!(out, "- {} {} · {}", checkbox, title, details.join(" · "));
+        }
+    }
+    if tasks.is_empty() {
+        out.push_str("\nNo tasks.\n");
+    }
+    out
+}
+
+/// Backslash-escape characters Markdown would treat as formatting
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '~' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The columns chosen with `--columns`, or the defaults when none were given
+fn csv_columns(columns: &[spreadsheet::Column]) -> &[spreadsheet::Column] {
+    if columns.is_empty() { spreadsheet::DEFAULT_COLUMNS } else { columns }
+}
+
+/// What `export` does when the target file already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportConflict {
+    /// Fail without writing anything
+    Refuse,
+    /// Replace the existing file
+    Overwrite,
+    /// Write to the first free numbered variant (`tasks-1.json`, `tasks-2.json`, ...)
+    Rename,
+}
+
+/// Pick the path an export should be written to, given the conflict policy
+fn export_target(path: PathBuf, on_conflict: ExportConflict) -> Result<PathBuf> {
+    if !path.exists() {
+        return Ok(path);
+    }
+
+    match on_conflict {
+        ExportConflict::Overwrite => Ok(path),
+        ExportConflict::Refuse => Err(TaskError::OperationNotAllowed(format!(
+            "{} already exists; pass --overwrite to replace it or --rename-on-conflict to write a numbered copy",
+            path.display()
+        ))),
+        ExportConflict::Rename => {
+            let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+            let candidate = (1..)
+                .map(|n| path.with_file_name(format!("{}-{}{}", stem, n, extension)))
+                .find(|candidate| !candidate.exists())
+                .expect("some numbered file name is free");
+            Ok(candidate)
+        }
+    }
+}
+
+/// Group tasks by category or status, keyed by a file-name-safe stem.
+///
+/// Categories are grouped case-insensitively; tasks without one go under "uncategorized".
+fn split_tasks(tasks: Vec<crate::task::Task>, split_by: cli::SplitByArg) -> std::collections::BTreeMap<String, Vec<crate::task::Task>> {
+    let mut groups: std::collections::BTreeMap<String, Vec<crate::task::Task>> = std::collections::BTreeMap::new();
+    for task in tasks {
+        let key = match split_by {
+            cli::SplitByArg::Category => task.category.as_deref()
+                .map(|c| c.trim().to_lowercase())
+                .filter(|c| !c.is_empty())
+                .unwrap_or_else(|| "uncategorized".to_string()),
+            cli::SplitByArg::Status => task.status.label().replace(' ', "-"),
+        };
+        let stem: String = key.chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        groups.entry(stem).or_default().push(task);
+    }
+    for group in groups.values_mut() {
+        group.sort_by_key(|t| (t.created_at, t.id));
+    }
+    groups
+}
+
+/// List tags with their usage counts, most used first or alphabetically
+async fn handle_tags(manager: &TaskManager, alpha: bool) -> Result<()> {
+    let mut counts = manager.tag_counts();
+    if counts.is_empty() {
+        println!("{}", "No tags in use. Add one with `add --tag <TAG>`.".yellow());
+        return Ok(());
+    }
+    if alpha {
+        counts.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    let width = counts.iter().map(|(tag, _)| tag.chars().count()).max().unwrap_or(0);
+    println!("{}", format!("🏷 Tags ({}):", counts.len()).cyan().bold());
+    for (tag, count) in counts {
+        println!("  {:<width$}  {}", tag, count, width = width);
+    }
+    Ok(())
+}
+
+/// Write the dependency graph as DOT to a file or stdout
+async fn handle_graph(manager: &TaskManager, output: Option<PathBuf>) -> Result<()> {
+    let dot = render_dot(manager);
+    match output {
+        Some(file) => {
+            tokio::fs::write(&file, dot).await?;
+            println!("{}", format!("🕸 Wrote task graph to {}", file.display()).green());
+        }
+        None => print!("{}", dot),
+    }
+    Ok(())
+}
+
+/// Render tasks as a Graphviz digraph: one node per task colored by status, and an
+/// edge from each dependency to the task that waits on it. Edges on a dependency
+/// cycle are drawn in red.
+fn render_dot(manager: &TaskManager) -> String {
+    use std::fmt::Write as _;
+
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut tasks: Vec<&crate::task::Task> = manager.get_all_tasks().collect();
+    tasks.sort_by_key(|t| (t.created_at, t.id));
+    let cycles = manager.dependency_cycle_edges();
+
+    let mut dot = String::from("digraph tasks {\n    rankdir=LR;\n    node [shape=box, style=\"rounded,filled\"];\n");
+    if !cycles.is_empty() {
+        let _ = writeln!(dot, "    // {} dependency edge(s) form cycles", cycles.len());
+    }
+
+    // Archived tasks appear only where an active task still points at them
+    let mut archived: Vec<&crate::task::Task> = tasks.iter()
+        .flat_map(|task| task.depends_on.iter().copied().chain(task.parent_id))
+        .filter_map(|id| manager.stored_task(&id.to_string()))
+        .filter(|related| related.archived_at.is_some())
+        .collect();
+    archived.sort_by_key(|t| (t.created_at, t.id));
+    archived.dedup_by_key(|t| t.id);
+
+    for task in tasks.iter().chain(&archived) {
+        let fill = match task.status {
+            crate::task::TaskStatus::Todo => "white",
+            crate::task::TaskStatus::InProgress => "lightblue",
+            crate::task::TaskStatus::Waiting => "khaki",
+            crate::task::TaskStatus::Done => "palegreen",
+            crate::task::TaskStatus::Cancelled => "lightgray",
+        };
+        let short_id = task.id.to_string().get(..UUID_DISPLAY_LENGTH).unwrap_or_default().to_string();
+        let (note, style) = if task.archived_at.is_some() { (" (archived)", ", style=\"rounded,filled,dashed\"") } else { ("", "") };
+        let _ = writeln!(
+            dot,
+            "    \"{}\" [label=\"{}{}\\n{}\", fillcolor={}{}];",
+            task.id, short_id, note, escape(&task.title), fill, style
+        );
+    }
+
+    // Edges to tasks that no longer exist would create undeclared nodes, so they are left out
+    let declared = |id: &uuid::Uuid| manager.stored_task(&id.to_string()).is_some();
+    for task in &tasks {
+        for dep in task.depends_on.iter().filter(|dep| declared(dep)) {
+            let attrs = if cycles.contains(&(task.id, *dep)) { " [color=red, label=\"cycle\"]" } else { "" };
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\"{};", dep, task.id, attrs);
+        }
+        if let Some(parent) = task.parent_id.filter(declared) {
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\" [style=dashed, arrowhead=none];", parent, task.id);
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Copy tasks with their free-text content replaced by placeholders.
+///
+/// Titles become "Task N" in creation order, descriptions keep only a rough length
+/// class, note text and the change history are dropped, and owners, categories, tags
+/// and links are renamed consistently so grouping survives. Status, priority, dates and
+/// relationships are left intact.
+fn anonymize_tasks(tasks: &[&crate::task::Task]) -> Vec<crate::task::Task> {
+    let mut ordered = tasks.to_vec();
+    ordered.sort_by_key(|t| (t.created_at, t.id));
+
+    let mut owners: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    let mut categories: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    let mut tags: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    let mut links: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+
+    ordered.into_iter().enumerate().map(|(i, task)| {
+        let mut task = task.clone();
+        task.title = format!("Task {}", i + 1);
+        task.description = task.description.map(|d| {
+            let class = match d.chars().count() {
+                0..=80 => "short",
+                81..=500 => "medium",
+                _ => "long",
+            };
+            format!("[{} description]", class)
+        });
+        task.owner = task.owner.map(|o| {
+            let next = owners.len() + 1;
+            owners.entry(o).or_insert_with(|| format!("owner-{}", next)).clone()
+        });
+        task.category = task.category.map(|c| {
+            let next = categories.len() + 1;
+            categories.entry(c).or_insert_with(|| format!("category-{}", next)).clone()
+        });
+        for note in &mut task.notes {
+            note.text = "[note]".to_string();
+        }
+        // Change events quote old and new field values verbatim
+        task.history.clear();
+        task.tags = task.tags.into_iter().map(|t| {
+            let next = tags.len() + 1;
+            tags.entry(t).or_insert_with(|| format!("tag-{}", next)).clone()
+        }).collect();
+        task.links = task.links.into_iter().map(|l| {
+            let next = links.len() + 1;
+            links.entry(l).or_insert_with(|| format!("https://example.com/link-{}", next)).clone()
+        }).collect();
+        task
+    }).collect()
+}
+
+/// Set, show or clear the focused task
+async fn handle_focus(manager: &TaskManager, id: Option<String>, action: Option<cli::FocusAction>) -> Result<()> {
+    match (id, action) {
+        (Some(id), _) => {
+            let id = resolve_task_ref(manager, &id).await?;
+            let task = manager.get_task(&id)?;
+            tokio::fs::write(manager.config.focus_path(), task.id.to_string()).await?;
+            println!("{}", format!("🎯 Focusing on: {}", task.title).green());
+        }
+        (None, Some(cli::FocusAction::Clear)) => match tokio::fs::remove_file(manager.config.focus_path()).await {
+            Ok(()) => println!("{}", "Focus cleared".green()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => println!("{}", "No task is focused.".yellow()),
+            Err(e) => return Err(e.into()),
+        },
+        (None, Some(cli::FocusAction::Show) | None) => {
+            let id = focused_task(manager).await?.ok_or_else(no_focus_error)?;
+            if manager.get_task(&id).is_err() {
+                return Err(TaskError::TaskNotFound(format!("{} (the focused task was deleted; run `focus clear`)", id)));
+            }
+            handle_show(manager, &id, None, false, false).await?;
+        }
+    }
     Ok(())
 }
 
+/// ID of the focused task, if one is set
+async fn focused_task(manager: &TaskManager) -> Result<Option<String>> {
+    match tokio::fs::read_to_string(manager.config.focus_path()).await {
+        Ok(data) => Ok(Some(data.trim().to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn no_focus_error() -> TaskError {
+    TaskError::ValidationError("No task is focused; run `focus <ID>` first".to_string())
+}
+
+/// Resolve a task reference into a full task ID.
+///
+/// Accepts a `#N` row reference from the last `list --numbered`, a full UUID, or
+/// any unambiguous prefix of one, such as the short ID `list` prints.
+async fn resolve_task_ref(manager: &TaskManager, id: &str) -> Result<String> {
+    let Some(row) = id.strip_prefix('#') else {
+        return Ok(manager.get_task_by_prefix(id)?.id.to_string());
+    };
+
+    let row: usize = row.parse().map_err(|_| TaskError::ValidationError(
+        format!("Invalid row reference '{}': expected #N", id)
+    ))?;
+    let data = tokio::fs::read_to_string(manager.config.last_list_path()).await.map_err(|_| {
+        TaskError::ValidationError("No numbered list to refer to; run `list --numbered` first".to_string())
+    })?;
+    let ids: Vec<String> = serde_json::from_str(&data)?;
+
+    row.checked_sub(1)
+        .and_then(|index| ids.get(index))
+        .cloned()
+        .ok_or_else(|| TaskError::ValidationError(
+            format!("Row {} is out of range for the last numbered list (1-{})", id, ids.len())
+        ))
+}
+
 /// Interactively select a task from a numbered list of all available tasks
 async fn select_task_interactive(manager: &TaskManager) -> Result<String> {
     let tasks = manager.get_sorted_tasks(crate::manager::TaskSort::CreatedDesc);
@@ -439,16 +2755,11 @@ async fn select_task_interactive(manager: &TaskManager) -> Result<String> {
 
     for (i, task) in tasks.iter().enumerate() {
         print!("{}: ", format!("{:2}", i + 1).bold());
-        print_task_summary(task);
+        print_task_summary(task, CATEGORY_MAX_DISPLAY, manager.config.display_timezone, TimeDisplay::new(manager, false), manager.now());
     }
 
     println!("{}", "─".repeat(80).dimmed());
-    print!("Enter task number (1-{}) or 'q' to cancel: ", tasks.len());
-
-    io::stdout().flush()?;
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let input = sanitize_input(&input)?;
+    let input = prompt_input(&format!("Enter task number (1-{}) or 'q' to cancel: ", tasks.len()))?;
 
     if input.eq_ignore_ascii_case("q") || input.eq_ignore_ascii_case("quit") {
         println!("{}", "Selection cancelled.".yellow());
@@ -467,16 +2778,137 @@ async fn select_task_interactive(manager: &TaskManager) -> Result<String> {
     }
 }
 
+/// Render a URL as a clickable OSC 8 terminal hyperlink when color output is enabled
+fn format_link(url: &str) -> String {
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+        format!("\x1b]8;;{url}\x1b\\{}\x1b]8;;\x1b\\", url.underline())
+    } else {
+        url.to_string()
+    }
+}
+
+/// Print a prompt and read one sanitized line of input from stdin.
+///
+/// Piped input is read like typed input. If stdin is closed before a line arrives
+/// the prompt fails instead of treating the missing answer as an empty one.
+fn prompt_input(prompt: &str) -> Result<String> {
+    use std::io::IsTerminal;
+
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input)? == 0 {
+        println!();
+        return Err(TaskError::OperationNotAllowed("No input available: stdin is closed".to_string()));
+    }
+    // Piped answers are not echoed, so end the prompt line ourselves
+    if !io::stdin().is_terminal() {
+        println!();
+    }
+    sanitize_input(&input)
+}
+
+/// Read the answer to a confirmation prompt, pointing at --force when stdin is closed
+fn confirmation_answer(prompt: &str) -> Result<String> {
+    prompt_input(prompt).map_err(|e| match e {
+        TaskError::OperationNotAllowed(_) => TaskError::OperationNotAllowed(
+            "Confirmation needed but stdin is closed; pass --force to skip the prompt or pipe in an answer (e.g. `yes |`)".to_string()
+        ),
+        other => other,
+    })
+}
+
+/// Ask a yes/no question; only "y" or "yes" (any case) counts as confirmation
+fn confirm(prompt: &str) -> Result<bool> {
+    let input = confirmation_answer(prompt)?;
+    Ok(input.eq_ignore_ascii_case("y") || input.eq_ignore_ascii_case("yes"))
+}
+
+/// Print a task in about 40 columns: priority emoji, short ID and a truncated title
+fn print_task_narrow(task: &crate::task::Task) {
+    let priority_color = match task.priority {
+        crate::task::Priority::None => "⚪",
+        crate::task::Priority::Low => "🟢",
+        crate::task::Priority::Medium => "🟡",
+        crate::task::Priority::High => "🟠",
+        crate::task::Priority::Critical => "🔴",
+    };
+
+    let id = task.id.to_string();
+    let short_id = id.get(..UUID_DISPLAY_LENGTH).unwrap_or(&id);
+    println!("{} {} {}", priority_color, short_id.dimmed(), truncate_display(&task.title, NARROW_TITLE_MAX_DISPLAY));
+}
+
+/// Shorten text to at most `max` terminal columns, ending in an ellipsis when cut.
+///
+/// Wide characters such as CJK and most emoji count as two columns, and text is
+/// only cut between grapheme clusters, so accents and emoji sequences stay whole.
+fn truncate_display(text: &str, max: usize) -> String {
+    use unicode_segmentation::UnicodeSegmentation;
+    use unicode_width::UnicodeWidthStr;
+
+    if text.width() <= max {
+        return text.to_string();
+    }
+    let budget = max.saturating_sub(1);
+    let mut width = 0;
+    let mut kept = String::new();
+    for grapheme in text.graphemes(true) {
+        width += grapheme.width();
+        if width > budget {
+            break;
+        }
+        kept.push_str(grapheme);
+    }
+    format!("{}…", kept)
+}
+
+/// How `list` and `show` render timestamps
+#[derive(Debug, Clone, Copy)]
+enum TimeDisplay {
+    /// Relative to the given instant, e.g. "3h ago" or "in 2 days"
+    Relative(chrono::DateTime<chrono::Utc>),
+    /// Exact times in the display timezone
+    Absolute,
+}
+
+impl TimeDisplay {
+    fn new(manager: &TaskManager, absolute: bool) -> Self {
+        if absolute { TimeDisplay::Absolute } else { TimeDisplay::Relative(manager.now()) }
+    }
+
+    fn format(self, timestamp: chrono::DateTime<chrono::Utc>, tz: chrono_tz::Tz) -> String {
+        match self {
+            TimeDisplay::Relative(now) => crate::task::humanize_relative(timestamp, now),
+            TimeDisplay::Absolute => format_timestamp(timestamp, tz),
+        }
+    }
+}
+
 /// Print a summary of a task
-fn print_task_summary(task: &crate::task::Task) {
+///
+/// Titles wider than 40 columns and categories wider than `category_width` are
+/// shortened with an ellipsis.
+/// With relative times the due date reads like "in 2 days" and the row ends with the
+/// task's age; absolute times show the due date as month/day. The due date is
+/// highlighted when the task is overdue at `now`.
+fn print_task_summary(
+    task: &crate::task::Task,
+    category_width: usize,
+    tz: chrono_tz::Tz,
+    times: TimeDisplay,
+    now: chrono::DateTime<chrono::Utc>,
+) {
     let status_icon = match task.status {
         crate::task::TaskStatus::Todo => "📋",
         crate::task::TaskStatus::InProgress => "🔄",
+        crate::task::TaskStatus::Waiting => "⏳",
         crate::task::TaskStatus::Done => "✅",
         crate::task::TaskStatus::Cancelled => "❌",
     };
 
     let priority_color = match task.priority {
+        crate::task::Priority::None => "⚪",
         crate::task::Priority::Low => "🟢",
         crate::task::Priority::Medium => "🟡",
         crate::task::Priority::High => "🟠",
@@ -484,26 +2916,299 @@ fn print_task_summary(task: &crate::task::Task) {
     };
 
     let id = format!("{}...", task.id.to_string().get(..UUID_DISPLAY_LENGTH).unwrap_or(&task.id.to_string()));
-    let title = if task.title.len() > TITLE_MAX_DISPLAY {
-        format!("{}...", task.title.get(..TITLE_MAX_DISPLAY - 3).unwrap_or(&task.title))
-    } else {
-        task.title.clone()
-    };
+    let title = truncate_display(&task.title, TITLE_MAX_DISPLAY);
 
     print!("{} {} {} {}", status_icon, priority_color, id.dimmed(), title);
 
     if let Some(ref category) = task.category {
-        print!(" {}", format!("[{}]", category).dimmed());
+        print!(" {}", format!("[{}]", truncate_display(category, category_width)).dimmed());
     }
 
     if let Some(due_date) = task.due_date {
-        let due_str = due_date.format("%m/%d").to_string();
-        if task.is_overdue() {
+        let due_str = match times {
+            TimeDisplay::Relative(now) => crate::task::humanize_relative(due_date, now),
+            TimeDisplay::Absolute => due_date.with_timezone(&tz).format("%m/%d").to_string(),
+        };
+        if task.is_overdue_at(now) {
             print!(" {}", format!("📅{}", due_str).red());
         } else {
             print!(" {}", format!("📅{}", due_str).dimmed());
         }
     }
 
+    if let TimeDisplay::Relative(now) = times {
+        print!(" {}", format!("· {}", crate::task::humanize_relative(task.created_at, now)).dimmed());
+    }
+
     println!();
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_strict_schema() {
+        let tasks = vec![crate::task::Task::new("Known fields only".to_string())];
+        let data = serde_json::to_vec(&tasks).unwrap();
+        assert!(check_strict_schema(data.as_slice(), StorageFormat::Json).is_ok());
+
+        let mut raw: Vec<serde_json::Value> = serde_json::from_slice(&data).unwrap();
+        raw[0]["colour"] = serde_json::json!("red");
+        let err = check_strict_schema(serde_json::to_vec(&raw).unwrap().as_slice(), StorageFormat::Json).unwrap_err();
+        assert!(err.to_string().contains("`colour`"));
+
+        let yaml = serde_yaml::to_string(&raw).unwrap();
+        let err = check_strict_schema(yaml.as_bytes(), StorageFormat::Yaml).unwrap_err();
+        assert!(err.to_string().contains("`colour`"));
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        use crate::task::{Priority, Task, TaskStatus};
+        use chrono::TimeZone;
+
+        let now = chrono::Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        let mut late = Task::new("Fix *prod* bug".to_string());
+        late.priority = Priority::High;
+        late.due_date = Some(chrono::Utc.with_ymd_and_hms(2024, 3, 5, 17, 0, 0).unwrap());
+        let mut shipped = Task::new("Ship v1".to_string());
+        shipped.status = TaskStatus::Done;
+        shipped.due_date = Some(chrono::Utc.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap());
+        let mut dropped = Task::new("Old idea".to_string());
+        dropped.status = TaskStatus::Cancelled;
+
+        let md = render_markdown(&[&shipped, &late, &dropped], now, chrono_tz::Tz::UTC);
+        assert_eq!(md, "# Tasks\n\
+            \n## To do (1)\n\n- [ ] Fix \\*prod\\* bug · high · due Mar 5 · **⚠️ overdue**\n\
+            \n## Done (1)\n\n- [x] Ship v1 · medium · due Dec 1, 2023\n\
+            \n## Cancelled (1)\n\n- [ ] ~~Old idea~~ · medium\n");
+
+        assert_eq!(render_markdown(&[], now, chrono_tz::Tz::UTC), "# Tasks\n\nNo tasks.\n");
+    }
+
+    #[test]
+    fn test_render_ics() {
+        use crate::task::{Task, TaskStatus};
+        use chrono::TimeZone;
+
+        let now = chrono::Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        let mut task = Task::new(format!("Call Ann, Bob; {}", "é".repeat(40)));
+        task.description = Some("Line one\nline two".to_string());
+        task.status = TaskStatus::InProgress;
+        task.due_date = Some(chrono::Utc.with_ymd_and_hms(2024, 3, 5, 17, 0, 0).unwrap());
+        let undated = Task::new("No due date".to_string());
+
+        let ics = render_ics(&[&task, &undated], now);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VTODO").count(), 1);
+        assert!(ics.contains(&format!("UID:{}\r\n", task.id)));
+        assert!(ics.contains("DTSTAMP:20240310T120000Z\r\n"));
+        assert!(ics.contains("DUE:20240305T170000Z\r\nSTATUS:IN-PROCESS\r\n"));
+        assert!(ics.contains("DESCRIPTION:Line one\\nline two\r\n"));
+
+        // Every physical line fits in 75 bytes, and unfolding restores the summary
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("SUMMARY:Call Ann\\, Bob\\; {}\r\n", "é".repeat(40))));
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let mut manager = TaskManager::new();
+        let blocker = manager.add_task("Say \"hi\"".to_string()).unwrap();
+        let dependent = manager.add_task("Follow up".to_string()).unwrap();
+        let blocker_id = manager.get_task(&blocker).unwrap().id;
+        manager.get_task_mut(&dependent).unwrap().depends_on.push(blocker_id);
+
+        let dot = render_dot(&manager);
+        assert!(dot.starts_with("digraph tasks {"));
+        assert!(dot.contains("Say \\\"hi\\\""));
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", blocker, dependent)));
+        assert!(!dot.contains("cycle"));
+
+        let dependent_id = manager.get_task(&dependent).unwrap().id;
+        manager.get_task_mut(&blocker).unwrap().depends_on.push(dependent_id);
+        assert!(render_dot(&manager).contains("[color=red, label=\"cycle\"]"));
+    }
+
+    #[test]
+    fn test_render_dot_declares_archived_dependencies() {
+        let mut manager = TaskManager::new();
+        let blocker = manager.add_task("Groundwork".to_string()).unwrap();
+        let dependent = manager.add_task("Build".to_string()).unwrap();
+        let blocker_id = manager.get_task(&blocker).unwrap().id;
+        manager.get_task_mut(&dependent).unwrap().depends_on.push(blocker_id);
+        manager.complete_task(&blocker).unwrap();
+        assert_eq!(manager.archive_completed(), 1);
+
+        let dot = render_dot(&manager);
+        assert!(dot.contains(&format!("\"{}\" [label=\"{} (archived)", blocker, &blocker[..8])));
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", blocker, dependent)));
+        assert!(manager.unfinished_dependencies(&dependent).unwrap().is_empty());
+
+        // A dependency that is gone entirely gets no edge to an undeclared node
+        manager.get_task_mut(&dependent).unwrap().depends_on.push(uuid::Uuid::new_v4());
+        assert_eq!(render_dot(&manager).matches(" -> ").count(), 1);
+    }
+
+    #[test]
+    fn test_split_tasks_by_category() {
+        let mut work = crate::task::Task::new("Ship".to_string());
+        work.category = Some("Work".to_string());
+        let mut work_lower = crate::task::Task::new("Review".to_string());
+        work_lower.category = Some("work".to_string());
+        let mut odd = crate::task::Task::new("Odd".to_string());
+        odd.category = Some("a/b".to_string());
+        let loose = crate::task::Task::new("Loose".to_string());
+
+        let groups = split_tasks(vec![work, work_lower, odd, loose], cli::SplitByArg::Category);
+        let names: Vec<&str> = groups.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["a_b", "uncategorized", "work"]);
+        assert_eq!(groups["work"].len(), 2);
+
+        let by_status = split_tasks(vec![crate::task::Task::new("New".to_string())], cli::SplitByArg::Status);
+        assert!(by_status.contains_key("todo"));
+    }
+
+    #[test]
+    fn test_export_target_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+        assert_eq!(export_target(path.clone(), ExportConflict::Refuse).unwrap(), path);
+
+        std::fs::write(&path, "[]").unwrap();
+        assert!(matches!(export_target(path.clone(), ExportConflict::Refuse), Err(TaskError::OperationNotAllowed(_))));
+        assert_eq!(export_target(path.clone(), ExportConflict::Overwrite).unwrap(), path);
+        assert_eq!(export_target(path.clone(), ExportConflict::Rename).unwrap(), dir.path().join("tasks-1.json"));
+
+        std::fs::write(dir.path().join("tasks-1.json"), "[]").unwrap();
+        assert_eq!(export_target(path, ExportConflict::Rename).unwrap(), dir.path().join("tasks-2.json"));
+    }
+
+    #[test]
+    fn test_nest_subtasks() {
+        let parent = crate::task::Task::new("Parent".to_string());
+        let mut child = crate::task::Task::new("Child".to_string());
+        child.parent_id = Some(parent.id);
+        let mut grandchild = crate::task::Task::new("Grandchild".to_string());
+        grandchild.parent_id = Some(child.id);
+        let mut orphan = crate::task::Task::new("Orphan".to_string());
+        orphan.parent_id = Some(uuid::Uuid::new_v4());
+        let other = crate::task::Task::new("Other".to_string());
+
+        let rows = nest_subtasks(vec![&grandchild, &other, &child, &orphan, &parent]);
+        let layout: Vec<(&str, usize)> = rows.iter().map(|(t, depth)| (t.title.as_str(), *depth)).collect();
+        assert_eq!(layout, vec![("Other", 0), ("Orphan", 0), ("Parent", 0), ("Child", 1), ("Grandchild", 2)]);
+    }
+
+    #[test]
+    fn test_format_timestamp_in_display_timezone() {
+        let timestamp = chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2024, 2, 1, 14, 30, 0).unwrap();
+        assert_eq!(format_timestamp(timestamp, chrono_tz::Tz::UTC), "2024-02-01 14:30:00 UTC");
+        assert_eq!(
+            format_timestamp(timestamp, chrono_tz::America::New_York),
+            "2024-02-01 09:30:00 EST (UTC-05:00)"
+        );
+        assert_eq!(
+            format_timestamp(timestamp, chrono_tz::Asia::Kolkata),
+            "2024-02-01 20:00:00 IST (UTC+05:30)"
+        );
+    }
+
+    #[test]
+    fn test_parse_id_list() {
+        let contents = "# tasks to close\n\n  550e8400-e29b-41d4-a716-446655440000  \n#skip-me\nabc\n";
+        assert_eq!(parse_id_list(contents), vec!["550e8400-e29b-41d4-a716-446655440000", "abc"]);
+        assert!(parse_id_list("# nothing here\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_completion_bar() {
+        assert_eq!(completion_bar(0.0), format!("[{}] 0.0%", "░".repeat(20)));
+        assert_eq!(completion_bar(78.5), format!("[{}{}] 78.5%", "█".repeat(16), "░".repeat(4)));
+        assert_eq!(completion_bar(100.0), format!("[{}] 100.0%", "█".repeat(20)));
+    }
+
+    #[test]
+    fn test_auto_color() {
+        assert!(auto_color(None, None, None, true));
+        assert!(!auto_color(None, None, None, false));
+        assert!(!auto_color(Some("1"), None, None, true));
+        assert!(auto_color(Some(""), None, None, true));
+        assert!(!auto_color(None, None, Some("0"), true));
+        assert!(auto_color(Some("1"), Some("1"), None, false));
+        assert!(!auto_color(None, Some("0"), None, false));
+    }
+
+    #[test]
+    fn test_format_effort() {
+        let effort = |estimated_minutes, tracked_minutes| crate::manager::Effort { estimated_minutes, tracked_minutes };
+        assert_eq!(format_effort(effort(120, 150)), "2h 00m estimated, 2h 30m tracked (125%)");
+        assert_eq!(format_effort(effort(0, 45)), "0m estimated, 45m tracked");
+    }
+
+    #[test]
+    fn test_batch_summary() {
+        assert_eq!(batch_summary("completed", 10, &[]), "completed 10");
+        assert_eq!(batch_summary("completed", 8, &["not found", "not found"]), "completed 8, 2 failed (not found)");
+        assert_eq!(
+            batch_summary("started", 7, &["not found", "not allowed", "not found"]),
+            "started 7, 3 failed (2 not found, 1 not allowed)"
+        );
+    }
+
+    #[test]
+    fn test_overdue_warning() {
+        assert_eq!(overdue_warning(5, 5), None);
+        assert_eq!(overdue_warning(7, 5).as_deref(), Some("⚠️ 7 overdue tasks need attention!"));
+        assert!(overdue_warning(1, 0).is_some());
+    }
+
+    #[test]
+    fn test_truncate_display() {
+        assert_eq!(truncate_display("work", 15), "work");
+        assert_eq!(truncate_display("infrastructure-migration", 15), "infrastructure…");
+        assert_eq!(truncate_display("ünïcödé", 4), "ünï…");
+        assert_eq!(truncate_display("abc", 0), "…");
+    }
+
+    #[test]
+    fn test_truncate_display_counts_wide_characters() {
+        // CJK characters take two columns each
+        assert_eq!(truncate_display("買い物リスト", 12), "買い物リスト");
+        assert_eq!(truncate_display("買い物リストを作る", 8), "買い物…");
+        assert_eq!(truncate_display("買い物リストを作る", 9), "買い物リ…");
+
+        // Emoji are wide and multi-codepoint sequences are never split
+        assert_eq!(truncate_display("🎉🎉🎉 party", 6), "🎉🎉…");
+        assert_eq!(truncate_display("👩‍💻 deploy", 3), "👩‍💻…");
+        assert_eq!(truncate_display("🇯🇵 trip planning", 5), "🇯🇵 t…");
+        assert_eq!(truncate_display("e\u{301}te\u{301} plans", 4), "e\u{301}te\u{301}…");
+    }
+
+    #[test]
+    fn test_anonymize_tasks() {
+        let mut first = crate::task::Task::new("Call the bank about my mortgage".to_string());
+        first.description = Some("Account 1234".to_string());
+        first.category = Some("finance".to_string());
+        first.owner = Some("alice@corp".to_string());
+        let mut second = crate::task::Task::new("Pay rent".to_string());
+        second.category = Some("finance".to_string());
+        second.owner = Some("alice@corp".to_string());
+        second.created_at = first.created_at + chrono::Duration::seconds(1);
+        second.depends_on.push(first.id);
+
+        let anonymized = anonymize_tasks(&[&second, &first]);
+        assert_eq!(anonymized[0].title, "Task 1");
+        assert_eq!(anonymized[0].description.as_deref(), Some("[short description]"));
+        assert_eq!(anonymized[1].title, "Task 2");
+        assert_eq!(anonymized[0].category, anonymized[1].category);
+        assert_ne!(anonymized[0].category.as_deref(), Some("finance"));
+        assert_eq!(anonymized[1].depends_on, vec![first.id]);
+        assert_eq!(anonymized[0].id, first.id);
+        assert_eq!(anonymized[0].owner.as_deref(), Some("owner-1"));
+        assert_eq!(anonymized[0].owner, anonymized[1].owner);
+    }
+}
@@ -1,30 +1,226 @@
+use crate::clock::{Clock, SystemClock};
+use crate::crypto::{self, Passphrase};
 use crate::error::{Result, TaskError};
-use crate::task::{Priority, Task, TaskStatus, UpdateValue};
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use crate::task::{NewTask, Note, Priority, Task, TaskStatus, TaskUpdate, ValidationLimits};
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::de::{DeserializeOwned, Error as _, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer as _, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use uuid::Uuid;
 use tokio::fs;
-use tracing::info;
-use validator::Validate;
+use tracing::{debug, info, warn};
+
+/// Default cap on import file size (10MB)
+pub const DEFAULT_MAX_IMPORT_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Default number of overdue tasks `stats` tolerates before warning
+pub const DEFAULT_OVERDUE_WARN_THRESHOLD: usize = 5;
+
+/// Default completion rate (percent) at or above which `stats` shows it in green
+pub const DEFAULT_COMPLETION_GOOD_THRESHOLD: f64 = 75.0;
+
+/// Default completion rate (percent) below which `stats` shows it in red
+pub const DEFAULT_COMPLETION_WARN_THRESHOLD: f64 = 50.0;
+
+/// Default number of timestamped backups of the data file to keep
+pub const DEFAULT_BACKUPS: usize = 5;
+
+/// Separates the data file name from the timestamp in a backup's name
+const BACKUP_MARKER: &str = ".bak-";
+
+/// Timestamp in backup file names; sorts chronologically as text
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// Serialization format for the data file and for export/import files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl StorageFormat {
+    /// Guess the format from a file extension (`.json`, `.yaml`/`.yml`), looking
+    /// through a trailing `.gz`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let path = match is_gzip_path(path) {
+            true => Path::new(path.file_stem()?),
+            false => path,
+        };
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(StorageFormat::Json),
+            "yaml" | "yml" => Some(StorageFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Serialize a value in this format (pretty-printed for JSON)
+    pub fn render<T: Serialize + ?Sized>(self, value: &T) -> Result<String> {
+        Ok(match self {
+            StorageFormat::Json => serde_json::to_string_pretty(value)?,
+            StorageFormat::Yaml => serde_yaml::to_string(value)?,
+        })
+    }
+
+    /// Deserialize a value from text in this format
+    pub fn parse<T: DeserializeOwned>(self, data: &str) -> Result<T> {
+        Ok(match self {
+            StorageFormat::Json => serde_json::from_str(data)?,
+            StorageFormat::Yaml => serde_yaml::from_str(data)?,
+        })
+    }
+}
+
+impl fmt::Display for StorageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StorageFormat::Json => "JSON",
+            StorageFormat::Yaml => "YAML",
+        })
+    }
+}
 
 /// Configuration for task storage
 #[derive(Debug, Clone)]
 pub struct TaskManagerConfig {
     pub storage_path: PathBuf,
+    /// Format the data file is read and written in
+    pub storage_format: StorageFormat,
     pub auto_save: bool,
+    /// Journal every mutation to a write-ahead log next to the storage file
+    pub wal_enabled: bool,
+    /// Largest file `import` will accept, in bytes
+    pub max_import_size: u64,
+    /// Timezone used for date input that carries no UTC offset
+    pub input_timezone: Tz,
+    /// Timezone dates and times are shown in; storage stays UTC
+    pub display_timezone: Tz,
+    /// Maximum lengths for task text fields
+    pub validation_limits: ValidationLimits,
+    /// First day of the week, used to resolve `eow` due dates
+    pub week_start: Weekday,
+    /// Completing a task at or above this priority asks for confirmation
+    pub confirm_complete_priority: Option<Priority>,
+    /// `stats` warns when more tasks than this are overdue
+    pub overdue_warn_threshold: usize,
+    /// Completion rates at or above this percentage are shown in green by `stats`
+    pub completion_good_threshold: f64,
+    /// Completion rates below this percentage are shown in red; between the two, yellow
+    pub completion_warn_threshold: f64,
+    /// Source of the current time for creation, completion and overdue checks
+    pub clock: Arc<dyn Clock>,
+    /// Load what can be read from a damaged JSON data file instead of failing
+    pub recover: bool,
+    /// Copy the data file to a timestamped backup before each write, keeping this many
+    /// (0 disables backups)
+    pub backups: usize,
+    /// Encrypt the data file with a key derived from this passphrase; an encrypted
+    /// file can only be loaded with it
+    pub passphrase: Option<Passphrase>,
+    /// Gzip the data file. Set automatically when a compressed file is loaded
+    pub compress: bool,
+}
+
+impl TaskManagerConfig {
+    /// Path of the write-ahead log, derived from the storage path (e.g. `tasks.wal`).
+    pub fn wal_path(&self) -> PathBuf {
+        self.storage_path.with_extension("wal")
+    }
+
+    /// Path recording the order of the last numbered listing (e.g. `tasks.last-list`).
+    pub fn last_list_path(&self) -> PathBuf {
+        self.storage_path.with_extension("last-list")
+    }
+
+    /// Path holding deleted tasks until the trash is emptied (e.g. `tasks.trash`).
+    pub fn trash_path(&self) -> PathBuf {
+        self.storage_path.with_extension("trash")
+    }
+
+    /// Path recording the ID of the focused task (e.g. `tasks.focus`).
+    pub fn focus_path(&self) -> PathBuf {
+        self.storage_path.with_extension("focus")
+    }
+
+    /// Path of a backup taken at `at` (e.g. `tasks.json.bak-20240501T123000.000Z`).
+    pub fn backup_path(&self, at: DateTime<Utc>) -> PathBuf {
+        let mut path = self.storage_path.clone().into_os_string();
+        path.push(BACKUP_MARKER);
+        path.push(at.format(BACKUP_TIMESTAMP_FORMAT).to_string());
+        PathBuf::from(path)
+    }
+}
+
+/// A backup of the data file
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// When the backup was taken, read from its file name
+    pub taken_at: DateTime<Utc>,
 }
 
 impl Default for TaskManagerConfig {
     fn default() -> Self {
         Self {
             storage_path: PathBuf::from("tasks.json"),
+            storage_format: StorageFormat::Json,
             auto_save: true,
+            wal_enabled: false,
+            max_import_size: DEFAULT_MAX_IMPORT_SIZE,
+            input_timezone: Tz::UTC,
+            display_timezone: Tz::UTC,
+            validation_limits: ValidationLimits::default(),
+            week_start: Weekday::Mon,
+            confirm_complete_priority: None,
+            overdue_warn_threshold: DEFAULT_OVERDUE_WARN_THRESHOLD,
+            completion_good_threshold: DEFAULT_COMPLETION_GOOD_THRESHOLD,
+            completion_warn_threshold: DEFAULT_COMPLETION_WARN_THRESHOLD,
+            clock: Arc::new(SystemClock),
+            recover: false,
+            backups: DEFAULT_BACKUPS,
+            passphrase: None,
+            compress: false,
         }
     }
 }
 
+/// A single mutation recorded in the write-ahead log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum WalOp {
+    /// Insert or replace a task with the given state
+    Upsert { task: Box<Task> },
+    /// Remove the task with the given ID
+    Delete { id: String },
+    /// Remove all tasks
+    Clear,
+    /// Move the task with the given ID to the trash
+    Trash { id: String },
+    /// Move the task with the given ID out of the trash
+    Untrash { id: String },
+    /// Permanently remove everything in the trash
+    EmptyTrash,
+}
+
+/// A timestamped write-ahead log record, stored one per line as JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalEntry {
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub op: WalOp,
+}
+
 /// Enterprise-grade task manager with persistence and comprehensive operations
 ///
 /// TaskManager provides a comprehensive interface for managing tasks with
@@ -35,6 +231,15 @@ pub struct TaskManager {
     /// All tasks indexed by ID for fast lookup
     pub tasks: HashMap<String, Task>,
 
+    /// Archived tasks indexed by ID; stored in the data file with `archived_at` set
+    /// but kept apart from `tasks` so they stay out of everyday commands
+    #[serde(skip)]
+    pub archive: HashMap<String, Task>,
+
+    /// Deleted tasks indexed by ID, kept in a separate file so they can be restored
+    #[serde(skip)]
+    pub trash: HashMap<String, Task>,
+
     /// Configuration
     #[serde(skip)]
     pub config: TaskManagerConfig,
@@ -42,6 +247,11 @@ pub struct TaskManager {
     /// Track if data has been modified since last save
     #[serde(skip)]
     pub dirty: AtomicBool,
+
+    /// Hash of the storage file contents as last read or written, used to skip
+    /// saves that would rewrite identical bytes
+    #[serde(skip)]
+    saved_hash: Mutex<Option<u64>>,
 }
 
 impl TaskManager {
@@ -57,53 +267,463 @@ impl TaskManager {
     pub fn with_config(config: TaskManagerConfig) -> Self {
         Self {
             tasks: HashMap::new(),
+            archive: HashMap::new(),
+            trash: HashMap::new(),
             config,
             dirty: AtomicBool::new(false),
+            saved_hash: Mutex::new(None),
         }
     }
 
+    /// The current time according to the configured clock
+    pub fn now(&self) -> DateTime<Utc> {
+        self.config.clock.now()
+    }
+
     /// Load tasks from the configured storage path asynchronously.
     ///
     /// If the file does not exist, it starts with an empty task list.
-    /// Clears any existing tasks in memory.
+    /// Clears any existing tasks in memory. When the write-ahead log is enabled,
+    /// entries recorded after the last full save are replayed on top.
     pub async fn load(&mut self) -> Result<()> {
-        if !self.config.storage_path.exists() {
+        self.tasks.clear();
+        self.archive.clear();
+        self.trash.clear();
+        self.dirty.store(false, Ordering::Relaxed);
+        self.set_saved_hash(None);
+
+        self.check_storage_path()?;
+
+        let mut last_saved = None;
+        if self.config.storage_path.exists() {
+            let raw = fs::read(&self.config.storage_path).await?;
+            let encrypted = crypto::is_encrypted(&raw);
+            let (data, compressed) = self.decode_storage(&raw)?;
+            // Keep a compressed file compressed even without --compress
+            let recompress = self.config.compress && !compressed;
+            self.config.compress |= compressed;
+            let mut recovered = false;
+            let loaded_tasks: Vec<Task> = match self.config.storage_format.parse(&data) {
+                Ok(tasks) => tasks,
+                Err(e) if self.config.recover && self.config.storage_format == StorageFormat::Json => {
+                    let (tasks, readable) = recover_json_tasks(&data);
+                    let mut backup = self.config.storage_path.clone().into_os_string();
+                    backup.push(".corrupt");
+                    fs::write(&backup, &raw).await?;
+                    warn!(
+                        "{} is damaged ({}); recovered {} tasks and discarded the last {} bytes. The original was copied to {}",
+                        self.config.storage_path.display(), e, tasks.len(), data.len() - readable, Path::new(&backup).display()
+                    );
+                    recovered = true;
+                    tasks
+                }
+                Err(e) => return Err(e),
+            };
+            self.set_saved_hash(Some(content_hash(&data)));
+
+            for task in loaded_tasks {
+                self.insert_stored(task);
+            }
+            // Write the recovered tasks back so the damaged file is replaced on save, and
+            // a plain file back encrypted or compressed once that is asked for
+            if recovered || recompress || (self.config.passphrase.is_some() && !encrypted) {
+                self.set_saved_hash(None);
+                self.dirty.store(true, Ordering::Relaxed);
+            }
+
+            last_saved = fs::metadata(&self.config.storage_path).await?.modified().ok().map(DateTime::<Utc>::from);
+            info!("Loaded {} tasks from {}", self.tasks.len(), self.config.storage_path.display());
+        } else {
             info!("No existing task file found, starting with empty task list");
+        }
+
+        let trash_path = self.config.trash_path();
+        if trash_path.exists() {
+            let (data, _) = self.decode_storage(&fs::read(&trash_path).await?)?;
+            let trashed: Vec<Task> = self.config.storage_format.parse(&data)?;
+            self.trash = trashed.into_iter().map(|task| (task.id.to_string(), task)).collect();
+        }
+
+        if self.config.wal_enabled {
+            self.replay_wal(last_saved)?;
+        }
+
+        Ok(())
+    }
+
+    /// Every task written to the data file, archived ones included, in a stable
+    /// order so identical task sets serialize to identical bytes
+    pub fn stored_tasks(&self) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.tasks.values().chain(self.archive.values()).collect();
+        tasks.sort_by_key(|task| (task.created_at, task.id));
+        tasks
+    }
+
+    /// Put a task read from storage or the log into the archive or the active
+    /// tasks, according to its `archived_at`
+    fn insert_stored(&mut self, task: Task) {
+        let id = task.id.to_string();
+        if task.archived_at.is_some() {
+            self.tasks.remove(&id);
+            self.archive.insert(id, task);
+        } else {
+            self.archive.remove(&id);
+            self.tasks.insert(id, task);
+        }
+    }
+
+    /// The active or archived task with the given ID
+    pub fn stored_task(&self, id: &str) -> Option<&Task> {
+        self.tasks.get(id).or_else(|| self.archive.get(id))
+    }
+
+    /// Write the trash next to the data file, in the same format and encoding, or
+    /// remove the trash file once nothing is left in it
+    async fn save_trash(&self) -> Result<()> {
+        let path = self.config.trash_path();
+        if self.trash.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path).await?;
+            }
             return Ok(());
         }
 
-        let data = fs::read_to_string(&self.config.storage_path).await?;
-        let loaded_tasks: Vec<Task> = serde_json::from_str(&data)?;
+        let mut trashed: Vec<&Task> = self.trash.values().collect();
+        trashed.sort_by_key(|task| (task.created_at, task.id));
+        let data = self.config.storage_format.render(&trashed)?;
+        write_atomically(&path, &self.encode_storage(&data)?).await
+    }
 
-        self.tasks.clear();
-        for task in loaded_tasks {
-            self.tasks.insert(task.id.to_string(), task);
+    /// Text of a storage file's contents, decrypting and decompressing them as needed,
+    /// and whether they were gzipped
+    fn decode_storage(&self, raw: &[u8]) -> Result<(String, bool)> {
+        let plain = match (crypto::is_encrypted(raw), &self.config.passphrase) {
+            (false, _) => raw.to_vec(),
+            (true, Some(passphrase)) => crypto::decrypt(raw, passphrase)?,
+            (true, None) => {
+                return Err(TaskError::EncryptionError(format!(
+                    "{} is encrypted; set {} or pass --encrypt to enter the passphrase",
+                    self.config.storage_path.display(), crypto::KEY_ENV_VAR
+                )));
+            }
+        };
+        let compressed = is_gzip(&plain);
+        let plain = if compressed { gunzip(&plain)? } else { plain };
+        let text = String::from_utf8(plain).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok((text, compressed))
+    }
+
+    /// Bytes to store for serialized tasks: compressed and then encrypted, as configured
+    fn encode_storage(&self, data: &str) -> Result<Vec<u8>> {
+        let data = match self.config.compress {
+            true => gzip(data.as_bytes())?,
+            false => data.as_bytes().to_vec(),
+        };
+        match &self.config.passphrase {
+            Some(passphrase) => crypto::encrypt(&data, passphrase),
+            None => Ok(data),
         }
+    }
 
-        self.dirty.store(false, Ordering::Relaxed);
-        info!("Loaded {} tasks from {}", self.tasks.len(), self.config.storage_path.display());
+    fn saved_hash(&self) -> Option<u64> {
+        *self.saved_hash.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn set_saved_hash(&self, hash: Option<u64>) {
+        *self.saved_hash.lock().unwrap_or_else(PoisonError::into_inner) = hash;
+    }
+
+    /// Reject storage paths that exist but are not regular files, which would
+    /// otherwise surface as an opaque IO error on read or write.
+    fn check_storage_path(&self) -> Result<()> {
+        let path = &self.config.storage_path;
+        match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_dir() => Err(TaskError::FileOperationError(
+                format!("storage path is a directory: {}", path.display())
+            )),
+            Ok(metadata) if !metadata.is_file() => Err(TaskError::FileOperationError(
+                format!("storage path is not a regular file: {}", path.display())
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Replay write-ahead log entries newer than `since` into memory.
+    ///
+    /// Replayed changes mark the manager dirty so the next save persists them
+    /// and truncates the log. A partially written trailing line is ignored.
+    fn replay_wal(&mut self, since: Option<DateTime<Utc>>) -> Result<()> {
+        let wal_path = self.config.wal_path();
+        if !wal_path.exists() {
+            return Ok(());
+        }
+
+        let reader = BufReader::new(std::fs::File::open(&wal_path)?);
+        let mut replayed = 0;
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: WalEntry = match serde_json::from_str(&line) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!("Ignoring unreadable write-ahead log entry: {}", e);
+                    continue;
+                }
+            };
+            if since.is_some_and(|saved| entry.timestamp <= saved) {
+                continue;
+            }
+
+            match entry.op {
+                WalOp::Upsert { task } => self.insert_stored(*task),
+                WalOp::Delete { id } => {
+                    self.tasks.remove(&id);
+                    self.archive.remove(&id);
+                }
+                WalOp::Clear => {
+                    self.tasks.clear();
+                    self.archive.clear();
+                }
+                WalOp::Trash { id } => {
+                    if let Some(task) = self.tasks.remove(&id) {
+                        self.trash.insert(id, task);
+                    }
+                }
+                WalOp::Untrash { id } => {
+                    if let Some(task) = self.trash.remove(&id) {
+                        self.tasks.insert(id, task);
+                    }
+                }
+                WalOp::EmptyTrash => self.trash.clear(),
+            }
+            replayed += 1;
+        }
+
+        if replayed > 0 {
+            self.dirty.store(true, Ordering::Relaxed);
+            info!("Replayed {} write-ahead log entries from {}", replayed, wal_path.display());
+        }
+        Ok(())
+    }
+
+    /// Append a mutation to the write-ahead log and flush it to disk.
+    ///
+    /// Does nothing unless the write-ahead log is enabled in the configuration.
+    fn journal(&self, op: WalOp) -> Result<()> {
+        if !self.config.wal_enabled {
+            return Ok(());
+        }
+
+        let entry = WalEntry { timestamp: self.now(), op };
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.config.wal_path())?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
         Ok(())
     }
 
+    /// Journal a mutation from an operation that cannot report errors.
+    fn journal_or_warn(&self, op: WalOp) {
+        if let Err(e) = self.journal(op) {
+            warn!("Failed to append to write-ahead log: {}", e);
+        }
+    }
+
     /// Save all tasks to the configured storage path asynchronously.
     ///
-    /// Only performs a save if the `dirty` flag is set to true.
+    /// Only performs a save if the `dirty` flag is set to true. The file is replaced
+    /// atomically, so an interrupted save leaves either the old or the new contents,
+    /// never a truncated file.
     pub async fn save(&self) -> Result<()> {
         if !self.dirty.load(Ordering::Relaxed) {
             return Ok(());
         }
 
-        let tasks: Vec<&Task> = self.tasks.values().collect();
-        let data = serde_json::to_string_pretty(&tasks)?;
+        self.check_storage_path()?;
+
+        let tasks = self.stored_tasks();
+        let data = self.config.storage_format.render(&tasks)?;
+        let hash = content_hash(&data);
+
+        if self.saved_hash() == Some(hash) && self.config.storage_path.exists() {
+            debug!("Task data unchanged; skipping write to {}", self.config.storage_path.display());
+        } else {
+            // Create directory if it doesn't exist
+            if let Some(parent) = self.config.storage_path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+
+            self.back_up().await?;
+            write_atomically(&self.config.storage_path, &self.encode_storage(&data)?).await?;
+            self.set_saved_hash(Some(hash));
+            info!("Saved {} tasks to {}", tasks.len(), self.config.storage_path.display());
+        }
+
+        self.save_trash().await?;
+
+        // Everything in the log is now captured by the full save
+        if self.config.wal_enabled && self.config.wal_path().exists() {
+            fs::write(self.config.wal_path(), b"").await?;
+        }
+        Ok(())
+    }
+
+    /// Copy the storage file to a new timestamped backup, then delete the oldest
+    /// backups beyond the configured number to keep.
+    ///
+    /// When a plain file is about to be encrypted, the new backup is written encrypted
+    /// and existing plaintext backups are encrypted in place, so no copy of the tasks
+    /// is left readable.
+    async fn back_up(&self) -> Result<()> {
+        if !self.config.storage_path.exists() {
+            return Ok(());
+        }
+        let encrypting = self.config.passphrase.as_ref()
+            .filter(|_| !crypto::file_is_encrypted(&self.config.storage_path));
+        if let Some(passphrase) = encrypting {
+            self.encrypt_plain_backups(passphrase).await?;
+        }
+        if self.config.backups == 0 {
+            return Ok(());
+        }
+
+        let path = self.config.backup_path(self.now());
+        match encrypting {
+            Some(passphrase) => {
+                let raw = fs::read(&self.config.storage_path).await?;
+                write_atomically(&path, &crypto::encrypt(&raw, passphrase)?).await?;
+            }
+            None => {
+                fs::copy(&self.config.storage_path, &path).await?;
+            }
+        }
+        debug!("Backed up {} to {}", self.config.storage_path.display(), path.display());
+
+        for stale in self.list_backups().await?.into_iter().skip(self.config.backups) {
+            fs::remove_file(&stale.path).await?;
+            debug!("Removed old backup {}", stale.path.display());
+        }
+        Ok(())
+    }
+
+    /// Rewrite every plaintext backup encrypted with `passphrase`
+    async fn encrypt_plain_backups(&self, passphrase: &Passphrase) -> Result<()> {
+        for backup in self.list_backups().await? {
+            if crypto::file_is_encrypted(&backup.path) {
+                continue;
+            }
+            let raw = fs::read(&backup.path).await?;
+            write_atomically(&backup.path, &crypto::encrypt(&raw, passphrase)?).await?;
+            warn!("Encrypted plaintext backup {}", backup.path.display());
+        }
+        Ok(())
+    }
+
+    /// Backups of the storage file, newest first
+    pub async fn list_backups(&self) -> Result<Vec<Backup>> {
+        let Some(name) = self.config.storage_path.file_name().and_then(|name| name.to_str()) else {
+            return Ok(Vec::new());
+        };
+        let prefix = format!("{}{}", name, BACKUP_MARKER);
+        let dir = match self.config.storage_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        let mut entries = fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = entry.file_name();
+            let Some(timestamp) = file_name.to_str().and_then(|name| name.strip_prefix(&prefix)) else {
+                continue;
+            };
+            if let Ok(taken_at) = chrono::NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT) {
+                backups.push(Backup { path: self.config.storage_path.with_file_name(&file_name), taken_at: taken_at.and_utc() });
+            }
+        }
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.taken_at));
+        Ok(backups)
+    }
+
+    /// Replace every task in memory with the contents of a backup file.
+    ///
+    /// Returns the number of tasks restored. The data file itself is only rewritten on
+    /// save, which backs up the current file first, so a restore can be undone too.
+    pub async fn restore_backup(&mut self, path: &Path) -> Result<usize> {
+        let (data, _) = self.decode_storage(&fs::read(path).await?)?;
+        let restored: Vec<Task> = self.config.storage_format.parse(&data)?;
+
+        self.journal(WalOp::Clear)?;
+        self.tasks.clear();
+        self.archive.clear();
+        for task in restored {
+            self.journal(WalOp::Upsert { task: Box::new(task.clone()) })?;
+            self.insert_stored(task);
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Restored {} tasks from {}", self.tasks.len(), path.display());
+        Ok(self.tasks.len())
+    }
+
+    /// Rewrite the storage file in a normalized form: tasks sorted by creation time
+    /// and pretty-printed, optionally dropping cancelled tasks last touched before
+    /// `drop_cancelled_before`.
+    pub async fn compact(&mut self, drop_cancelled_before: Option<DateTime<Utc>>) -> Result<CompactReport> {
+        self.check_storage_path()?;
+
+        let bytes_before = match fs::metadata(&self.config.storage_path).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+
+        let mut removed = 0;
+        if let Some(cutoff) = drop_cancelled_before {
+            let stale: Vec<String> = self.stored_tasks().into_iter()
+                .filter(|task| task.status == TaskStatus::Cancelled && task.updated_at < cutoff)
+                .map(|task| task.id.to_string())
+                .collect();
+            let mut dropped = Vec::new();
+            for id in stale {
+                self.journal(WalOp::Delete { id: id.clone() })?;
+                dropped.extend(self.tasks.remove(&id).or_else(|| self.archive.remove(&id)).map(|task| task.id));
+                removed += 1;
+            }
+            self.drop_dependencies_on(&dropped)?;
+        }
+
+        let tasks = self.stored_tasks();
+        let data = self.config.storage_format.render(&tasks)?;
 
-        // Create directory if it doesn't exist
         if let Some(parent) = self.config.storage_path.parent() {
             fs::create_dir_all(parent).await?;
         }
+        let stored = self.encode_storage(&data)?;
+        self.back_up().await?;
+        write_atomically(&self.config.storage_path, &stored).await?;
+        self.set_saved_hash(Some(content_hash(&data)));
 
-        fs::write(&self.config.storage_path, data).await?;
-        info!("Saved {} tasks to {}", tasks.len(), self.config.storage_path.display());
-        Ok(())
+        if self.config.wal_enabled && self.config.wal_path().exists() {
+            fs::write(self.config.wal_path(), b"").await?;
+        }
+        self.dirty.store(false, Ordering::Relaxed);
+
+        info!("Compacted {} ({} tasks removed)", self.config.storage_path.display(), removed);
+        Ok(CompactReport {
+            bytes_before,
+            bytes_after: stored.len() as u64,
+            removed,
+        })
     }
 
     /// Add a new task with basic info and perform validation.
@@ -111,10 +731,13 @@ impl TaskManager {
     /// Returns the ID of the newly created task.
     #[allow(dead_code)]
     pub fn add_task(&mut self, title: String) -> Result<String> {
-        let task = Task::new(title);
-        task.validate().map_err(TaskError::from_validation_errors)?;
+        let mut task = Task::new(title);
+        task.created_at = self.now();
+        task.updated_at = task.created_at;
+        task.validate_with(&self.config.validation_limits).map_err(TaskError::from_validation_errors)?;
 
         let id = task.id.to_string();
+        self.journal(WalOp::Upsert { task: Box::new(task.clone()) })?;
         self.tasks.insert(id.clone(), task);
         self.dirty.store(true, Ordering::Relaxed);
 
@@ -125,25 +748,33 @@ impl TaskManager {
     /// Add a new task with full details and perform validation.
     ///
     /// Returns the ID of the newly created task.
-    pub fn add_task_detailed(
-        &mut self,
-        title: String,
-        description: Option<String>,
-        priority: Option<Priority>,
-        category: Option<String>,
-        due_date: Option<DateTime<Utc>>,
-    ) -> Result<String> {
-        let task = Task::with_details(
-            title,
-            description,
-            priority.unwrap_or(Priority::Medium),
-            category,
-            due_date,
+    pub fn add_task_detailed(&mut self, details: NewTask) -> Result<String> {
+        let mut task = Task::with_details(
+            details.title,
+            details.description,
+            details.priority.unwrap_or(Priority::Medium),
+            details.category,
+            details.due_date,
         );
+        task.created_at = self.now();
+        task.updated_at = task.created_at;
+        task.links = details.links;
+        task.recurrence = details.recurrence;
+        task.owner = details.owner;
+        task.estimate_minutes = details.estimate_minutes;
+        task.tags = crate::task::normalize_tags(details.tags);
+        if let Some(parent) = details.parent {
+            task.parent_id = Some(self.get_task(&parent)?.id);
+        }
+        for dependency in &details.depends_on {
+            task.depends_on.push(self.get_task(dependency)?.id);
+        }
+        self.check_dependency_cycles(task.id, &task.depends_on)?;
 
-        task.validate().map_err(TaskError::from_validation_errors)?;
+        task.validate_with(&self.config.validation_limits).map_err(TaskError::from_validation_errors)?;
 
         let id = task.id.to_string();
+        self.journal(WalOp::Upsert { task: Box::new(task.clone()) })?;
         self.tasks.insert(id.clone(), task);
         self.dirty.store(true, Ordering::Relaxed);
 
@@ -158,6 +789,16 @@ impl TaskManager {
         self.tasks.get(id).ok_or_else(|| TaskError::TaskNotFound(id.to_string()))
     }
 
+    /// Retrieve the task whose ID is `prefix` or starts with it (ignoring case), so the
+    /// 8-character short IDs shown by `list` can be typed in place of a full UUID.
+    ///
+    /// Returns `TaskError::InvalidId` when `prefix` can't be part of a UUID,
+    /// `TaskError::AmbiguousId` listing the candidates when several tasks match,
+    /// and `TaskError::TaskNotFound` when none do.
+    pub fn get_task_by_prefix(&self, prefix: &str) -> Result<&Task> {
+        find_by_prefix(&self.tasks, prefix)
+    }
+
     /// Retrieve a mutable reference to a task by its ID.
     ///
     /// Returns `TaskError::TaskNotFound` if the task doesn't exist.
@@ -167,98 +808,835 @@ impl TaskManager {
 
     /// Update an existing task's fields.
     ///
-    /// Re-validates the task after update and sets the dirty flag.
-    pub fn update_task(
-        &mut self,
-        id: &str,
-        title: Option<String>,
-        description: UpdateValue<String>,
-        priority: Option<Priority>,
-        category: UpdateValue<String>,
-        due_date: UpdateValue<DateTime<Utc>>,
-    ) -> Result<()> {
-        let task = self.get_task_mut(id)?;
-        task.update(title, description, priority, category, due_date);
-        task.validate().map_err(TaskError::from_validation_errors)?;
+    /// Re-validates the task after update and sets the dirty flag. An update that
+    /// leaves every field as it was changes nothing, so the next save writes nothing.
+    pub fn update_task(&mut self, id: &str, changes: TaskUpdate) -> Result<()> {
+        let updated = self.preview_update(id, changes)?;
+        if updated == *self.get_task(id)? {
+            debug!("Update leaves task {} unchanged", id);
+            return Ok(());
+        }
+        self.journal(WalOp::Upsert { task: Box::new(updated.clone()) })?;
+        *self.get_task_mut(id)? = updated;
         self.dirty.store(true, Ordering::Relaxed);
 
         info!("Updated task: {}", id);
         Ok(())
     }
 
-    /// Delete a task by its ID and return it.
+    /// Apply changes to a copy of a task and validate the result without storing it.
     ///
-    /// Returns `TaskError::TaskNotFound` if the task doesn't exist.
-    pub fn delete_task(&mut self, id: &str) -> Result<Task> {
-        let task = self.tasks.remove(id).ok_or_else(|| TaskError::TaskNotFound(id.to_string()))?;
+    /// Returns the updated copy, or the validation errors the update would produce.
+    pub fn preview_update(&self, id: &str, changes: TaskUpdate) -> Result<Task> {
+        let mut updated = self.get_task(id)?.clone();
+        if let Some(dependencies) = &changes.depends_on {
+            for dependency in dependencies {
+                self.get_task(&dependency.to_string())?;
+            }
+            self.check_dependency_cycles(updated.id, dependencies)?;
+        }
+        updated.update(changes, self.now());
+        updated.validate_with(&self.config.validation_limits).map_err(TaskError::from_validation_errors)?;
+        Ok(updated)
+    }
+
+    /// IDs of a task's dependencies that exist but are not done yet
+    pub fn unfinished_dependencies(&self, id: &str) -> Result<Vec<Uuid>> {
+        Ok(self.get_task(id)?.depends_on.iter()
+            .filter(|dep| !self.dependency_finished(dep))
+            .copied()
+            .collect())
+    }
+
+    /// Whether a dependency no longer blocks the tasks that depend on it: it is done,
+    /// or no longer exists. A cancelled dependency still blocks.
+    fn dependency_finished(&self, dep: &Uuid) -> bool {
+        self.stored_task(&dep.to_string()).is_none_or(|d| d.status == TaskStatus::Done)
+    }
+
+    /// Append text to a task's description on a new line, creating the
+    /// description if the task has none.
+    pub fn append_description(&mut self, id: &str, text: &str) -> Result<()> {
+        let now = self.now();
+        let before = self.get_task(id)?;
+        let mut updated = before.clone();
+        updated.description = Some(updated.appended_description(text));
+        updated.record_changes_since(before, now);
+        updated.updated_at = now;
+        updated.validate_with(&self.config.validation_limits).map_err(TaskError::from_validation_errors)?;
+        self.journal(WalOp::Upsert { task: Box::new(updated.clone()) })?;
+        *self.get_task_mut(id)? = updated;
         self.dirty.store(true, Ordering::Relaxed);
 
-        info!("Deleted task: {}", id);
-        Ok(task)
+        info!("Appended to description of task: {}", id);
+        Ok(())
     }
 
-    /// Mark a task as complete.
+    /// Append a timestamped progress note to a task.
     ///
-    /// Returns an error if the task is already completed.
-    pub fn complete_task(&mut self, id: &str) -> Result<()> {
-        let task = self.get_task_mut(id)?;
-        if task.status == TaskStatus::Done {
-            return Err(TaskError::OperationNotAllowed("Task is already completed".to_string()));
-        }
-        task.complete();
+    /// Notes are only ever added, never edited, so they form a log of the work.
+    pub fn add_note(&mut self, id: &str, text: String) -> Result<()> {
+        let now = self.now();
+        let mut updated = self.get_task(id)?.clone();
+        updated.notes.push(Note { timestamp: now, text });
+        updated.updated_at = now;
+        updated.validate_with(&self.config.validation_limits).map_err(TaskError::from_validation_errors)?;
+        self.journal(WalOp::Upsert { task: Box::new(updated.clone()) })?;
+        *self.get_task_mut(id)? = updated;
         self.dirty.store(true, Ordering::Relaxed);
 
-        info!("Completed task: {}", id);
+        info!("Added note to task: {}", id);
         Ok(())
     }
 
-    /// Move a task to the InProgress status.
-    pub fn start_task(&mut self, id: &str) -> Result<()> {
-        let task = self.get_task_mut(id)?;
-        task.start();
+    /// Attach a link to a task.
+    ///
+    /// The task is left untouched if the link is already present or the
+    /// resulting link list fails validation.
+    pub fn add_link(&mut self, id: &str, url: String) -> Result<()> {
+        let task = self.get_task(id)?;
+        if task.links.contains(&url) {
+            return Err(TaskError::OperationNotAllowed(format!("Task already has link '{}'", url)));
+        }
+
+        let now = self.now();
+        let mut updated = task.clone();
+        updated.links.push(url);
+        updated.record_changes_since(task, now);
+        updated.updated_at = now;
+        updated.validate_with(&self.config.validation_limits).map_err(TaskError::from_validation_errors)?;
+        self.journal(WalOp::Upsert { task: Box::new(updated.clone()) })?;
+        *self.get_task_mut(id)? = updated;
         self.dirty.store(true, Ordering::Relaxed);
 
-        info!("Started task: {}", id);
+        info!("Added link to task: {}", id);
         Ok(())
     }
 
-    /// Move a task to the Cancelled status.
-    pub fn cancel_task(&mut self, id: &str) -> Result<()> {
-        let task = self.get_task_mut(id)?;
-        task.cancel();
+    /// Remove a link from a task.
+    ///
+    /// Returns `TaskError::OperationNotAllowed` if the task has no such link,
+    /// matching `add_link` on a duplicate.
+    pub fn remove_link(&mut self, id: &str, url: &str) -> Result<()> {
+        let task = self.get_task(id)?;
+        let position = task.links.iter().position(|link| link == url)
+            .ok_or_else(|| TaskError::OperationNotAllowed(format!("Task has no link '{}'", url)))?;
+
+        let now = self.now();
+        let mut updated = task.clone();
+        updated.links.remove(position);
+        updated.record_changes_since(task, now);
+        updated.updated_at = now;
+        updated.validate_with(&self.config.validation_limits).map_err(TaskError::from_validation_errors)?;
+        self.journal(WalOp::Upsert { task: Box::new(updated.clone()) })?;
+        *self.get_task_mut(id)? = updated;
         self.dirty.store(true, Ordering::Relaxed);
 
-        info!("Cancelled task: {}", id);
+        info!("Removed link from task: {}", id);
         Ok(())
     }
 
-    /// Get all tasks (immutable view)
-    pub fn get_all_tasks(&self) -> impl Iterator<Item = &Task> {
-        self.tasks.values()
+    /// Delete a task by its ID and return it.
+    ///
+    /// Returns `TaskError::TaskNotFound` if the task doesn't exist.
+    pub fn delete_task(&mut self, id: &str) -> Result<Task> {
+        let subtasks = self.get_children(id)?.len();
+        if subtasks > 0 {
+            return Err(TaskError::OperationNotAllowed(format!(
+                "Task {} has {} subtask(s); delete them first or use --cascade", id, subtasks
+            )));
+        }
+
+        let task = self.move_to_trash(id)?;
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Moved task to the trash: {}", id);
+        Ok(task)
     }
 
-    /// Get tasks filtered by status
-    pub fn get_tasks_by_status(&self, status: TaskStatus) -> impl Iterator<Item = &Task> {
-        self.tasks.values().filter(move |task| task.status == status)
+    /// Delete a task together with all of its subtasks, at any depth.
+    ///
+    /// Returns the removed tasks, the requested one first.
+    pub fn delete_task_cascade(&mut self, id: &str) -> Result<Vec<Task>> {
+        let doomed = self.get_subtree(id)?;
+
+        let mut removed = Vec::new();
+        for doomed_id in doomed.iter().map(Uuid::to_string) {
+            removed.push(self.move_to_trash(&doomed_id)?);
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Moved task {} and {} subtasks to the trash", id, removed.len() - 1);
+        Ok(removed)
     }
 
-    /// Get tasks filtered by priority
-    pub fn get_tasks_by_priority(&self, priority: Priority) -> impl Iterator<Item = &Task> {
-        self.tasks.values().filter(move |task| task.priority == priority)
+    /// Fold the task `from` into `keep` (see `Task::absorb`) and delete `from`.
+    ///
+    /// `keep` retains its ID, and dependencies on or subtasks of `from` are moved
+    /// over to it so no reference is left dangling. Fails if either task is
+    /// missing, if both IDs name the same task, or if the merge would create a
+    /// dependency cycle, in which case nothing is changed.
+    pub fn merge_tasks(&mut self, keep: &str, from: &str) -> Result<()> {
+        let keep_id = self.get_task(keep)?.id;
+        let from_id = self.get_task(from)?.id;
+        if keep_id == from_id {
+            return Err(TaskError::OperationNotAllowed(format!("Cannot merge task {} into itself", keep)));
+        }
+        let inside_from = self.get_subtree(from)?.contains(&keep_id);
+        let now = self.now();
+
+        let backup = self.tasks.clone();
+        let absorbed = self.tasks.remove(from).ok_or_else(|| TaskError::TaskNotFound(from.to_string()))?;
+        let from_parent = absorbed.parent_id;
+
+        let mut changed = vec![keep.to_string()];
+        for task in self.tasks.values_mut() {
+            let before = task.clone();
+            let mut touched = false;
+            if task.depends_on.contains(&from_id) {
+                task.depends_on.retain(|dep| *dep != from_id);
+                if task.id != keep_id && !task.depends_on.contains(&keep_id) {
+                    task.depends_on.push(keep_id);
+                }
+                touched = true;
+            }
+            if task.parent_id == Some(from_id) {
+                task.parent_id = Some(keep_id);
+                touched = true;
+            }
+            if touched && task.id != keep_id {
+                task.record_changes_since(&before, now);
+                task.updated_at = now;
+                changed.push(task.id.to_string());
+            }
+        }
+
+        let keep_task = self.tasks.get_mut(keep).ok_or_else(|| TaskError::TaskNotFound(keep.to_string()))?;
+        keep_task.absorb(absorbed, now);
+        if inside_from {
+            // `keep` can't stay below the task it replaces
+            keep_task.parent_id = from_parent;
+        }
+        let merged = keep_task.clone();
+        let checked = merged
+            .validate_with(&self.config.validation_limits)
+            .map_err(TaskError::from_validation_errors)
+            .and_then(|_| self.check_dependency_cycles(keep_id, &merged.depends_on));
+        if let Err(e) = checked {
+            self.tasks = backup;
+            return Err(e);
+        }
+
+        for id in &changed {
+            let task = self.tasks[id].clone();
+            self.journal(WalOp::Upsert { task: Box::new(task) })?;
+        }
+        self.journal(WalOp::Delete { id: from.to_string() })?;
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Merged task {} into {}", from, keep);
+        Ok(())
     }
 
-    /// Get tasks filtered by category
-    pub fn get_tasks_by_category<'a>(&'a self, category: &'a str) -> impl Iterator<Item = &'a Task> {
-        self.tasks.values()
-            .filter(move |task| task.category.as_ref().map_or(false, |c| c == category))
+    /// Move a task into the trash, replacing any older trashed copy with the same ID.
+    ///
+    /// Other tasks stop depending on it, so no stored task refers to one that is gone.
+    fn move_to_trash(&mut self, id: &str) -> Result<Task> {
+        let task = self.tasks.remove(id).ok_or_else(|| TaskError::TaskNotFound(id.to_string()))?;
+        if let Err(e) = self.journal(WalOp::Trash { id: id.to_string() }) {
+            self.tasks.insert(id.to_string(), task);
+            return Err(e);
+        }
+        self.trash.insert(id.to_string(), task.clone());
+        self.drop_dependencies_on(&[task.id])?;
+        Ok(task)
     }
 
-    /// Get overdue tasks
-    pub fn get_overdue_tasks(&self) -> impl Iterator<Item = &Task> {
-        self.tasks.values().filter(|task| task.is_overdue())
+    /// Remove `removed` from the dependencies of every remaining task
+    fn drop_dependencies_on(&mut self, removed: &[Uuid]) -> Result<()> {
+        let now = self.now();
+        let mut changed = Vec::new();
+        for task in self.tasks.values_mut().chain(self.archive.values_mut()) {
+            if task.depends_on.iter().any(|dep| removed.contains(dep)) {
+                let before = task.clone();
+                task.depends_on.retain(|dep| !removed.contains(dep));
+                task.record_changes_since(&before, now);
+                changed.push(task.clone());
+            }
+        }
+        for task in changed {
+            self.journal(WalOp::Upsert { task: Box::new(task) })?;
+        }
+        Ok(())
     }
 
-    /// Search tasks by title or description
+    /// Retrieve the trashed task whose ID is `prefix` or starts with it, with the same
+    /// rules as `get_task_by_prefix`.
+    pub fn get_trashed_by_prefix(&self, prefix: &str) -> Result<&Task> {
+        find_by_prefix(&self.trash, prefix)
+    }
+
+    /// Trashed tasks, oldest first
+    pub fn trashed_tasks(&self) -> Vec<&Task> {
+        let mut trashed: Vec<&Task> = self.trash.values().collect();
+        trashed.sort_by_key(|task| (task.created_at, task.id));
+        trashed
+    }
+
+    /// Move a task out of the trash and back into the task list.
+    ///
+    /// Fails if a live task has the same ID or the task's parent is still in the
+    /// trash (restore the parent first). A parent that no longer exists at all is
+    /// dropped, making the task top-level, and so are dependencies on tasks that are
+    /// not in the task list or the archive.
+    pub fn restore_from_trash(&mut self, id: &str) -> Result<()> {
+        let task = self.trash.get(id).ok_or_else(|| TaskError::TaskNotFound(id.to_string()))?;
+        if self.tasks.contains_key(id) {
+            return Err(TaskError::OperationNotAllowed(format!("A task with ID {} already exists", id)));
+        }
+        if let Some(parent) = task.parent_id.map(|p| p.to_string()) {
+            if self.trash.contains_key(&parent) {
+                return Err(TaskError::OperationNotAllowed(format!(
+                    "Task {} is a subtask of trashed task {}; restore that first", id, parent
+                )));
+            }
+        }
+
+        let mut task = task.clone();
+        self.journal(WalOp::Untrash { id: id.to_string() })?;
+        let stored = |id: &Uuid| self.tasks.contains_key(&id.to_string()) || self.archive.contains_key(&id.to_string());
+        let orphaned = task.parent_id.is_some_and(|p| !stored(&p));
+        if orphaned {
+            task.parent_id = None;
+        }
+        let dependencies = task.depends_on.len();
+        let before = task.clone();
+        task.depends_on.retain(stored);
+        task.record_changes_since(&before, self.now());
+        if orphaned || task.depends_on.len() != dependencies {
+            self.journal(WalOp::Upsert { task: Box::new(task.clone()) })?;
+        }
+        self.trash.remove(id);
+        self.tasks.insert(id.to_string(), task);
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Restored task from the trash: {}", id);
+        Ok(())
+    }
+
+    /// Permanently remove every task in the trash.
+    ///
+    /// Returns the number of tasks removed.
+    pub fn empty_trash(&mut self) -> Result<usize> {
+        let count = self.trash.len();
+        if count == 0 {
+            return Ok(0);
+        }
+        self.journal(WalOp::EmptyTrash)?;
+        let removed: Vec<Uuid> = self.trash.values().map(|task| task.id).collect();
+        self.trash.clear();
+        self.drop_dependencies_on(&removed)?;
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Emptied {} tasks from the trash", count);
+        Ok(count)
+    }
+
+    /// Move every Done or Cancelled task into the archive.
+    ///
+    /// A closed task is skipped while any of its subtasks is still open, so open
+    /// work never ends up below an archived parent. Returns the number archived.
+    pub fn archive_completed(&mut self) -> usize {
+        let now = self.now();
+        let ids: Vec<String> = self.tasks.values()
+            .filter(|task| task.status.is_closed())
+            .map(|task| task.id.to_string())
+            .filter(|id| {
+                self.get_subtree(id).unwrap_or_default().iter()
+                    .all(|sub| self.tasks.get(&sub.to_string()).is_none_or(|t| t.status.is_closed()))
+            })
+            .collect();
+
+        for id in &ids {
+            if let Some(mut task) = self.tasks.remove(id) {
+                task.archived_at = Some(now);
+                self.journal_or_warn(WalOp::Upsert { task: Box::new(task.clone()) });
+                self.archive.insert(id.clone(), task);
+            }
+        }
+        if !ids.is_empty() {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+
+        info!("Archived {} tasks", ids.len());
+        ids.len()
+    }
+
+    /// Retrieve the archived task whose ID is `prefix` or starts with it, with the same
+    /// rules as `get_task_by_prefix`.
+    pub fn get_archived_by_prefix(&self, prefix: &str) -> Result<&Task> {
+        find_by_prefix(&self.archive, prefix)
+    }
+
+    /// Move an archived task back into the active task list.
+    ///
+    /// Returns `TaskError::TaskNotFound` if no archived task has that ID.
+    pub fn unarchive(&mut self, id: &str) -> Result<()> {
+        if self.tasks.contains_key(id) {
+            return Err(TaskError::OperationNotAllowed(format!("A task with ID {} already exists", id)));
+        }
+        let mut task = self.archive.get(id).cloned().ok_or_else(|| TaskError::TaskNotFound(id.to_string()))?;
+        task.archived_at = None;
+        self.journal(WalOp::Upsert { task: Box::new(task.clone()) })?;
+        self.archive.remove(id);
+        self.tasks.insert(id.to_string(), task);
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Unarchived task: {}", id);
+        Ok(())
+    }
+
+    /// IDs of a task and all of its subtasks at any depth, the task itself first.
+    ///
+    /// Returns `TaskError::TaskNotFound` if the task doesn't exist.
+    pub fn get_subtree(&self, id: &str) -> Result<Vec<Uuid>> {
+        let mut pending = vec![self.get_task(id)?.id];
+        let mut subtree = Vec::new();
+        let mut seen = HashSet::new();
+        while let Some(current) = pending.pop() {
+            if !seen.insert(current) {
+                continue;
+            }
+            subtree.push(current);
+            pending.extend(self.tasks.values().filter(|t| t.parent_id == Some(current)).map(|t| t.id));
+        }
+        Ok(subtree)
+    }
+
+    /// Direct subtasks of a task, oldest first.
+    ///
+    /// Returns `TaskError::TaskNotFound` if the parent doesn't exist.
+    pub fn get_children(&self, id: &str) -> Result<Vec<&Task>> {
+        let parent = self.get_task(id)?.id;
+        let mut children: Vec<&Task> = self.tasks.values().filter(|t| t.parent_id == Some(parent)).collect();
+        children.sort_by_key(|t| (t.created_at, t.id));
+        Ok(children)
+    }
+
+    /// Mark a task as complete.
+    ///
+    /// Returns an error if the task is already completed or any of its dependencies
+    /// is not done yet. Completing a recurring task creates its next instance in
+    /// the same series and returns that ID, unless the series already has that
+    /// instance, e.g. because the task was reopened and completed again.
+    pub fn complete_task(&mut self, id: &str) -> Result<Option<String>> {
+        let blockers = self.unfinished_dependencies(id)?;
+        if !blockers.is_empty() {
+            let ids: Vec<String> = blockers.iter().map(Uuid::to_string).collect();
+            return Err(TaskError::OperationNotAllowed(format!(
+                "Task {} depends on unfinished tasks: {}", id, ids.join(", ")
+            )));
+        }
+
+        let now = self.now();
+        let task = self.transition(id, TaskStatus::Done)?;
+        task.complete(now);
+        if task.recurrence.is_some() && task.series_id.is_none() {
+            task.series_id = Some(task.id);
+        }
+        let task = task.clone();
+        let next = task.next_occurrence(now).filter(|next| !self.series_continued(&task, next));
+        self.journal(WalOp::Upsert { task: Box::new(task) })?;
+        self.dirty.store(true, Ordering::Relaxed);
+        info!("Completed task: {}", id);
+
+        let Some(next) = next else {
+            return Ok(None);
+        };
+        let next_id = next.id.to_string();
+        self.journal(WalOp::Upsert { task: Box::new(next.clone()) })?;
+        self.tasks.insert(next_id.clone(), next);
+
+        info!("Created next occurrence of recurring task {}: {}", id, next_id);
+        Ok(Some(next_id))
+    }
+
+    /// Whether `task`'s series already holds the instance `next` would add: another
+    /// instance due at the same time or, for an undated series, one still open
+    fn series_continued(&self, task: &Task, next: &Task) -> bool {
+        self.stored_tasks().into_iter().any(|other| {
+            other.id != task.id
+                && other.series_id == next.series_id
+                && other.due_date == next.due_date
+                && (next.due_date.is_some() || !other.status.is_closed())
+        })
+    }
+
+    /// Move a task to the InProgress status.
+    pub fn start_task(&mut self, id: &str) -> Result<()> {
+        let now = self.now();
+        let task = self.transition(id, TaskStatus::InProgress)?;
+        task.start(now);
+        let task = task.clone();
+        self.journal(WalOp::Upsert { task: Box::new(task) })?;
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Started task: {}", id);
+        Ok(())
+    }
+
+    /// Move a task to the Waiting status while it is blocked on something external.
+    pub fn wait_task(&mut self, id: &str) -> Result<()> {
+        let now = self.now();
+        let task = self.transition(id, TaskStatus::Waiting)?;
+        task.wait(now);
+        let task = task.clone();
+        self.journal(WalOp::Upsert { task: Box::new(task) })?;
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Task is waiting: {}", id);
+        Ok(())
+    }
+
+    /// Move a task to the Cancelled status.
+    pub fn cancel_task(&mut self, id: &str) -> Result<()> {
+        let now = self.now();
+        let task = self.transition(id, TaskStatus::Cancelled)?;
+        task.cancel(now);
+        let task = task.clone();
+        self.journal(WalOp::Upsert { task: Box::new(task) })?;
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Cancelled task: {}", id);
+        Ok(())
+    }
+
+    /// Move a task to any status, with the same checks and side effects as the
+    /// dedicated command for that status.
+    ///
+    /// Moving to Done checks dependencies, records the completion time and continues a
+    /// recurring series, returning the next instance's ID. Moving a task back to Todo
+    /// clears its completion time.
+    pub fn set_status(&mut self, id: &str, status: TaskStatus) -> Result<Option<String>> {
+        match status {
+            TaskStatus::Done => return self.complete_task(id),
+            TaskStatus::InProgress => self.start_task(id)?,
+            TaskStatus::Waiting => self.wait_task(id)?,
+            TaskStatus::Cancelled => self.cancel_task(id)?,
+            TaskStatus::Todo => {
+                let now = self.now();
+                let task = self.transition(id, TaskStatus::Todo)?;
+                task.reopen(now);
+                let task = task.clone();
+                self.journal(WalOp::Upsert { task: Box::new(task) })?;
+                self.dirty.store(true, Ordering::Relaxed);
+                info!("Moved task back to todo: {}", id);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Look up a task for a status change, rejecting moves the lifecycle doesn't allow
+    fn transition(&mut self, id: &str, next: TaskStatus) -> Result<&mut Task> {
+        let task = self.get_task_mut(id)?;
+        if !task.status.can_transition_to(next) {
+            let message = if task.status == next {
+                format!("Task is already {}", next.label())
+            } else {
+                format!("Cannot move a {} task to {}; reopen it first", task.status.label(), next.label())
+            };
+            return Err(TaskError::OperationNotAllowed(message));
+        }
+        Ok(task)
+    }
+
+    /// Move a completed or cancelled task back to Todo.
+    ///
+    /// Returns an error if the task is still open.
+    pub fn reopen_task(&mut self, id: &str) -> Result<()> {
+        let now = self.now();
+        let task = self.get_task_mut(id)?;
+        if !task.status.is_closed() {
+            return Err(TaskError::OperationNotAllowed("Task is already open".to_string()));
+        }
+        task.reopen(now);
+        let task = task.clone();
+        self.journal(WalOp::Upsert { task: Box::new(task) })?;
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Reopened task: {}", id);
+        Ok(())
+    }
+
+    /// Get completed tasks whose completion time is after the given cutoff
+    pub fn get_tasks_completed_after(&self, cutoff: DateTime<Utc>) -> impl Iterator<Item = &Task> {
+        self.get_tasks_by_status(TaskStatus::Done)
+            .filter(move |task| task.completed_at.is_some_and(|done| done > cutoff))
+    }
+
+    /// Get tasks changed after the given time, archived ones included, for syncing
+    /// deltas to other systems
+    pub fn get_tasks_modified_since(&self, since: DateTime<Utc>) -> impl Iterator<Item = &Task> {
+        self.tasks.values().chain(self.archive.values()).filter(move |task| task.updated_at > since)
+    }
+
+    /// Reopen every task completed after the given cutoff.
+    ///
+    /// Returns the number of tasks reopened.
+    pub fn reopen_completed_after(&mut self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let ids: Vec<String> = self.get_tasks_completed_after(cutoff)
+            .map(|task| task.id.to_string())
+            .collect();
+        for id in &ids {
+            self.reopen_task(id)?;
+        }
+
+        info!("Reopened {} tasks completed after {}", ids.len(), cutoff);
+        Ok(ids.len())
+    }
+
+    /// Shift the due date of every matching task by `delta`.
+    ///
+    /// Tasks without a due date are skipped. Returns the number of tasks changed.
+    /// Returns `TaskError::ValidationError`, leaving every task untouched, if a shifted
+    /// date would fall outside the representable range.
+    pub fn shift_due_dates<F>(&mut self, predicate: F, delta: chrono::Duration) -> Result<usize>
+    where
+        F: Fn(&Task) -> bool,
+    {
+        let mut moves = Vec::new();
+        for task in self.tasks.values() {
+            let Some(due_date) = task.due_date else { continue };
+            if !predicate(task) {
+                continue;
+            }
+            let shifted = due_date.checked_add_signed(delta).ok_or_else(|| TaskError::ValidationError(format!(
+                "Shifting the due date of task {} would move it out of the supported date range", task.id
+            )))?;
+            moves.push((task.id, shifted));
+        }
+
+        let now = self.now();
+        let mut shifted = Vec::with_capacity(moves.len());
+        for (id, due_date) in moves {
+            let Some(task) = self.tasks.get_mut(&id.to_string()) else { continue };
+            let before = task.clone();
+            task.due_date = Some(due_date);
+            task.record_changes_since(&before, now);
+            task.updated_at = now;
+            shifted.push(task.clone());
+        }
+
+        let count = shifted.len();
+        for task in shifted {
+            self.journal_or_warn(WalOp::Upsert { task: Box::new(task) });
+        }
+        if count > 0 {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+
+        info!("Shifted due dates of {} tasks by {}", count, delta);
+        Ok(count)
+    }
+
+    /// Get open tasks that are overdue or due within `within` from now, soonest first.
+    ///
+    /// A window reaching past the supported date range covers every future due date.
+    pub fn get_reminders(&self, within: chrono::Duration) -> Vec<Reminder> {
+        let now = self.now();
+        let horizon = now.checked_add_signed(within).unwrap_or(if within < chrono::Duration::zero() {
+            DateTime::<Utc>::MIN_UTC
+        } else {
+            DateTime::<Utc>::MAX_UTC
+        });
+
+        let mut reminders: Vec<Reminder> = self.tasks.values()
+            .filter(|task| !task.status.is_closed())
+            .filter_map(|task| {
+                let due_date = task.due_date?;
+                let reason = if due_date < now {
+                    ReminderReason::Overdue
+                } else if due_date <= horizon {
+                    ReminderReason::DueSoon
+                } else {
+                    return None;
+                };
+                Some(Reminder { id: task.id, title: task.title.clone(), due_date, reason })
+            })
+            .collect();
+        reminders.sort_by_key(|r| r.due_date);
+        reminders
+    }
+
+    /// Open a new time-tracking interval on a task.
+    ///
+    /// Returns an error if the task already has a running interval.
+    pub fn start_time_entry(&mut self, id: &str) -> Result<()> {
+        let now = self.now();
+        let task = self.get_task_mut(id)?;
+        if task.running_time_entry().is_some() {
+            return Err(TaskError::OperationNotAllowed("Time tracking is already running for this task".to_string()));
+        }
+        task.time_entries.push(crate::task::TimeEntry { start: now, end: None });
+        task.updated_at = now;
+        let task = task.clone();
+        self.journal(WalOp::Upsert { task: Box::new(task) })?;
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Started time tracking on task: {}", id);
+        Ok(())
+    }
+
+    /// Close the running time-tracking interval on a task and return its length.
+    ///
+    /// Returns an error if no interval is running.
+    pub fn stop_time_entry(&mut self, id: &str) -> Result<chrono::Duration> {
+        let now = self.now();
+        let task = self.get_task_mut(id)?;
+        let entry = task.time_entries.iter_mut()
+            .find(|entry| entry.end.is_none())
+            .ok_or_else(|| TaskError::OperationNotAllowed(
+                "Time tracking is not running for this task; use `track start` first".to_string()
+            ))?;
+        entry.end = Some(now);
+        let elapsed = entry.duration(now);
+        task.updated_at = now;
+        let task = task.clone();
+        self.journal(WalOp::Upsert { task: Box::new(task) })?;
+        self.dirty.store(true, Ordering::Relaxed);
+
+        info!("Stopped time tracking on task: {}", id);
+        Ok(elapsed)
+    }
+
+    /// Get all tasks (immutable view)
+    pub fn get_all_tasks(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.values()
+    }
+
+    /// Get tasks filtered by status
+    pub fn get_tasks_by_status(&self, status: TaskStatus) -> impl Iterator<Item = &Task> {
+        self.tasks.values().filter(move |task| task.status == status)
+    }
+
+    /// Get tasks filtered by priority
+    pub fn get_tasks_by_priority(&self, priority: Priority) -> impl Iterator<Item = &Task> {
+        self.tasks.values().filter(move |task| task.priority == priority)
+    }
+
+    /// Get overdue tasks
+    pub fn get_overdue_tasks(&self) -> impl Iterator<Item = &Task> {
+        let now = self.now();
+        self.tasks.values().filter(move |task| task.is_overdue_at(now))
+    }
+
+    /// Suggest the task to work on next: a Todo task that `complete_task` would accept
+    /// because its dependencies are all done, preferring overdue tasks, then higher priority, then the earliest
+    /// due date, then the oldest.
+    pub fn suggest_next(&self) -> Option<&Task> {
+        let now = self.now();
+        self.tasks.values()
+            .filter(|task| task.status == TaskStatus::Todo)
+            .filter(|task| task.depends_on.iter().all(|dep| self.dependency_finished(dep)))
+            .min_by_key(|task| (
+                task.overdue_by(now).is_none(),
+                std::cmp::Reverse(task.priority),
+                task.due_date.is_none(),
+                task.due_date,
+                task.created_at,
+            ))
+    }
+
+    /// Returns true if `from` depends on `target`, directly or through other tasks
+    pub fn depends_transitively(&self, from: Uuid, target: Uuid) -> bool {
+        self.dependency_path(from, target).is_some()
+    }
+
+    /// Returns true if making `id` depend on `new_dep` would close a dependency cycle
+    pub fn has_dependency_cycle(&self, id: Uuid, new_dep: Uuid) -> bool {
+        id == new_dep || self.depends_transitively(new_dep, id)
+    }
+
+    /// Fail with the offending chain if any of `deps` would put `id` on a dependency cycle
+    fn check_dependency_cycles(&self, id: Uuid, deps: &[Uuid]) -> Result<()> {
+        for &dep in deps {
+            if !self.has_dependency_cycle(id, dep) {
+                continue;
+            }
+            let mut chain = vec![id];
+            chain.extend(self.dependency_path(dep, id).unwrap_or_else(|| vec![dep]));
+            let chain: Vec<String> = chain.iter().map(|id| id.to_string()[..8].to_string()).collect();
+            return Err(TaskError::OperationNotAllowed(format!("dependency cycle detected: {}", chain.join(" -> "))));
+        }
+        Ok(())
+    }
+
+    /// The chain of dependencies leading from `from` to `target`, both included
+    fn dependency_path(&self, from: Uuid, target: Uuid) -> Option<Vec<Uuid>> {
+        let mut came_from: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut seen = HashSet::new();
+        let mut stack = vec![from];
+        while let Some(current) = stack.pop() {
+            if !seen.insert(current) {
+                continue;
+            }
+            let Some(task) = self.tasks.get(&current.to_string()) else {
+                continue;
+            };
+            for &dep in &task.depends_on {
+                if dep == target {
+                    let mut path = vec![target, current];
+                    let mut node = current;
+                    while let Some(&previous) = came_from.get(&node) {
+                        path.push(previous);
+                        node = previous;
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                if dep != from {
+                    came_from.entry(dep).or_insert(current);
+                }
+                stack.push(dep);
+            }
+        }
+        None
+    }
+
+    /// Get the dependency edges `(task, dependency)` that lie on a cycle
+    pub fn dependency_cycle_edges(&self) -> HashSet<(Uuid, Uuid)> {
+        self.tasks.values()
+            .flat_map(|task| task.depends_on.iter().map(move |&dep| (task.id, dep)))
+            .filter(|&(task, dep)| task == dep || self.depends_transitively(dep, task))
+            .collect()
+    }
+
+    /// Get the tasks that list the given task as a dependency, oldest first
+    pub fn tasks_blocked_by(&self, id: &str) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.tasks.values()
+            .filter(|task| task.depends_on.iter().any(|dep| dep.to_string() == id))
+            .collect();
+        tasks.sort_by_key(|t| t.created_at);
+        tasks
+    }
+
+    /// Get every instance in the recurring series the given task belongs to, oldest first.
+    ///
+    /// A task that is not part of a series is returned on its own.
+    pub fn series_tasks(&self, id: &str) -> Result<Vec<&Task>> {
+        let task = self.get_task(id)?;
+        let Some(series_id) = task.series_id else {
+            return Ok(vec![task]);
+        };
+
+        let mut tasks: Vec<&Task> = self.tasks.values()
+            .filter(|t| t.series_id == Some(series_id))
+            .collect();
+        tasks.sort_by_key(|t| t.created_at);
+        Ok(tasks)
+    }
+
+    /// Search tasks by title or description
     pub fn search_tasks<'a>(&'a self, query: &'a str) -> impl Iterator<Item = &'a Task> {
         let query_lower = query.to_lowercase();
         self.tasks.values()
@@ -269,17 +1647,24 @@ impl TaskManager {
                 // However, we can avoid allocating if we use a better approach, but for now 
                 // lowercase the target and compare with query_lower.
                 task.title.to_lowercase().contains(&query_lower) ||
-                task.description.as_ref().map_or(false, |d| d.to_lowercase().contains(&query_lower))
+                task.description.as_ref().is_some_and(|d| d.to_lowercase().contains(&query_lower))
             })
     }
 
     /// Get tasks sorted by different criteria
     pub fn get_sorted_tasks(&self, sort_by: TaskSort) -> Vec<&Task> {
-        let mut tasks: Vec<&Task> = self.tasks.values().collect();
+        self.sort_tasks(self.tasks.values().collect(), sort_by)
+    }
+
+    /// Get archived tasks sorted by different criteria
+    pub fn get_sorted_archived(&self, sort_by: TaskSort) -> Vec<&Task> {
+        self.sort_tasks(self.archive.values().collect(), sort_by)
+    }
 
+    fn sort_tasks<'a>(&self, mut tasks: Vec<&'a Task>, sort_by: TaskSort) -> Vec<&'a Task> {
         match sort_by {
             TaskSort::CreatedAsc => tasks.sort_by_key(|t| t.created_at),
-            TaskSort::CreatedDesc => tasks.sort_by(|a, b| b.created_at.cmp(&a.created_at)),
+            TaskSort::CreatedDesc => tasks.sort_by_key(|t| std::cmp::Reverse(t.created_at)),
             TaskSort::DueDateAsc => tasks.sort_by_key(|t| t.due_date),
             TaskSort::DueDateDesc => tasks.sort_by(|a, b| {
                 match (a.due_date, b.due_date) {
@@ -290,81 +1675,623 @@ impl TaskManager {
                 }
             }),
             TaskSort::PriorityAsc => tasks.sort_by_key(|t| t.priority),
-            TaskSort::PriorityDesc => tasks.sort_by(|a, b| b.priority.cmp(&a.priority)),
+            TaskSort::PriorityDesc => tasks.sort_by_key(|t| std::cmp::Reverse(t.priority)),
             TaskSort::TitleAsc => tasks.sort_by(|a, b| a.title.cmp(&b.title)),
             TaskSort::TitleDesc => tasks.sort_by(|a, b| b.title.cmp(&a.title)),
+            TaskSort::OverdueAmount => {
+                // Evaluate against a single instant so the ordering is consistent
+                let now = self.now();
+                tasks.sort_by_cached_key(|t| std::cmp::Reverse(t.overdue_by(now)));
+            }
+            TaskSort::EstimateAsc => tasks.sort_by_key(|t| (t.estimate_minutes.is_none(), t.estimate_minutes)),
+            TaskSort::EstimateDesc => {
+                tasks.sort_by_key(|t| (t.estimate_minutes.is_none(), std::cmp::Reverse(t.estimate_minutes)));
+            }
+            TaskSort::Random { seed } => {
+                // Start from a stable order so a given seed always yields the same shuffle
+                tasks.sort_by_key(|t| (t.created_at, t.id));
+                let mut rng = match seed {
+                    Some(seed) => StdRng::seed_from_u64(seed),
+                    None => StdRng::from_os_rng(),
+                };
+                tasks.shuffle(&mut rng);
+            }
         }
 
         tasks
     }
 
+    /// Every distinct tag with the number of tasks carrying it, most used first
+    /// (ties in alphabetical order)
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for tag in self.tasks.values().flat_map(|task| &task.tags) {
+            *counts.entry(tag).or_default() += 1;
+        }
+        let mut counts: Vec<(String, usize)> = counts.into_iter().map(|(tag, n)| (tag.to_string(), n)).collect();
+        counts.sort_by(|(a_tag, a), (b_tag, b)| b.cmp(a).then_with(|| a_tag.cmp(b_tag)));
+        counts
+    }
+
+    /// Number of tasks at each priority, highest priority first, including empty levels
+    pub fn priority_counts(&self) -> Vec<(Priority, usize)> {
+        [Priority::Critical, Priority::High, Priority::Medium, Priority::Low, Priority::None]
+            .into_iter()
+            .map(|priority| (priority, self.get_tasks_by_priority(priority).count()))
+            .collect()
+    }
+
+    /// Get statistics per owner, with unowned tasks under "(unassigned)"
+    pub fn get_stats_by_owner(&self) -> HashMap<String, TaskStats> {
+        let now = self.now();
+        let mut by_owner: HashMap<String, Vec<&Task>> = HashMap::new();
+        for task in self.tasks.values() {
+            let owner = task.owner.clone().unwrap_or_else(|| UNASSIGNED_OWNER.to_string());
+            by_owner.entry(owner).or_default().push(task);
+        }
+        by_owner.into_iter()
+            .map(|(owner, tasks)| (owner, TaskStats::from_tasks(tasks, now)))
+            .collect()
+    }
+
+    /// Count completed tasks per UTC day from `since` through today, inclusive.
+    ///
+    /// Every day in the range is present, with zero for days without completions.
+    pub fn completions_by_day(&self, since: NaiveDate) -> Vec<(NaiveDate, usize)> {
+        let today = self.now().date_naive();
+        let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+        for completed_at in self.get_tasks_by_status(TaskStatus::Done).filter_map(|t| t.completed_at) {
+            *counts.entry(completed_at.date_naive()).or_default() += 1;
+        }
+
+        since.iter_days()
+            .take_while(|day| *day <= today)
+            .map(|day| (day, counts.get(&day).copied().unwrap_or(0)))
+            .collect()
+    }
+
     /// Get statistics about tasks
     pub fn get_stats(&self) -> TaskStats {
-        let total = self.tasks.len();
-        let completed = self.tasks.values().filter(|t| t.status == TaskStatus::Done).count();
-        let in_progress = self.tasks.values().filter(|t| t.status == TaskStatus::InProgress).count();
-        let overdue = self.get_overdue_tasks().count();
+        TaskStats::from_tasks(self.tasks.values(), self.now())
+    }
 
-        TaskStats {
-            total,
-            completed,
-            in_progress,
-            overdue,
-            completion_rate: if total > 0 { (completed as f64 / total as f64) * 100.0 } else { 0.0 },
-        }
+    /// Statistics about active and archived tasks together
+    pub fn get_stats_including_archived(&self) -> TaskStats {
+        TaskStats::from_tasks(self.tasks.values().chain(self.archive.values()), self.now())
     }
 
-    /// Clear all completed tasks from memory and set the dirty flag.
+    /// Move all completed tasks to the trash and set the dirty flag.
     ///
     /// Returns the number of tasks removed.
     pub fn clear_completed(&mut self) -> usize {
-        let initial_count = self.tasks.len();
-        self.tasks.retain(|_, task| task.status != TaskStatus::Done);
-        let removed = initial_count - self.tasks.len();
-        self.dirty.store(true, Ordering::Relaxed);
+        let completed: Vec<String> = self.get_tasks_by_status(TaskStatus::Done)
+            .map(|task| task.id.to_string())
+            .collect();
+        let removed = self.trash_all(completed);
 
-        info!("Cleared {} completed tasks", removed);
+        info!("Moved {} completed tasks to the trash", removed);
         removed
     }
 
-    /// Clear all tasks from memory and set the dirty flag.
+    /// Move all tasks to the trash and set the dirty flag.
     ///
     /// Returns the number of tasks removed.
     pub fn clear_all(&mut self) -> usize {
-        let count = self.tasks.len();
-        self.tasks.clear();
+        let ids: Vec<String> = self.tasks.keys().cloned().collect();
+        let removed = self.trash_all(ids);
+
+        info!("Moved all {} tasks to the trash", removed);
+        removed
+    }
+
+    /// Move each listed task to the trash, logging rather than failing on journal errors
+    fn trash_all(&mut self, ids: Vec<String>) -> usize {
+        let mut removed = Vec::new();
+        for id in &ids {
+            if let Some(task) = self.tasks.remove(id) {
+                self.journal_or_warn(WalOp::Trash { id: id.clone() });
+                removed.push(task.id);
+                self.trash.insert(id.clone(), task);
+            }
+        }
+        if let Err(e) = self.drop_dependencies_on(&removed) {
+            warn!("Failed to append to write-ahead log: {}", e);
+        }
         self.dirty.store(true, Ordering::Relaxed);
+        ids.len()
+    }
 
-        info!("Cleared all {} tasks", count);
-        count
+    /// Import tasks from a list, resolving IDs already present in memory with `strategy`.
+    ///
+    /// All imported tasks are re-validated before insertion. With `dedupe` set, tasks
+    /// that logically duplicate an existing (or earlier imported) task are skipped too.
+    /// If any task is rejected, nothing is imported.
+    pub fn import_tasks(&mut self, tasks: Vec<Task>, dedupe: Option<ImportDedupe>, strategy: ImportStrategy) -> Result<ImportSummary> {
+        let mut run = self.start_import(dedupe, strategy);
+        let result = tasks.into_iter().try_for_each(|task| self.import_one(task, &mut run));
+        if let Err(e) = result {
+            self.roll_back_import(run)?;
+            return Err(e);
+        }
+        self.finish_import(run)
     }
 
-    /// Import tasks from a list, skipping any that have IDs already present in memory.
+    /// Import tasks from a JSON array read incrementally from `reader`.
     ///
-    /// All imported tasks are re-validated before insertion.
-    pub fn import_tasks(&mut self, tasks: Vec<Task>) -> Result<usize> {
-        let mut imported_count = 0;
-        for task in tasks {
-            // Validate the task
-            task.validate().map_err(TaskError::from_validation_errors)?;
+    /// Tasks are validated and inserted one at a time as they are parsed, so memory
+    /// use does not grow with the size of the input. Rules match `import_tasks`; tasks
+    /// inserted before a later one fails to parse or validate are removed again.
+    pub fn import_reader<R: Read>(&mut self, reader: R, dedupe: Option<ImportDedupe>, strategy: ImportStrategy) -> Result<ImportSummary> {
+        let mut run = self.start_import(dedupe, strategy);
+        if let Err(e) = for_each_json_array_item(reader, |task: Task| self.import_one(task, &mut run)) {
+            self.roll_back_import(run)?;
+            return Err(e);
+        }
+        self.finish_import(run)
+    }
+
+    fn start_import(&self, dedupe: Option<ImportDedupe>, strategy: ImportStrategy) -> ImportRun {
+        ImportRun {
+            dedupe,
+            strategy,
+            titles: self.title_index(dedupe),
+            summary: ImportSummary::default(),
+            inserted: Vec::new(),
+            replaced: Vec::new(),
+            unresolved: Vec::new(),
+        }
+    }
 
-            // Skip if task with this ID already exists
-            if !self.tasks.contains_key(&task.id.to_string()) {
-                self.tasks.insert(task.id.to_string(), task);
-                imported_count += 1;
+    /// Validate and insert one imported task, skipping duplicate titles and handling
+    /// known IDs according to the import strategy
+    fn import_one(&mut self, mut task: Task, run: &mut ImportRun) -> Result<()> {
+        task.validate_with(&self.config.validation_limits).map_err(TaskError::from_validation_errors)?;
+
+        let mut renamed = false;
+        if let Some(existing) = self.stored_task(&task.id.to_string()) {
+            match run.strategy {
+                ImportStrategy::Skip => {
+                    run.summary.skipped_existing += 1;
+                    return Ok(());
+                }
+                ImportStrategy::Merge => {
+                    let mut merged = existing.clone();
+                    merged.merge(task);
+                    self.import_replacement(merged, run)?;
+                    run.summary.merged += 1;
+                    return Ok(());
+                }
+                ImportStrategy::Overwrite => {
+                    self.import_replacement(task, run)?;
+                    run.summary.overwritten += 1;
+                    return Ok(());
+                }
+                ImportStrategy::RenameDuplicates => {
+                    task.id = Uuid::new_v4();
+                    renamed = true;
+                }
+            }
+        } else if self.trash.contains_key(&task.id.to_string()) {
+            match run.strategy {
+                ImportStrategy::Skip => {
+                    run.summary.skipped_existing += 1;
+                    return Ok(());
+                }
+                ImportStrategy::Merge | ImportStrategy::Overwrite => {
+                    return Err(TaskError::OperationNotAllowed(format!(
+                        "Task {} is in the trash; restore it or empty the trash before importing it", task.id
+                    )));
+                }
+                ImportStrategy::RenameDuplicates => {
+                    task.id = Uuid::new_v4();
+                    renamed = true;
+                }
             }
         }
+        let id = task.id.to_string();
+        if run.dedupe == Some(ImportDedupe::Title) && !run.titles.insert(normalize_title(&task.title)) {
+            run.summary.skipped_duplicate_title += 1;
+            return Ok(());
+        }
 
-        if imported_count > 0 {
-            self.dirty.store(true, Ordering::Relaxed);
+        self.check_dependency_cycles(task.id, &task.depends_on)?;
+
+        // Dependencies may refer to tasks later in the same import; check them at the end
+        run.unresolved.extend(
+            task.depends_on.iter()
+                .filter(|dep| self.stored_task(&dep.to_string()).is_none())
+                .map(|&dep| (task.id, dep)),
+        );
+
+        self.journal(WalOp::Upsert { task: Box::new(task.clone()) })?;
+        self.insert_stored(task);
+        self.dirty.store(true, Ordering::Relaxed);
+        run.inserted.push(id);
+        match renamed {
+            true => run.summary.renamed += 1,
+            false => run.summary.imported += 1,
         }
+        Ok(())
+    }
+
+    /// Store an imported or merged task in place of an existing one with the same ID,
+    /// remembering the original so a rejected import can restore it
+    fn import_replacement(&mut self, task: Task, run: &mut ImportRun) -> Result<()> {
+        task.validate_with(&self.config.validation_limits).map_err(TaskError::from_validation_errors)?;
+        self.check_dependency_cycles(task.id, &task.depends_on)?;
+        run.unresolved.extend(
+            task.depends_on.iter()
+                .filter(|dep| self.stored_task(&dep.to_string()).is_none())
+                .map(|&dep| (task.id, dep)),
+        );
 
-        info!("Imported {} tasks", imported_count);
-        Ok(imported_count)
+        self.journal(WalOp::Upsert { task: Box::new(task.clone()) })?;
+        let id = task.id.to_string();
+        let original = self.tasks.remove(&id).or_else(|| self.archive.remove(&id));
+        self.insert_stored(task);
+        if let Some(original) = original {
+            // Only the state before the import's first change to the task is worth restoring
+            if !run.inserted.contains(&original.id.to_string()) && !run.replaced.iter().any(|t| t.id == original.id) {
+                run.replaced.push(original);
+            }
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+        Ok(())
     }
-}
 
-/// Sorting options for tasks
+    /// Reject the import, undoing everything it inserted or merged, if any dependency is still unknown
+    fn finish_import(&mut self, run: ImportRun) -> Result<ImportSummary> {
+        let dangling: Vec<String> = run.unresolved.iter()
+            .filter(|(_, dep)| self.stored_task(&dep.to_string()).is_none())
+            .map(|(task, dep)| format!("task {} depends on unknown task {}", task, dep))
+            .collect();
+
+        if !dangling.is_empty() {
+            self.roll_back_import(run)?;
+            return Err(TaskError::ValidationError(format!("Import rejected: {}", dangling.join("; "))));
+        }
+
+        info!("Import finished: {}", run.summary);
+        Ok(run.summary)
+    }
+
+    /// Undo everything an import inserted or merged so far
+    fn roll_back_import(&mut self, run: ImportRun) -> Result<()> {
+        for id in run.inserted {
+            self.tasks.remove(&id);
+            self.archive.remove(&id);
+            self.journal(WalOp::Delete { id })?;
+        }
+        for original in run.replaced {
+            self.journal(WalOp::Upsert { task: Box::new(original.clone()) })?;
+            self.insert_stored(original);
+        }
+        Ok(())
+    }
+
+    /// Preview an import without inserting anything.
+    ///
+    /// Applies the same ID and de-duplication rules as `import_tasks`, but collects
+    /// every validation error instead of stopping at the first one.
+    pub fn preview_import(&self, tasks: &[Task], dedupe: Option<ImportDedupe>, strategy: ImportStrategy) -> ImportPreview {
+        let mut preview = ImportPreview { total: tasks.len(), ..Default::default() };
+        let mut titles = self.title_index(dedupe);
+        let mut seen_ids = HashSet::new();
+        let file_ids: HashSet<Uuid> = tasks.iter().map(|t| t.id).collect();
+
+        for (index, task) in tasks.iter().enumerate() {
+            if let Err(errors) = task.validate_with(&self.config.validation_limits) {
+                let message = TaskError::from_validation_errors(errors);
+                preview.errors.push(format!("task #{} ({}): {}", index + 1, task.id, message));
+                continue;
+            }
+            let dangling: Vec<String> = task.depends_on.iter()
+                .filter(|dep| !file_ids.contains(dep) && self.stored_task(&dep.to_string()).is_none())
+                .map(Uuid::to_string)
+                .collect();
+            if !dangling.is_empty() {
+                preview.errors.push(format!(
+                    "task #{} ({}): depends on unknown task(s) {}", index + 1, task.id, dangling.join(", ")
+                ));
+                continue;
+            }
+            let in_trash = self.trash.contains_key(&task.id.to_string());
+            if in_trash && matches!(strategy, ImportStrategy::Merge | ImportStrategy::Overwrite) {
+                preview.errors.push(format!("task #{} ({}): is in the trash", index + 1, task.id));
+                continue;
+            }
+            if in_trash || self.stored_task(&task.id.to_string()).is_some() || !seen_ids.insert(task.id) {
+                match strategy {
+                    ImportStrategy::Skip => preview.summary.skipped_existing += 1,
+                    ImportStrategy::Merge => preview.summary.merged += 1,
+                    ImportStrategy::Overwrite => preview.summary.overwritten += 1,
+                    ImportStrategy::RenameDuplicates => preview.summary.renamed += 1,
+                }
+                continue;
+            }
+            if dedupe == Some(ImportDedupe::Title) && !titles.insert(normalize_title(&task.title)) {
+                preview.summary.skipped_duplicate_title += 1;
+                continue;
+            }
+            preview.summary.imported += 1;
+        }
+
+        preview
+    }
+
+    /// Normalized titles of existing tasks, when de-duplicating by title
+    fn title_index(&self, dedupe: Option<ImportDedupe>) -> HashSet<String> {
+        match dedupe {
+            Some(ImportDedupe::Title) => self.tasks.values().map(|t| normalize_title(&t.title)).collect(),
+            None => HashSet::new(),
+        }
+    }
+}
+
+/// Owner bucket for tasks without an owner in per-owner statistics
+pub const UNASSIGNED_OWNER: &str = "(unassigned)";
+
+/// Pass each element of the JSON array in `reader` to `handle` as soon as it is parsed.
+///
+/// Stops at the first error from `handle` and returns it unchanged.
+pub fn for_each_json_array_item<R, T, F>(reader: R, handle: F) -> Result<()>
+where
+    R: Read,
+    T: DeserializeOwned,
+    F: FnMut(T) -> Result<()>,
+{
+    struct ItemVisitor<T, F> {
+        handle: F,
+        failure: Option<TaskError>,
+        item: PhantomData<T>,
+    }
+
+    impl<'de, T: DeserializeOwned, F: FnMut(T) -> Result<()>> Visitor<'de> for &mut ItemVisitor<T, F> {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a JSON array")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+            while let Some(item) = seq.next_element::<T>()? {
+                if let Err(e) = (self.handle)(item) {
+                    self.failure = Some(e);
+                    return Err(A::Error::custom("stopped by handler"));
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let mut visitor = ItemVisitor { handle, failure: None, item: PhantomData };
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let outcome = deserializer.deserialize_seq(&mut visitor);
+    if let Some(e) = visitor.failure {
+        return Err(e);
+    }
+    outcome?;
+    deserializer.end()?;
+    Ok(())
+}
+
+/// Read the complete tasks at the start of a JSON array that was cut off partway,
+/// e.g. by an interrupted write.
+///
+/// Returns the tasks and the byte offset just past the last one read; anything after
+/// that offset could not be parsed.
+fn recover_json_tasks(data: &str) -> (Vec<Task>, usize) {
+    let mut tasks = Vec::new();
+    let Some(start) = data.find('[') else {
+        return (tasks, 0);
+    };
+
+    let mut position = start + 1;
+    let mut readable = position;
+    loop {
+        let rest = &data[position..];
+        let skipped = rest.len() - rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',').len();
+        position += skipped;
+        if data[position..].starts_with(']') {
+            return (tasks, position + 1);
+        }
+
+        let mut items = serde_json::Deserializer::from_str(&data[position..]).into_iter::<Task>();
+        match items.next() {
+            Some(Ok(task)) => {
+                position += items.byte_offset();
+                readable = position;
+                tasks.push(task);
+            }
+            _ => return (tasks, readable),
+        }
+    }
+}
+
+/// Whether `path` names a gzipped file (`.gz`)
+pub fn is_gzip_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+/// Whether `data` starts with the gzip magic bytes
+fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&[0x1f, 0x8b])
+}
+
+fn gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+fn gunzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut plain = Vec::new();
+    flate2::read::GzDecoder::new(data).read_to_end(&mut plain)?;
+    Ok(plain)
+}
+
+/// The task in `tasks` whose ID is `prefix` or starts with it; see `TaskManager::get_task_by_prefix`
+fn find_by_prefix<'a>(tasks: &'a HashMap<String, Task>, prefix: &str) -> Result<&'a Task> {
+    if let Some(task) = tasks.get(prefix) {
+        return Ok(task);
+    }
+    crate::task::validate_id(prefix)?;
+    // A complete UUID in another form (e.g. without hyphens) names exactly one task
+    if let Ok(id) = Uuid::parse_str(prefix.trim()) {
+        return tasks.get(&id.to_string()).ok_or_else(|| TaskError::TaskNotFound(prefix.to_string()));
+    }
+    let needle = prefix.trim().to_ascii_lowercase();
+
+    let mut matches: Vec<&Task> = tasks.values().filter(|task| task.id.to_string().starts_with(&needle)).collect();
+    match matches.len() {
+        0 => Err(TaskError::TaskNotFound(prefix.to_string())),
+        1 => Ok(matches[0]),
+        _ => {
+            matches.sort_by_key(|task| task.id);
+            Err(TaskError::AmbiguousId {
+                prefix: prefix.to_string(),
+                candidates: matches.iter().map(|task| format!("{} ({})", task.id, task.title)).collect(),
+            })
+        }
+    }
+}
+
+/// Replace `path` with `data` by writing a `.tmp` sibling (e.g. `tasks.json.tmp`),
+/// flushing it to disk and renaming it over the target.
+///
+/// The rename is atomic on the same filesystem, so readers see either the old file or
+/// the complete new one. The temporary file is removed if any step fails.
+async fn write_atomically(path: &Path, data: &[u8]) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut tmp_path = path.to_path_buf().into_os_string();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let written = async {
+        let mut file = fs::File::create(&tmp_path).await?;
+        file.write_all(data).await?;
+        file.sync_all().await?;
+        fs::rename(&tmp_path, path).await
+    }
+    .await;
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp_path).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Hash serialized task data to detect saves that would not change the file
+fn content_hash(data: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Normalize a title for duplicate detection: case-insensitive, whitespace-collapsed
+fn normalize_title(title: &str) -> String {
+    title.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Extra duplicate detection applied during import, beyond matching task IDs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportDedupe {
+    /// Treat tasks with the same normalized title as duplicates
+    Title,
+}
+
+/// What an import does with a task whose ID already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImportStrategy {
+    /// Keep the stored task and ignore the imported one
+    #[default]
+    Skip,
+    /// Combine both copies with `Task::merge`
+    Merge,
+    /// Replace the stored task with the imported one
+    Overwrite,
+    /// Keep both, inserting the imported task under a fresh ID
+    RenameDuplicates,
+}
+
+/// Result of compacting the storage file
+#[derive(Debug, Clone)]
+pub struct CompactReport {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    /// Cancelled tasks dropped during compaction
+    pub removed: usize,
+}
+
+/// Outcome counts of an import
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// Tasks merged into an existing task with the same ID
+    pub merged: usize,
+    /// Tasks that replaced an existing task with the same ID
+    pub overwritten: usize,
+    /// Tasks inserted under a fresh ID because theirs was taken
+    pub renamed: usize,
+    pub skipped_existing: usize,
+    pub skipped_duplicate_title: usize,
+}
+
+impl ImportSummary {
+    /// Tasks in the import, whatever happened to them
+    pub fn total(&self) -> usize {
+        self.imported + self.merged + self.overwritten + self.renamed + self.skipped_existing + self.skipped_duplicate_title
+    }
+}
+
+impl std::fmt::Display for ImportSummary {
+    /// Per-outcome counts, e.g. "imported 5, overwrote 2, skipped 1"; outcomes that
+    /// didn't happen are left out
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "imported {}", self.imported)?;
+        let outcomes = [
+            ("merged", self.merged, ""),
+            ("overwrote", self.overwritten, ""),
+            ("renamed", self.renamed, ""),
+            ("skipped", self.skipped_existing, ""),
+            ("skipped", self.skipped_duplicate_title, " duplicate titles"),
+        ];
+        for (verb, count, suffix) in outcomes {
+            if count > 0 {
+                write!(f, ", {} {}{}", verb, count, suffix)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bookkeeping for an import in progress
+struct ImportRun {
+    dedupe: Option<ImportDedupe>,
+    strategy: ImportStrategy,
+    titles: HashSet<String>,
+    summary: ImportSummary,
+    /// IDs inserted so far, removed again if the import is rejected
+    inserted: Vec<String>,
+    /// Tasks as they were before being merged into, restored if the import is rejected
+    replaced: Vec<Task>,
+    /// (task, dependency) pairs whose dependency was unknown when the task was inserted
+    unresolved: Vec<(Uuid, Uuid)>,
+}
+
+/// What an import would do, computed without modifying any tasks
+#[derive(Debug, Clone, Default)]
+pub struct ImportPreview {
+    /// Number of tasks in the import file
+    pub total: usize,
+    /// Counts as `import_tasks` would report them; `imported` means "would be new"
+    pub summary: ImportSummary,
+    /// Validation errors for individual tasks
+    pub errors: Vec<String>,
+}
+
+/// Sorting options for tasks
 #[derive(Debug, Clone, Copy)]
 pub enum TaskSort {
     CreatedAsc,
@@ -375,16 +2302,105 @@ pub enum TaskSort {
     PriorityDesc,
     TitleAsc,
     TitleDesc,
+    /// Most overdue first; tasks that aren't overdue come last
+    OverdueAmount,
+    /// Smallest estimate first; tasks without one come last
+    EstimateAsc,
+    /// Largest estimate first; tasks without one come last
+    EstimateDesc,
+    /// Shuffled order; non-deterministic unless a seed is given
+    Random { seed: Option<u64> },
+}
+
+/// Why a task appears in the reminders output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderReason {
+    Overdue,
+    DueSoon,
+}
+
+/// A task needing attention, in the stable machine-readable reminders schema
+#[derive(Debug, Clone, Serialize)]
+pub struct Reminder {
+    pub id: uuid::Uuid,
+    pub title: String,
+    pub due_date: DateTime<Utc>,
+    pub reason: ReminderReason,
 }
 
 /// Statistics about tasks
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TaskStats {
     pub total: usize,
     pub completed: usize,
     pub in_progress: usize,
+    pub waiting: usize,
+    pub cancelled: usize,
     pub overdue: usize,
     pub completion_rate: f64,
+    /// Estimated and tracked time on tasks still to be done
+    pub open_effort: Effort,
+    /// Estimated and tracked time on completed tasks
+    pub completed_effort: Effort,
+}
+
+/// Estimated versus tracked time over a group of tasks, in minutes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Effort {
+    pub estimated_minutes: u64,
+    pub tracked_minutes: u64,
+}
+
+impl Effort {
+    fn add(&mut self, task: &Task, now: DateTime<Utc>) {
+        self.estimated_minutes += u64::from(task.estimate_minutes.unwrap_or(0));
+        self.tracked_minutes += task.total_tracked(now).num_minutes().max(0) as u64;
+    }
+
+    /// Whether any task in the group has an estimate or tracked time
+    pub fn is_empty(&self) -> bool {
+        self.estimated_minutes == 0 && self.tracked_minutes == 0
+    }
+}
+
+impl TaskStats {
+    /// Compute statistics over any set of tasks, such as a loaded snapshot,
+    /// counting tasks overdue as of `now`
+    pub fn from_tasks<'a>(tasks: impl IntoIterator<Item = &'a Task>, now: DateTime<Utc>) -> Self {
+        let mut stats = TaskStats {
+            total: 0, completed: 0, in_progress: 0, waiting: 0, cancelled: 0, overdue: 0, completion_rate: 0.0,
+            open_effort: Effort::default(), completed_effort: Effort::default(),
+        };
+        for task in tasks {
+            stats.total += 1;
+            match task.status {
+                TaskStatus::Done => stats.completed += 1,
+                TaskStatus::InProgress => stats.in_progress += 1,
+                TaskStatus::Waiting => stats.waiting += 1,
+                TaskStatus::Cancelled => stats.cancelled += 1,
+                TaskStatus::Todo => {}
+            }
+            match task.status {
+                TaskStatus::Done => stats.completed_effort.add(task, now),
+                TaskStatus::Cancelled => {}
+                _ => stats.open_effort.add(task, now),
+            }
+            if task.is_overdue_at(now) {
+                stats.overdue += 1;
+            }
+        }
+
+        if stats.total > 0 {
+            stats.completion_rate = (stats.completed as f64 / stats.total as f64) * 100.0;
+        }
+        stats
+    }
+
+    /// Tasks still to be done: neither completed nor cancelled
+    pub fn outstanding(&self) -> usize {
+        self.total - self.completed - self.cancelled
+    }
 }
 
 #[cfg(test)]
@@ -409,6 +2425,30 @@ mod tests {
         assert_eq!(task.status, TaskStatus::Todo);
     }
 
+    #[test]
+    fn test_get_task_by_prefix() {
+        let mut manager = TaskManager::new();
+        for (id, title) in [("abcd1234-0000-4000-8000-000000000001", "First"), ("abcd5678-0000-4000-8000-000000000002", "Second")] {
+            let mut task = Task::new(title.to_string());
+            task.id = uuid::Uuid::parse_str(id).unwrap();
+            manager.tasks.insert(id.to_string(), task);
+        }
+
+        assert_eq!(manager.get_task_by_prefix("abcd1234").unwrap().title, "First");
+        assert_eq!(manager.get_task_by_prefix("ABCD5").unwrap().title, "Second");
+        assert_eq!(manager.get_task_by_prefix("abcd5678-0000-4000-8000-000000000002").unwrap().title, "Second");
+        match manager.get_task_by_prefix("abcd") {
+            Err(TaskError::AmbiguousId { candidates, .. }) => {
+                assert_eq!(candidates, ["abcd1234-0000-4000-8000-000000000001 (First)", "abcd5678-0000-4000-8000-000000000002 (Second)"]);
+            }
+            other => panic!("expected an ambiguous ID, got {:?}", other.map(|task| &task.title)),
+        }
+        assert!(matches!(manager.get_task_by_prefix("ffff"), Err(TaskError::TaskNotFound(_))));
+        assert!(matches!(manager.get_task_by_prefix("abcd1234000040008000000000000001"), Ok(task) if task.title == "First"));
+        assert!(matches!(manager.get_task_by_prefix(""), Err(TaskError::InvalidId(_))));
+        assert!(matches!(manager.get_task_by_prefix("first"), Err(TaskError::InvalidId(_))));
+    }
+
     #[test]
     fn test_task_completion() {
         let mut manager = TaskManager::new();
@@ -445,4 +2485,1282 @@ mod tests {
         assert_eq!(stats.completed, 1);
         assert_eq!(stats.completion_rate, 50.0);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_wal_replays_unsaved_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            wal_enabled: true,
+            ..Default::default()
+        };
+
+        let mut manager = TaskManager::with_config(config.clone());
+        let saved_id = manager.add_task("Saved".to_string()).unwrap();
+        manager.save().await.unwrap();
+        assert_eq!(std::fs::read_to_string(config.wal_path()).unwrap(), "");
+
+        // Simulate a crash after these mutations but before the next save
+        let unsaved_id = manager.add_task("Unsaved".to_string()).unwrap();
+        manager.complete_task(&saved_id).unwrap();
+
+        let mut recovered = TaskManager::with_config(config);
+        recovered.load().await.unwrap();
+        assert_eq!(recovered.tasks.len(), 2);
+        assert_eq!(recovered.get_task(&saved_id).unwrap().status, TaskStatus::Done);
+        assert!(recovered.get_task(&unsaved_id).is_ok());
+        assert!(recovered.dirty.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_deleting_a_task_drops_dependencies_on_it() {
+        let mut manager = TaskManager::new();
+        let first = manager.add_task("First".to_string()).unwrap();
+        let second = manager.add_task("Second".to_string()).unwrap();
+        let dependent = manager.add_task_detailed(NewTask {
+            title: "Dependent".to_string(),
+            depends_on: vec![first.clone(), second.clone()],
+            ..Default::default()
+        }).unwrap();
+
+        manager.delete_task(&first).unwrap();
+        assert_eq!(manager.get_task(&dependent).unwrap().depends_on, vec![Uuid::parse_str(&second).unwrap()]);
+        manager.clear_all();
+        manager.restore_from_trash(&dependent).unwrap();
+        assert!(manager.get_task(&dependent).unwrap().depends_on.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deleted_tasks_go_to_the_trash_and_can_be_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            ..Default::default()
+        };
+
+        let mut manager = TaskManager::with_config(config.clone());
+        let parent = manager.add_task("Parent".to_string()).unwrap();
+        let child = manager.add_task_detailed(NewTask {
+            title: "Child".to_string(),
+            parent: Some(parent.clone()),
+            ..Default::default()
+        }).unwrap();
+        manager.delete_task_cascade(&parent).unwrap();
+        assert!(manager.tasks.is_empty());
+        manager.save().await.unwrap();
+        assert!(config.trash_path().exists());
+
+        let mut reloaded = TaskManager::with_config(config.clone());
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.trashed_tasks().len(), 2);
+        assert_eq!(reloaded.get_trashed_by_prefix(&child[..8]).unwrap().title, "Child");
+
+        // A subtask waits for its parent
+        assert!(matches!(reloaded.restore_from_trash(&child), Err(TaskError::OperationNotAllowed(_))));
+        reloaded.restore_from_trash(&parent).unwrap();
+        reloaded.restore_from_trash(&child).unwrap();
+        assert_eq!(reloaded.get_task(&child).unwrap().parent_id.map(|p| p.to_string()), Some(parent.clone()));
+
+        reloaded.clear_all();
+        assert_eq!(reloaded.empty_trash().unwrap(), 2);
+        reloaded.save().await.unwrap();
+        assert!(!config.trash_path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_archive_keeps_finished_tasks_in_the_data_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            ..Default::default()
+        };
+
+        let mut manager = TaskManager::with_config(config.clone());
+        let done = manager.add_task("Done".to_string()).unwrap();
+        manager.complete_task(&done).unwrap();
+        let open = manager.add_task("Open".to_string()).unwrap();
+        let parent = manager.add_task("Closed parent".to_string()).unwrap();
+        manager.add_task_detailed(NewTask {
+            title: "Open child".to_string(),
+            parent: Some(parent.clone()),
+            ..Default::default()
+        }).unwrap();
+        manager.cancel_task(&parent).unwrap();
+
+        // The cancelled parent stays while its subtask is open
+        assert_eq!(manager.archive_completed(), 1);
+        assert!(manager.get_task(&done).is_err());
+        assert!(manager.get_task(&parent).is_ok());
+        assert_eq!(manager.get_stats().total, 3);
+        assert_eq!(manager.get_stats_including_archived().total, 4);
+        manager.save().await.unwrap();
+
+        let mut reloaded = TaskManager::with_config(config);
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.tasks.len(), 3);
+        assert!(reloaded.get_archived_by_prefix(&done[..8]).unwrap().archived_at.is_some());
+
+        reloaded.unarchive(&done).unwrap();
+        assert!(reloaded.get_task(&done).unwrap().archived_at.is_none());
+        assert!(matches!(reloaded.unarchive(&open), Err(TaskError::OperationNotAllowed(_))));
+        assert!(matches!(reloaded.unarchive(&Uuid::new_v4().to_string()), Err(TaskError::TaskNotFound(_))));
+        assert!(reloaded.archive.is_empty());
+    }
+
+    #[test]
+    fn test_import_treats_archived_and_trashed_ids_as_existing() {
+        let mut manager = TaskManager::new();
+        let done = manager.add_task("Done".to_string()).unwrap();
+        manager.add_task_detailed(NewTask { title: "Next".to_string(), depends_on: vec![done.clone()], ..Default::default() }).unwrap();
+        let older_copy = manager.get_task(&done).unwrap().clone();
+        let deleted = manager.add_task("Deleted".to_string()).unwrap();
+        let deleted_copy = manager.get_task(&deleted).unwrap().clone();
+        manager.complete_task(&done).unwrap();
+        manager.archive_completed();
+        manager.delete_task(&deleted).unwrap();
+
+        // A full export carries the archived task, so its dependents import cleanly
+        let exported: Vec<Task> = manager.stored_tasks().into_iter().cloned().collect();
+        let mut fresh = TaskManager::new();
+        assert_eq!(fresh.import_tasks(exported, None, ImportStrategy::Skip).unwrap().imported, 2);
+        assert_eq!(fresh.tasks.len(), 1);
+        assert!(fresh.archive.contains_key(&done));
+
+        let summary = manager.import_tasks(vec![older_copy, deleted_copy.clone()], None, ImportStrategy::Skip).unwrap();
+        assert_eq!(summary.skipped_existing, 2);
+        assert_eq!(manager.stored_tasks().len(), 2);
+        assert!(matches!(
+            manager.import_tasks(vec![deleted_copy], None, ImportStrategy::Overwrite),
+            Err(TaskError::OperationNotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_preview_update_leaves_task_untouched() {
+        let mut manager = TaskManager::new();
+        let id = manager.add_task("Original".to_string()).unwrap();
+
+        let ok = manager.preview_update(&id, TaskUpdate {
+            title: Some("Renamed".to_string()),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(ok.title, "Renamed");
+
+        let err = manager.preview_update(&id, TaskUpdate {
+            title: Some(String::new()),
+            ..Default::default()
+        });
+        assert!(matches!(err, Err(TaskError::ValidationError(_))));
+
+        assert_eq!(manager.get_task(&id).unwrap().title, "Original");
+        assert!(manager.update_task(&id, TaskUpdate { title: Some(String::new()), ..Default::default() }).is_err());
+        assert_eq!(manager.get_task(&id).unwrap().title, "Original");
+    }
+
+    #[tokio::test]
+    async fn test_storage_path_is_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        let mut manager = TaskManager::with_config(config);
+        let err = manager.load().await.unwrap_err();
+        assert!(matches!(err, TaskError::FileOperationError(ref msg) if msg.starts_with("storage path is a directory")));
+
+        manager.add_task("Task".to_string()).unwrap();
+        let err = manager.save().await.unwrap_err();
+        assert!(matches!(err, TaskError::FileOperationError(ref msg) if msg.starts_with("storage path is a directory")));
+    }
+
+    #[tokio::test]
+    async fn test_write_atomically_replaces_file_and_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+        std::fs::write(&path, "old").unwrap();
+
+        write_atomically(&path, b"new").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert!(!dir.path().join("tasks.json.tmp").exists());
+
+        // Renaming over a directory fails; the temp file must not be left behind
+        let blocked = dir.path().join("blocked");
+        std::fs::create_dir_all(blocked.join("inner")).unwrap();
+        assert!(write_atomically(&blocked, b"data").await.is_err());
+        assert!(!dir.path().join("blocked.tmp").exists());
+        assert!(blocked.join("inner").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_save_keeps_newest_backups_and_restores_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = Arc::new(crate::clock::MockClock::new(start));
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            clock: clock.clone(),
+            backups: 2,
+            ..Default::default()
+        };
+
+        // The first save has no file to back up; each later one backs up the previous state
+        let mut manager = TaskManager::with_config(config.clone());
+        for title in ["One", "Two", "Three", "Four"] {
+            clock.advance(chrono::Duration::minutes(1));
+            manager.add_task(title.to_string()).unwrap();
+            manager.save().await.unwrap();
+        }
+
+        let backups = manager.list_backups().await.unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0].taken_at, start + chrono::Duration::minutes(4));
+        assert_eq!(backups[0].path, dir.path().join("tasks.json.bak-20240301T090400.000Z"));
+
+        // The newest backup was taken just before "Four" was saved
+        assert_eq!(manager.restore_backup(&backups[0].path).await.unwrap(), 3);
+        assert!(manager.get_all_tasks().all(|task| task.title != "Four"));
+        clock.advance(chrono::Duration::minutes(1));
+        manager.save().await.unwrap();
+
+        let mut reloaded = TaskManager::with_config(config);
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.get_all_tasks().count(), 3);
+        assert_eq!(reloaded.list_backups().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_passphrase_encrypts_existing_plain_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            backups: 0,
+            ..Default::default()
+        };
+        let mut manager = TaskManager::with_config(config.clone());
+        manager.add_task("Private".to_string()).unwrap();
+        manager.save().await.unwrap();
+
+        // Loading a plain file with a passphrase rewrites it encrypted on the next save
+        let encrypted = TaskManagerConfig { passphrase: Some(Passphrase::new("hunter2".to_string())), ..config.clone() };
+        let mut manager = TaskManager::with_config(encrypted.clone());
+        manager.load().await.unwrap();
+        manager.save().await.unwrap();
+        assert!(crypto::file_is_encrypted(&config.storage_path));
+
+        let mut reloaded = TaskManager::with_config(encrypted);
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.get_all_tasks().next().unwrap().title, "Private");
+
+        let mut without = TaskManager::with_config(config);
+        assert!(matches!(without.load().await, Err(TaskError::EncryptionError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_compressed_storage_is_detected_and_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            backups: 0,
+            ..Default::default()
+        };
+        let mut manager = TaskManager::with_config(config.clone());
+        for n in 0..50 {
+            manager.add_task(format!("Repetitive task {}", n)).unwrap();
+        }
+        manager.save().await.unwrap();
+        let plain_size = std::fs::metadata(&config.storage_path).unwrap().len();
+
+        // --compress converts an existing plain file on the next save
+        let mut manager = TaskManager::with_config(TaskManagerConfig { compress: true, ..config.clone() });
+        manager.load().await.unwrap();
+        manager.save().await.unwrap();
+        let stored = std::fs::read(&config.storage_path).unwrap();
+        assert!(is_gzip(&stored));
+        assert!((stored.len() as u64) < plain_size / 2);
+
+        // Later runs detect the compression without the flag and keep it
+        let mut manager = TaskManager::with_config(config.clone());
+        manager.load().await.unwrap();
+        assert_eq!(manager.get_all_tasks().count(), 50);
+        manager.add_task("One more".to_string()).unwrap();
+        manager.save().await.unwrap();
+        assert!(is_gzip(&std::fs::read(&config.storage_path).unwrap()));
+
+        assert_eq!(StorageFormat::from_path(Path::new("tasks.yaml.gz")), Some(StorageFormat::Yaml));
+        assert_eq!(StorageFormat::from_path(Path::new("tasks.json.GZ")), Some(StorageFormat::Json));
+    }
+
+    #[tokio::test]
+    async fn test_save_skips_unchanged_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            ..Default::default()
+        };
+
+        let mut manager = TaskManager::with_config(config.clone());
+        let id = manager.add_task("Stable".to_string()).unwrap();
+        manager.save().await.unwrap();
+        let written = std::fs::read_to_string(&config.storage_path).unwrap();
+
+        let mut reloaded = TaskManager::with_config(config.clone());
+        reloaded.load().await.unwrap();
+
+        // Setting a field to its current value marks the manager dirty without changing content
+        let title = reloaded.get_task(&id).unwrap().title.clone();
+        reloaded.get_task_mut(&id).unwrap().title = title;
+        reloaded.dirty.store(true, Ordering::Relaxed);
+        std::fs::write(&config.storage_path, "sentinel").unwrap();
+        reloaded.save().await.unwrap();
+        assert_eq!(std::fs::read_to_string(&config.storage_path).unwrap(), "sentinel");
+
+        reloaded.get_task_mut(&id).unwrap().title = "Changed".to_string();
+        reloaded.save().await.unwrap();
+        assert_ne!(std::fs::read_to_string(&config.storage_path).unwrap(), written);
+    }
+
+    #[tokio::test]
+    async fn test_no_op_update_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            ..Default::default()
+        };
+        let mut manager = TaskManager::with_config(config.clone());
+        let id = manager.add_task("Stable".to_string()).unwrap();
+        manager.save().await.unwrap();
+        manager.update_task(&id, TaskUpdate { title: Some("Saved once".to_string()), ..Default::default() }).unwrap();
+        manager.save().await.unwrap();
+
+        let mut reloaded = TaskManager::with_config(config.clone());
+        reloaded.load().await.unwrap();
+        let before = reloaded.get_task(&id).unwrap().clone();
+        let modified = std::fs::metadata(&config.storage_path).unwrap().modified().unwrap();
+        let backups = reloaded.list_backups().await.unwrap().len();
+
+        let same = TaskUpdate { title: Some(before.title.clone()), priority: Some(before.priority), ..Default::default() };
+        reloaded.update_task(&id, same).unwrap();
+        assert!(!reloaded.dirty.load(Ordering::Relaxed));
+        assert_eq!(reloaded.get_task(&id).unwrap(), &before);
+
+        reloaded.save().await.unwrap();
+        assert_eq!(std::fs::metadata(&config.storage_path).unwrap().modified().unwrap(), modified);
+        assert_eq!(reloaded.list_backups().await.unwrap().len(), backups);
+    }
+
+    #[tokio::test]
+    async fn test_yaml_and_json_storage_round_trip_identically() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut source = TaskManager::new();
+        let parent = source.add_task_detailed(NewTask {
+            title: "Plan: \"launch\" #1".to_string(),
+            description: Some("Line one\nline two".to_string()),
+            priority: Some(Priority::High),
+            category: Some("work".to_string()),
+            due_date: Some(DateTime::parse_from_rfc3339("2024-05-01T12:30:00Z").unwrap().with_timezone(&Utc)),
+            links: vec!["https://example.com/a?b=c".to_string()],
+            tags: vec!["yes".to_string(), "123".to_string()],
+            ..Default::default()
+        }).unwrap();
+        source.add_task_detailed(NewTask { title: "Child".to_string(), parent: Some(parent), ..Default::default() }).unwrap();
+        source.add_task("null".to_string()).unwrap();
+
+        let mut loaded = Vec::new();
+        for (name, format) in [("tasks.json", StorageFormat::Json), ("tasks.yaml", StorageFormat::Yaml)] {
+            let config = TaskManagerConfig {
+                storage_path: dir.path().join(name),
+                storage_format: format,
+                ..Default::default()
+            };
+            let mut manager = TaskManager::with_config(config.clone());
+            manager.tasks = source.tasks.clone();
+            manager.dirty.store(true, Ordering::Relaxed);
+            manager.save().await.unwrap();
+
+            let mut reloaded = TaskManager::with_config(config);
+            reloaded.load().await.unwrap();
+            let mut tasks: Vec<Task> = reloaded.tasks.into_values().collect();
+            tasks.sort_by_key(|task| (task.created_at, task.id));
+            loaded.push(serde_json::to_value(&tasks).unwrap());
+        }
+
+        assert!(std::fs::read_to_string(dir.path().join("tasks.yaml")).unwrap().contains("title: Child"));
+        assert_eq!(loaded[0], loaded[1]);
+        assert_eq!(loaded[0].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_recover_loads_complete_tasks_from_truncated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            ..Default::default()
+        };
+        let mut manager = TaskManager::with_config(config.clone());
+        for title in ["One", "Two", "Three"] {
+            manager.add_task(title.to_string()).unwrap();
+        }
+        manager.save().await.unwrap();
+
+        // Cut the file off partway through the last task
+        let data = std::fs::read_to_string(&config.storage_path).unwrap();
+        std::fs::write(&config.storage_path, &data[..data.len() - 40]).unwrap();
+
+        let mut strict = TaskManager::with_config(config.clone());
+        assert!(matches!(strict.load().await, Err(TaskError::JsonError(_))));
+
+        let mut recovering = TaskManager::with_config(TaskManagerConfig { recover: true, ..config.clone() });
+        recovering.load().await.unwrap();
+        assert_eq!(recovering.tasks.len(), 2);
+        assert!(dir.path().join("tasks.json.corrupt").exists());
+
+        recovering.save().await.unwrap();
+        let mut reloaded = TaskManager::with_config(config);
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.tasks.len(), 2);
+
+        assert_eq!(recover_json_tasks("[]").0.len(), 0);
+        assert_eq!(recover_json_tasks("garbage").1, 0);
+    }
+
+    #[test]
+    fn test_storage_format_from_path() {
+        assert_eq!(StorageFormat::from_path(Path::new("tasks.yml")), Some(StorageFormat::Yaml));
+        assert_eq!(StorageFormat::from_path(Path::new("tasks.YAML")), Some(StorageFormat::Yaml));
+        assert_eq!(StorageFormat::from_path(Path::new("tasks.json")), Some(StorageFormat::Json));
+        assert_eq!(StorageFormat::from_path(Path::new("tasks")), None);
+    }
+
+    #[tokio::test]
+    async fn test_compact_drops_old_cancelled_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskManagerConfig {
+            storage_path: dir.path().join("tasks.json"),
+            ..Default::default()
+        };
+
+        let mut manager = TaskManager::with_config(config.clone());
+        let kept = manager.add_task("Kept".to_string()).unwrap();
+        let old = manager.add_task("Old cancelled".to_string()).unwrap();
+        let recent = manager.add_task("Recent cancelled".to_string()).unwrap();
+        manager.cancel_task(&old).unwrap();
+        manager.cancel_task(&recent).unwrap();
+        manager.get_task_mut(&old).unwrap().updated_at = Utc::now() - chrono::Duration::days(60);
+        manager.save().await.unwrap();
+
+        let report = manager.compact(Some(Utc::now() - chrono::Duration::days(30))).await.unwrap();
+        assert_eq!(report.removed, 1);
+        assert!(report.bytes_after < report.bytes_before);
+
+        let mut reloaded = TaskManager::with_config(config);
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.tasks.len(), 2);
+        assert!(reloaded.get_task(&kept).is_ok());
+        assert!(reloaded.get_task(&recent).is_ok());
+        assert!(reloaded.get_task(&old).is_err());
+    }
+
+    #[test]
+    fn test_tasks_blocked_by() {
+        let mut manager = TaskManager::new();
+        let blocker = manager.add_task("Blocker".to_string()).unwrap();
+        let dependent = manager.add_task("Dependent".to_string()).unwrap();
+        manager.add_task("Unrelated".to_string()).unwrap();
+
+        let blocker_uuid = manager.get_task(&blocker).unwrap().id;
+        manager.get_task_mut(&dependent).unwrap().depends_on.push(blocker_uuid);
+
+        let blocked = manager.tasks_blocked_by(&blocker);
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].title, "Dependent");
+        assert!(manager.tasks_blocked_by(&dependent).is_empty());
+    }
+
+    #[test]
+    fn test_append_description() {
+        let mut manager = TaskManager::new();
+        let id = manager.add_task("Investigate".to_string()).unwrap();
+
+        manager.append_description(&id, "First finding").unwrap();
+        assert_eq!(manager.get_task(&id).unwrap().description.as_deref(), Some("First finding"));
+        manager.append_description(&id, "Second finding").unwrap();
+        assert_eq!(
+            manager.get_task(&id).unwrap().description.as_deref(),
+            Some("First finding\nSecond finding")
+        );
+
+        let too_long = "x".repeat(2000);
+        assert!(manager.append_description(&id, &too_long).is_err());
+        assert_eq!(
+            manager.get_task(&id).unwrap().description.as_deref(),
+            Some("First finding\nSecond finding")
+        );
+    }
+
+    #[test]
+    fn test_add_note_appends_and_validates() {
+        let mut manager = TaskManager::new();
+        let id = manager.add_task("Investigate".to_string()).unwrap();
+        let before = manager.get_task(&id).unwrap().updated_at;
+
+        manager.add_note(&id, "Reproduced locally".to_string()).unwrap();
+        manager.add_note(&id, "Root cause found".to_string()).unwrap();
+        let task = manager.get_task(&id).unwrap();
+        let texts: Vec<&str> = task.notes.iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(texts, ["Reproduced locally", "Root cause found"]);
+        assert!(task.updated_at >= before);
+        assert!(manager.dirty.load(Ordering::Relaxed));
+
+        assert!(manager.add_note(&id, String::new()).is_err());
+        assert!(manager.add_note(&id, "x".repeat(ValidationLimits::default().note_max + 1)).is_err());
+        assert_eq!(manager.get_task(&id).unwrap().notes.len(), 2);
+    }
+
+    #[test]
+    fn test_stats_by_owner() {
+        let mut manager = TaskManager::new();
+        for (title, owner) in [("A", Some("ana")), ("B", Some("ana")), ("C", Some("bo")), ("D", None)] {
+            let id = manager.add_task(title.to_string()).unwrap();
+            manager.get_task_mut(&id).unwrap().owner = owner.map(str::to_string);
+            if title == "B" {
+                manager.complete_task(&id).unwrap();
+            }
+        }
+
+        let stats = manager.get_stats_by_owner();
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats["ana"].total, 2);
+        assert_eq!(stats["ana"].outstanding(), 1);
+        assert_eq!(stats["ana"].completion_rate, 50.0);
+        assert_eq!(stats["bo"].outstanding(), 1);
+        assert_eq!(stats[UNASSIGNED_OWNER].total, 1);
+    }
+
+    #[test]
+    fn test_unprioritized_tasks_sort_below_low() {
+        let mut manager = TaskManager::new();
+        let none = manager.add_task("Someday".to_string()).unwrap();
+        manager.get_task_mut(&none).unwrap().priority = Priority::None;
+        let low = manager.add_task("Low".to_string()).unwrap();
+        manager.get_task_mut(&low).unwrap().priority = Priority::Low;
+        manager.add_task("Medium".to_string()).unwrap();
+
+        let titles: Vec<&str> = manager.get_sorted_tasks(TaskSort::PriorityDesc).iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Medium", "Low", "Someday"]);
+        assert_eq!(manager.get_tasks_by_priority(Priority::None).count(), 1);
+        let counts = manager.priority_counts();
+        assert_eq!(counts.first(), Some(&(Priority::Critical, 0)));
+        assert_eq!(counts.iter().map(|(_, n)| n).sum::<usize>(), manager.tasks.len());
+    }
+
+    #[test]
+    fn test_merge_tasks() {
+        let mut manager = TaskManager::new();
+        let keep = manager.add_task("Keep".to_string()).unwrap();
+        let from = manager.add_task_detailed(NewTask {
+            title: "Duplicate".to_string(),
+            tags: vec!["x".to_string()],
+            ..Default::default()
+        }).unwrap();
+        let dependent = manager.add_task_detailed(NewTask {
+            title: "Dependent".to_string(),
+            depends_on: vec![from.clone(), keep.clone()],
+            ..Default::default()
+        }).unwrap();
+        let child = manager.add_task_detailed(NewTask {
+            title: "Child".to_string(),
+            parent: Some(from.clone()),
+            ..Default::default()
+        }).unwrap();
+
+        assert!(matches!(manager.merge_tasks(&keep, &keep), Err(TaskError::OperationNotAllowed(_))));
+        assert!(matches!(manager.merge_tasks(&keep, &Uuid::new_v4().to_string()), Err(TaskError::TaskNotFound(_))));
+
+        manager.merge_tasks(&keep, &from).unwrap();
+        let keep_uuid = Uuid::parse_str(&keep).unwrap();
+        assert!(manager.get_task(&from).is_err());
+        assert_eq!(manager.get_task(&keep).unwrap().tags, vec!["x"]);
+        assert_eq!(manager.get_task(&dependent).unwrap().depends_on, vec![keep_uuid]);
+        assert_eq!(manager.get_task(&child).unwrap().parent_id, Some(keep_uuid));
+
+        // A merge that would close a dependency loop leaves both tasks alone
+        let blocker = manager.add_task_detailed(NewTask {
+            title: "Blocker".to_string(),
+            depends_on: vec![keep.clone()],
+            ..Default::default()
+        }).unwrap();
+        let waiting = manager.add_task_detailed(NewTask {
+            title: "Waiting".to_string(),
+            depends_on: vec![blocker.clone()],
+            ..Default::default()
+        }).unwrap();
+        assert!(matches!(manager.merge_tasks(&keep, &waiting), Err(TaskError::OperationNotAllowed(_))));
+        assert!(manager.get_task(&waiting).is_ok());
+        assert!(manager.get_task(&keep).unwrap().depends_on.is_empty());
+    }
+
+    #[test]
+    fn test_dependencies_block_completion() {
+        let mut manager = TaskManager::new();
+        let first = manager.add_task("Design".to_string()).unwrap();
+        let second = manager.add_task_detailed(NewTask {
+            title: "Build".to_string(),
+            depends_on: vec![first.clone()],
+            ..Default::default()
+        }).unwrap();
+
+        let missing = manager.add_task_detailed(NewTask {
+            title: "Ship".to_string(),
+            depends_on: vec![Uuid::new_v4().to_string()],
+            ..Default::default()
+        });
+        assert!(matches!(missing, Err(TaskError::TaskNotFound(_))));
+
+        match manager.complete_task(&second) {
+            Err(TaskError::OperationNotAllowed(message)) => assert!(message.contains(&first)),
+            other => panic!("expected completion to be blocked, got {:?}", other),
+        }
+        manager.complete_task(&first).unwrap();
+        manager.complete_task(&second).unwrap();
+    }
+
+    #[test]
+    fn test_truncated_import_inserts_nothing() {
+        let mut manager = TaskManager::new();
+        let existing = manager.add_task("Existing".to_string()).unwrap();
+
+        let batch = vec![Task::new("First".to_string()), Task::new("Second".to_string())];
+        let data = serde_json::to_string(&batch).unwrap();
+        let truncated = &data[..data.len() - 20];
+        assert!(manager.import_reader(truncated.as_bytes(), None, ImportStrategy::Skip).is_err());
+        assert_eq!(manager.tasks.len(), 1);
+        assert!(manager.get_task(&existing).is_ok());
+
+        let mut invalid = Task::new("Fine".to_string());
+        invalid.title = String::new();
+        assert!(manager.import_tasks(vec![Task::new("Kept out".to_string()), invalid], None, ImportStrategy::Skip).is_err());
+        assert_eq!(manager.tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_import_rejects_dangling_dependencies() {
+        let mut manager = TaskManager::new();
+        let first = Task::new("First".to_string());
+        let mut second = Task::new("Second".to_string());
+        second.depends_on.push(first.id);
+
+        // A dependency later in the same file is fine
+        let data = serde_json::to_vec(&vec![second.clone(), first.clone()]).unwrap();
+        assert_eq!(manager.import_reader(data.as_slice(), None, ImportStrategy::Skip).unwrap().imported, 2);
+
+        let mut dangling = Task::new("Dangling".to_string());
+        dangling.depends_on.push(Uuid::new_v4());
+        let fine = Task::new("Fine".to_string());
+        let batch = vec![fine, dangling];
+        assert_eq!(manager.preview_import(&batch, None, ImportStrategy::Skip).errors.len(), 1);
+
+        let err = manager.import_tasks(batch, None, ImportStrategy::Skip).unwrap_err();
+        assert!(err.to_string().contains("depends on unknown task"));
+        assert_eq!(manager.tasks.len(), 2);
+    }
+
+    #[test]
+    fn test_dependency_cycles_are_rejected() {
+        let mut manager = TaskManager::new();
+        let a = manager.add_task("A".to_string()).unwrap();
+        let b = manager.add_task_detailed(NewTask { title: "B".to_string(), depends_on: vec![a.clone()], ..Default::default() }).unwrap();
+        let c = manager.add_task_detailed(NewTask { title: "C".to_string(), depends_on: vec![b.clone()], ..Default::default() }).unwrap();
+        let uuid = |id: &str| Uuid::parse_str(id).unwrap();
+        let depend = |deps: Vec<Uuid>| TaskUpdate { depends_on: Some(deps), ..Default::default() };
+
+        // Self-dependency
+        assert!(manager.has_dependency_cycle(uuid(&a), uuid(&a)));
+        assert!(matches!(manager.update_task(&a, depend(vec![uuid(&a)])), Err(TaskError::OperationNotAllowed(_))));
+
+        // Two-node cycle: A -> B -> A
+        match manager.update_task(&a, depend(vec![uuid(&b)])) {
+            Err(TaskError::OperationNotAllowed(message)) => {
+                assert_eq!(message, format!("dependency cycle detected: {} -> {} -> {}", &a[..8], &b[..8], &a[..8]));
+            }
+            other => panic!("expected a cycle error, got {:?}", other),
+        }
+
+        // Longer chain: A -> C -> B -> A
+        match manager.update_task(&a, depend(vec![uuid(&c)])) {
+            Err(TaskError::OperationNotAllowed(message)) => {
+                assert_eq!(message, format!("dependency cycle detected: {} -> {} -> {} -> {}", &a[..8], &c[..8], &b[..8], &a[..8]));
+            }
+            other => panic!("expected a cycle error, got {:?}", other),
+        }
+        assert!(manager.get_task(&a).unwrap().depends_on.is_empty());
+
+        // Depending on an unrelated chain is fine
+        let d = manager.add_task("D".to_string()).unwrap();
+        manager.update_task(&d, depend(vec![uuid(&c)])).unwrap();
+
+        // Cycles inside an import are caught when the closing task arrives
+        let mut x = Task::new("X".to_string());
+        let mut y = Task::new("Y".to_string());
+        x.depends_on.push(y.id);
+        y.depends_on.push(x.id);
+        assert!(matches!(manager.import_tasks(vec![x, y], None, ImportStrategy::Skip), Err(TaskError::OperationNotAllowed(_))));
+    }
+
+    #[test]
+    fn test_subtasks() {
+        let mut manager = TaskManager::new();
+        let parent = manager.add_task("Launch".to_string()).unwrap();
+        let child = manager.add_task_detailed(NewTask {
+            title: "Write announcement".to_string(),
+            parent: Some(parent.clone()),
+            ..Default::default()
+        }).unwrap();
+        let grandchild = manager.add_task_detailed(NewTask {
+            title: "Draft".to_string(),
+            parent: Some(child.clone()),
+            ..Default::default()
+        }).unwrap();
+        manager.add_task("Unrelated".to_string()).unwrap();
+
+        let missing = manager.add_task_detailed(NewTask {
+            title: "Orphan".to_string(),
+            parent: Some(Uuid::new_v4().to_string()),
+            ..Default::default()
+        });
+        assert!(matches!(missing, Err(TaskError::TaskNotFound(_))));
+
+        let children: Vec<String> = manager.get_children(&parent).unwrap().iter().map(|t| t.id.to_string()).collect();
+        assert_eq!(children, vec![child.clone()]);
+
+        let subtree: Vec<String> = manager.get_subtree(&parent).unwrap().iter().map(Uuid::to_string).collect();
+        assert_eq!(subtree, vec![parent.clone(), child.clone(), grandchild.clone()]);
+
+        assert!(matches!(manager.delete_task(&parent), Err(TaskError::OperationNotAllowed(_))));
+        let removed = manager.delete_task_cascade(&parent).unwrap();
+        assert_eq!(removed.len(), 3);
+        assert!(manager.get_task(&grandchild).is_err());
+        assert_eq!(manager.tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_suggest_next() {
+        let mut manager = TaskManager::new();
+        assert!(manager.suggest_next().is_none());
+
+        let low = manager.add_task("Low".to_string()).unwrap();
+        manager.get_task_mut(&low).unwrap().priority = Priority::Low;
+        let high = manager.add_task("High".to_string()).unwrap();
+        manager.get_task_mut(&high).unwrap().priority = Priority::High;
+        assert_eq!(manager.suggest_next().unwrap().title, "High");
+
+        // Unfinished dependencies and non-Todo statuses rule a task out
+        let low_id = manager.get_task(&low).unwrap().id;
+        manager.get_task_mut(&high).unwrap().depends_on.push(low_id);
+        assert_eq!(manager.suggest_next().unwrap().title, "Low");
+        manager.start_task(&low).unwrap();
+        assert!(manager.suggest_next().is_none());
+
+        manager.complete_task(&low).unwrap();
+        assert_eq!(manager.suggest_next().unwrap().title, "High");
+    }
+
+    #[test]
+    fn test_cancelled_dependency_blocks_both_next_and_complete() {
+        let mut manager = TaskManager::new();
+        let dropped = manager.add_task("Dropped".to_string()).unwrap();
+        let dependent = manager.add_task("Dependent".to_string()).unwrap();
+        let dropped_id = manager.get_task(&dropped).unwrap().id;
+        manager.get_task_mut(&dependent).unwrap().depends_on.push(dropped_id);
+        manager.cancel_task(&dropped).unwrap();
+
+        assert_eq!(manager.unfinished_dependencies(&dependent).unwrap(), vec![dropped_id]);
+        assert!(manager.suggest_next().is_none());
+        assert!(matches!(manager.complete_task(&dependent), Err(TaskError::OperationNotAllowed(_))));
+    }
+
+    #[test]
+    fn test_dependency_cycle_edges() {
+        let mut manager = TaskManager::new();
+        let a = manager.add_task("A".to_string()).unwrap();
+        let b = manager.add_task("B".to_string()).unwrap();
+        let c = manager.add_task("C".to_string()).unwrap();
+        let (a_id, b_id, c_id) = (
+            manager.get_task(&a).unwrap().id,
+            manager.get_task(&b).unwrap().id,
+            manager.get_task(&c).unwrap().id,
+        );
+
+        // A -> B -> A is a cycle; C -> A is not part of it
+        manager.get_task_mut(&a).unwrap().depends_on.push(b_id);
+        manager.get_task_mut(&b).unwrap().depends_on.push(a_id);
+        manager.get_task_mut(&c).unwrap().depends_on.push(a_id);
+
+        assert!(manager.depends_transitively(c_id, b_id));
+        assert!(!manager.depends_transitively(a_id, c_id));
+
+        let cycles = manager.dependency_cycle_edges();
+        assert_eq!(cycles.len(), 2);
+        assert!(cycles.contains(&(a_id, b_id)));
+        assert!(cycles.contains(&(b_id, a_id)));
+    }
+
+    #[test]
+    fn test_add_and_remove_link() {
+        let mut manager = TaskManager::new();
+        let id = manager.add_task("Review PR".to_string()).unwrap();
+
+        manager.add_link(&id, "https://example.com/pr/42".to_string()).unwrap();
+        assert!(matches!(
+            manager.add_link(&id, "https://example.com/pr/42".to_string()),
+            Err(TaskError::OperationNotAllowed(_))
+        ));
+        assert_eq!(manager.get_task(&id).unwrap().links.len(), 1);
+
+        manager.remove_link(&id, "https://example.com/pr/42").unwrap();
+        assert!(manager.get_task(&id).unwrap().links.is_empty());
+        assert!(matches!(
+            manager.remove_link(&id, "https://example.com/pr/42"),
+            Err(TaskError::OperationNotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_stats_from_snapshot_tasks() {
+        let mut done = Task::new("Done".to_string());
+        done.complete(Utc::now());
+        let mut started = Task::new("Started".to_string());
+        started.start(Utc::now());
+        let snapshot = [done, started, Task::new("Todo".to_string())];
+
+        let stats = TaskStats::from_tasks(&snapshot, Utc::now());
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.completed, 1);
+        assert_eq!(stats.in_progress, 1);
+        assert_eq!(stats.overdue, 0);
+    }
+
+    #[test]
+    fn test_import_dedupe_on_title() {
+        let mut manager = TaskManager::new();
+        manager.add_task("Write report".to_string()).unwrap();
+
+        let incoming = vec![
+            Task::new("  write   REPORT ".to_string()),
+            Task::new("Plan offsite".to_string()),
+            Task::new("plan offsite".to_string()),
+        ];
+
+        let summary = manager.import_tasks(incoming.clone(), Some(ImportDedupe::Title), ImportStrategy::Skip).unwrap();
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.skipped_duplicate_title, 2);
+
+        let summary = manager.import_tasks(incoming, None, ImportStrategy::Skip).unwrap();
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.skipped_existing, 1);
+    }
+
+    #[test]
+    fn test_import_merge_unions_tags_and_rolls_back_on_rejection() {
+        let mut manager = TaskManager::new();
+        let id = manager.add_task_detailed(NewTask {
+            title: "Stored".to_string(),
+            tags: vec!["home".to_string()],
+            ..Default::default()
+        }).unwrap();
+        let stored = manager.get_task(&id).unwrap().clone();
+
+        let mut incoming = stored.clone();
+        incoming.title = "Renamed elsewhere".to_string();
+        incoming.tags = vec!["urgent".to_string()];
+        incoming.updated_at = stored.updated_at + chrono::Duration::minutes(5);
+
+        let preview = manager.preview_import(std::slice::from_ref(&incoming), None, ImportStrategy::Merge);
+        assert_eq!((preview.summary.merged, preview.summary.skipped_existing), (1, 0));
+
+        // A dangling dependency elsewhere in the file rejects the import and restores the stored task
+        let mut dangling = Task::new("Dangling".to_string());
+        dangling.depends_on = vec![Uuid::new_v4()];
+        let err = manager.import_tasks(vec![incoming.clone(), dangling], None, ImportStrategy::Merge).unwrap_err();
+        assert!(matches!(err, TaskError::ValidationError(_)));
+        assert_eq!(manager.get_task(&id).unwrap().title, "Stored");
+        assert_eq!(manager.get_task(&id).unwrap().tags, vec!["home"]);
+
+        let summary = manager.import_tasks(vec![incoming], None, ImportStrategy::Merge).unwrap();
+        assert_eq!((summary.merged, summary.imported, summary.skipped_existing), (1, 0, 0));
+        let merged = manager.get_task(&id).unwrap();
+        assert_eq!(merged.title, "Renamed elsewhere");
+        assert_eq!(merged.tags, vec!["home", "urgent"]);
+    }
+
+    #[test]
+    fn test_import_overwrite_and_rename_duplicates() {
+        let mut manager = TaskManager::new();
+        let id = manager.add_task("Stored".to_string()).unwrap();
+        let mut incoming = manager.get_task(&id).unwrap().clone();
+        incoming.title = "From backup".to_string();
+        let batch = vec![incoming, Task::new("New".to_string())];
+
+        let summary = manager.import_tasks(batch.clone(), None, ImportStrategy::Overwrite).unwrap();
+        assert_eq!((summary.imported, summary.overwritten), (1, 1));
+        assert_eq!(summary.to_string(), "imported 1, overwrote 1");
+        assert_eq!(manager.get_task(&id).unwrap().title, "From backup");
+
+        manager.get_task_mut(&id).unwrap().title = "Stored".to_string();
+        let summary = manager.import_tasks(batch, None, ImportStrategy::RenameDuplicates).unwrap();
+        assert_eq!((summary.imported, summary.renamed), (0, 2));
+        assert_eq!(summary.to_string(), "imported 0, renamed 2");
+        assert_eq!(manager.get_task(&id).unwrap().title, "Stored");
+        let mut titles: Vec<&str> = manager.get_all_tasks().map(|t| t.title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, vec!["From backup", "New", "New", "Stored"]);
+
+        let skipped = ImportSummary { imported: 5, overwritten: 2, skipped_existing: 1, ..Default::default() };
+        assert_eq!(skipped.to_string(), "imported 5, overwrote 2, skipped 1");
+        assert_eq!(skipped.total(), 8);
+    }
+
+    #[test]
+    fn test_completing_recurring_task_continues_series() {
+        let mut manager = TaskManager::new();
+        let id = manager.add_task_detailed(NewTask {
+            title: "Water plants".to_string(),
+            recurrence: Some(crate::task::Recurrence::Weekly),
+            ..Default::default()
+        }).unwrap();
+
+        let next_id = manager.complete_task(&id).unwrap().expect("next instance");
+        let second_next = manager.complete_task(&next_id).unwrap().expect("next instance");
+
+        let series = manager.series_tasks(&second_next).unwrap();
+        assert_eq!(series.len(), 3);
+        assert_eq!(series.iter().filter(|t| t.status == TaskStatus::Done).count(), 2);
+        assert!(series.iter().all(|t| t.series_id == manager.get_task(&id).unwrap().series_id));
+    }
+
+    #[test]
+    fn test_recompleting_a_reopened_recurring_task_does_not_duplicate_the_next_instance() {
+        let mut manager = TaskManager::new();
+        let dated = manager.add_task_detailed(NewTask {
+            title: "Pay rent".to_string(),
+            due_date: Some(Utc::now()),
+            recurrence: Some(crate::task::Recurrence::Monthly),
+            ..Default::default()
+        }).unwrap();
+        let undated = manager.add_task_detailed(NewTask {
+            title: "Water plants".to_string(),
+            recurrence: Some(crate::task::Recurrence::Weekly),
+            ..Default::default()
+        }).unwrap();
+
+        for id in [&dated, &undated] {
+            let next_id = manager.complete_task(id).unwrap().expect("next instance");
+            manager.reopen_task(id).unwrap();
+            assert_eq!(manager.complete_task(id).unwrap(), None);
+            assert_eq!(manager.series_tasks(id).unwrap().len(), 2);
+
+            assert!(manager.complete_task(&next_id).unwrap().is_some());
+            assert_eq!(manager.series_tasks(id).unwrap().len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_mock_clock_drives_timestamps_and_overdue() {
+        let start = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = Arc::new(crate::clock::MockClock::new(start));
+        let mut manager = TaskManager::with_config(TaskManagerConfig { clock: clock.clone(), ..Default::default() });
+
+        let id = manager.add_task_detailed(NewTask {
+            title: "Standup".to_string(),
+            due_date: Some(start + chrono::Duration::days(1)),
+            recurrence: Some(crate::task::Recurrence::Daily),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(manager.get_task(&id).unwrap().created_at, start);
+        assert_eq!(manager.get_overdue_tasks().count(), 0);
+
+        clock.advance(chrono::Duration::days(2));
+        assert_eq!(manager.get_overdue_tasks().count(), 1);
+        assert_eq!(manager.get_stats().overdue, 1);
+
+        let next_id = manager.complete_task(&id).unwrap().expect("next instance");
+        let done = manager.get_task(&id).unwrap();
+        assert_eq!(done.completed_at, Some(clock.now()));
+        let next = manager.get_task(&next_id).unwrap();
+        assert_eq!(next.created_at, clock.now());
+        assert_eq!(next.due_date, Some(start + chrono::Duration::days(2)));
+        assert_eq!(manager.get_overdue_tasks().count(), 0);
+    }
+
+    #[test]
+    fn test_tasks_modified_since() {
+        let start = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = Arc::new(crate::clock::MockClock::new(start));
+        let mut manager = TaskManager::with_config(TaskManagerConfig { clock: clock.clone(), ..Default::default() });
+        let untouched = manager.add_task("Untouched".to_string()).unwrap();
+        let edited = manager.add_task("Edited".to_string()).unwrap();
+
+        clock.advance(chrono::Duration::hours(1));
+        let added = manager.add_task("Added later".to_string()).unwrap();
+        manager.start_task(&edited).unwrap();
+
+        let changed: HashSet<String> = manager.get_tasks_modified_since(start).map(|t| t.id.to_string()).collect();
+        assert_eq!(changed, HashSet::from([edited, added]));
+        assert!(!changed.contains(&untouched));
+        assert_eq!(manager.get_tasks_modified_since(clock.now()).count(), 0);
+    }
+
+    #[test]
+    fn test_tag_counts() {
+        let mut manager = TaskManager::new();
+        for tags in [vec!["home", "urgent"], vec!["urgent"], vec!["errand", "home"], vec!["urgent", "urgent"], vec![]] {
+            manager.add_task_detailed(NewTask {
+                title: "Tagged".to_string(),
+                tags: tags.into_iter().map(String::from).collect(),
+                ..Default::default()
+            }).unwrap();
+        }
+
+        assert_eq!(manager.tag_counts(), vec![
+            ("urgent".to_string(), 3),
+            ("home".to_string(), 2),
+            ("errand".to_string(), 1),
+        ]);
+    }
+
+    #[test]
+    fn test_sort_by_overdue_amount() {
+        let mut manager = TaskManager::new();
+        let now = Utc::now();
+        for (title, due) in [
+            ("Slightly late", Some(now - chrono::Duration::hours(1))),
+            ("Undated", None),
+            ("Very late", Some(now - chrono::Duration::days(5))),
+            ("Upcoming", Some(now + chrono::Duration::days(1))),
+        ] {
+            let id = manager.add_task(title.to_string()).unwrap();
+            manager.get_task_mut(&id).unwrap().due_date = due;
+        }
+
+        let sorted = manager.get_sorted_tasks(TaskSort::OverdueAmount);
+        assert_eq!(sorted[0].title, "Very late");
+        assert_eq!(sorted[1].title, "Slightly late");
+        assert!(sorted[2..].iter().all(|t| !t.is_overdue_at(now)));
+    }
+
+    #[test]
+    fn test_random_sort_is_reproducible_with_seed() {
+        let mut manager = TaskManager::new();
+        for i in 0..20 {
+            manager.add_task(format!("Task {}", i)).unwrap();
+        }
+
+        let order = |manager: &TaskManager| -> Vec<uuid::Uuid> {
+            manager.get_sorted_tasks(TaskSort::Random { seed: Some(42) }).iter().map(|t| t.id).collect()
+        };
+        let first = order(&manager);
+        assert_eq!(first, order(&manager));
+        assert_eq!(first.len(), 20);
+    }
+
+    #[test]
+    fn test_completions_by_day_includes_empty_days() {
+        let mut manager = TaskManager::new();
+        let today = Utc::now().date_naive();
+        let since = today - chrono::Duration::days(3);
+
+        for days_ago in [0, 0, 2, 10] {
+            let id = manager.add_task(format!("Done {} days ago", days_ago)).unwrap();
+            manager.complete_task(&id).unwrap();
+            manager.get_task_mut(&id).unwrap().completed_at = Some(Utc::now() - chrono::Duration::days(days_ago));
+        }
+        manager.add_task("Still open".to_string()).unwrap();
+
+        let buckets = manager.completions_by_day(since);
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0], (since, 0));
+        assert_eq!(buckets[1].1, 1);
+        assert_eq!(buckets[2].1, 0);
+        assert_eq!(buckets[3], (today, 2));
+    }
+
+    #[test]
+    fn test_reopen_completed_after() {
+        let mut manager = TaskManager::new();
+        let early = manager.add_task("Early".to_string()).unwrap();
+        let late = manager.add_task("Late".to_string()).unwrap();
+        let open = manager.add_task("Open".to_string()).unwrap();
+
+        manager.complete_task(&early).unwrap();
+        manager.get_task_mut(&early).unwrap().completed_at = Some(Utc::now() - chrono::Duration::days(2));
+        manager.complete_task(&late).unwrap();
+
+        let reopened = manager.reopen_completed_after(Utc::now() - chrono::Duration::days(1)).unwrap();
+        assert_eq!(reopened, 1);
+        assert_eq!(manager.get_task(&late).unwrap().status, TaskStatus::Todo);
+        assert!(manager.get_task(&late).unwrap().completed_at.is_none());
+        assert_eq!(manager.get_task(&early).unwrap().status, TaskStatus::Done);
+        assert!(manager.reopen_task(&open).is_err());
+    }
+
+    #[test]
+    fn test_set_status_applies_transition_rules_and_side_effects() {
+        let mut manager = TaskManager::new();
+        let id = manager.add_task("Draft".to_string()).unwrap();
+
+        manager.set_status(&id, TaskStatus::InProgress).unwrap();
+        manager.set_status(&id, TaskStatus::Todo).unwrap();
+        assert_eq!(manager.get_task(&id).unwrap().status, TaskStatus::Todo);
+
+        assert_eq!(manager.set_status(&id, TaskStatus::Done).unwrap(), None);
+        assert!(manager.get_task(&id).unwrap().completed_at.is_some());
+        assert!(matches!(manager.set_status(&id, TaskStatus::Done), Err(TaskError::OperationNotAllowed(_))));
+        assert!(matches!(manager.set_status(&id, TaskStatus::Waiting), Err(TaskError::OperationNotAllowed(_))));
+
+        manager.set_status(&id, TaskStatus::Todo).unwrap();
+        assert!(manager.get_task(&id).unwrap().completed_at.is_none());
+
+        let blocked = manager.add_task_detailed(NewTask {
+            title: "Blocked".to_string(),
+            depends_on: vec![id.clone()],
+            ..Default::default()
+        }).unwrap();
+        assert!(manager.set_status(&blocked, TaskStatus::Done).is_err());
+    }
+
+    #[test]
+    fn test_preview_import_inserts_nothing() {
+        let mut manager = TaskManager::new();
+        let existing_id = manager.add_task("Existing".to_string()).unwrap();
+        let existing = manager.get_task(&existing_id).unwrap().clone();
+
+        let incoming = vec![existing, Task::new("Fresh".to_string()), Task::new(String::new())];
+        let preview = manager.preview_import(&incoming, None, ImportStrategy::Skip);
+
+        assert_eq!(preview.total, 3);
+        assert_eq!(preview.summary.imported, 1);
+        assert_eq!(preview.summary.skipped_existing, 1);
+        assert_eq!(preview.errors.len(), 1);
+        assert_eq!(manager.tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_import_reader_streams_array() {
+        let mut manager = TaskManager::new();
+        let existing_id = manager.add_task("Existing".to_string()).unwrap();
+        let existing = manager.get_task(&existing_id).unwrap().clone();
+
+        let incoming = vec![existing, Task::new("Fresh".to_string()), Task::new("Also fresh".to_string())];
+        let data = serde_json::to_vec(&incoming).unwrap();
+        let summary = manager.import_reader(data.as_slice(), None, ImportStrategy::Skip).unwrap();
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.skipped_existing, 1);
+        assert_eq!(manager.tasks.len(), 3);
+
+        // Validation errors surface as-is; tasks before the bad one are removed again
+        let bad = serde_json::to_vec(&vec![Task::new("Rolled back".to_string()), Task::new(String::new())]).unwrap();
+        assert!(matches!(manager.import_reader(bad.as_slice(), None, ImportStrategy::Skip), Err(TaskError::ValidationError(_))));
+        assert_eq!(manager.tasks.len(), 3);
+
+        assert!(matches!(manager.import_reader(&b"{}"[..], None, ImportStrategy::Skip), Err(TaskError::JsonError(_))));
+        assert!(matches!(manager.import_reader(&b"[] []"[..], None, ImportStrategy::Skip), Err(TaskError::JsonError(_))));
+    }
+
+    #[test]
+    fn test_shift_due_dates() {
+        let mut manager = TaskManager::new();
+        let due = Utc::now();
+        let work = manager.add_task_detailed(NewTask {
+            title: "Work".to_string(),
+            category: Some("work".to_string()),
+            due_date: Some(due),
+            ..Default::default()
+        }).unwrap();
+        let home = manager.add_task_detailed(NewTask {
+            title: "Home".to_string(),
+            category: Some("home".to_string()),
+            due_date: Some(due),
+            ..Default::default()
+        }).unwrap();
+        manager.add_task("Undated".to_string()).unwrap();
+
+        let shifted = manager.shift_due_dates(|t| t.category.as_deref() == Some("work"), chrono::Duration::days(2)).unwrap();
+        assert_eq!(shifted, 1);
+        assert_eq!(manager.get_task(&work).unwrap().due_date, Some(due + chrono::Duration::days(2)));
+        assert_eq!(manager.get_task(&home).unwrap().due_date, Some(due));
+
+        assert_eq!(manager.shift_due_dates(|_| true, chrono::Duration::days(-1)).unwrap(), 2);
+
+        // An offset past the end of the calendar is refused before any task moves
+        let huge = chrono::Duration::weeks(99_999_999);
+        assert!(matches!(manager.shift_due_dates(|_| true, huge), Err(TaskError::ValidationError(_))));
+        assert_eq!(manager.get_task(&home).unwrap().due_date, Some(due - chrono::Duration::days(1)));
+    }
+
+    #[test]
+    fn test_reschedule_and_links_are_recorded_in_history() {
+        let mut manager = TaskManager::new();
+        let due = Utc::now();
+        let id = manager.add_task_detailed(NewTask {
+            title: "Ship".to_string(),
+            due_date: Some(due),
+            ..Default::default()
+        }).unwrap();
+
+        manager.shift_due_dates(|_| true, chrono::Duration::weeks(1)).unwrap();
+        manager.add_link(&id, "https://example.com/pr/7".to_string()).unwrap();
+        manager.remove_link(&id, "https://example.com/pr/7").unwrap();
+
+        let fields: Vec<&str> = manager.get_task(&id).unwrap().history.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["due_date", "links", "links"]);
+        let shift = &manager.get_task(&id).unwrap().history[0];
+        assert_ne!(shift.old, shift.new);
+    }
+
+    #[test]
+    fn test_get_reminders() {
+        let mut manager = TaskManager::new();
+        let now = Utc::now();
+        for (title, offset) in [("Late", -2), ("Soon", 3), ("Later", 48)] {
+            manager.add_task_detailed(NewTask {
+                title: title.to_string(),
+                due_date: Some(now + chrono::Duration::hours(offset)),
+                ..Default::default()
+            }).unwrap();
+        }
+        let done = manager.add_task_detailed(NewTask {
+            title: "Finished".to_string(),
+            due_date: Some(now - chrono::Duration::hours(1)),
+            ..Default::default()
+        }).unwrap();
+        manager.complete_task(&done).unwrap();
+
+        let reminders = manager.get_reminders(chrono::Duration::hours(24));
+        assert_eq!(reminders.len(), 2);
+        assert_eq!(reminders[0].title, "Late");
+        assert_eq!(reminders[0].reason, ReminderReason::Overdue);
+        assert_eq!(reminders[1].title, "Soon");
+        assert_eq!(reminders[1].reason, ReminderReason::DueSoon);
+
+        let reminders = manager.get_reminders(chrono::Duration::weeks(99_999_999));
+        assert_eq!(reminders.len(), 3, "a window past the end of the calendar covers every due date");
+    }
+
+    #[test]
+    fn test_time_tracking_intervals() {
+        let mut manager = TaskManager::new();
+        let id = manager.add_task("Deep work".to_string()).unwrap();
+
+        assert!(manager.stop_time_entry(&id).is_err());
+        manager.start_time_entry(&id).unwrap();
+        assert!(manager.start_time_entry(&id).is_err());
+        manager.stop_time_entry(&id).unwrap();
+        manager.start_time_entry(&id).unwrap();
+        manager.stop_time_entry(&id).unwrap();
+
+        let task = manager.get_task(&id).unwrap();
+        assert_eq!(task.time_entries.len(), 2);
+        assert!(task.running_time_entry().is_none());
+        assert!(task.total_tracked(Utc::now()) >= chrono::Duration::zero());
+    }
+}
@@ -0,0 +1,297 @@
+use crate::cli::{ColorArg, FormatArg, PriorityArg, SortArg};
+use crate::error::{Result, TaskError};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Checks a string value before it becomes an argument default
+type Validator = fn(&str) -> std::result::Result<(), String>;
+
+/// Settings read from the TOML config file.
+///
+/// Every key is optional and replaces the built-in default of the option it names,
+/// so command-line flags and environment variables still take precedence.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    /// Data file, like `--file`; a leading `~/` is the home directory
+    pub file: Option<PathBuf>,
+    pub format: Option<String>,
+    /// Priority given to tasks added without `--priority`
+    pub default_priority: Option<String>,
+    /// Order `list` uses without `--sort`
+    pub default_sort: Option<String>,
+    pub timezone: Option<String>,
+    pub input_tz: Option<String>,
+    pub week_start: Option<String>,
+    pub color: Option<String>,
+    pub backups: Option<usize>,
+    pub wal: Option<bool>,
+    pub compress: Option<bool>,
+    pub max_import_size: Option<u64>,
+    pub overdue_warn_threshold: Option<usize>,
+    pub completion_good_threshold: Option<f64>,
+    pub completion_warn_threshold: Option<f64>,
+    /// Lowest priority `complete` asks about before finishing a task
+    pub confirm_complete_priority: Option<String>,
+}
+
+/// `$XDG_CONFIG_HOME/task-manager/config.toml`, or `~/.config/task-manager/config.toml`
+pub fn default_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => home_dir()?.join(".config"),
+    };
+    Some(base.join("task-manager").join("config.toml"))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").filter(|home| !home.is_empty()).map(PathBuf::from)
+}
+
+/// Read the config file at `path`; a file that doesn't exist is an empty config
+pub fn load(path: &Path) -> Result<FileConfig> {
+    if !path.exists() {
+        return Ok(FileConfig::default());
+    }
+    let config = config::Config::builder()
+        .add_source(config::File::from(path).format(config::FileFormat::Toml))
+        .build()?;
+    Ok(config.try_deserialize()?)
+}
+
+impl FileConfig {
+    /// Install the configured values as the defaults of the matching arguments.
+    ///
+    /// Values are checked here, so a bad one is reported against its key in the config
+    /// file rather than as if it had been typed on the command line.
+    pub fn apply(&self, path: &Path, mut command: clap::Command) -> Result<clap::Command> {
+        let invalid = |key: &str, value: &str, error: &dyn Display| {
+            TaskError::ConfigError(config::ConfigError::Message(format!(
+                "{}: invalid `{}` value '{}': {}", path.display(), key, value, error
+            )))
+        };
+        fn check<T, E: Display>(parsed: std::result::Result<T, E>) -> std::result::Result<(), E> {
+            parsed.map(|_| ())
+        }
+
+        let mut global: Vec<(&str, String)> = Vec::new();
+        if let Some(file) = &self.file {
+            global.push(("file", expand_home(file).display().to_string()));
+        }
+        let checked: [(&str, &Option<String>, Validator); 6] = [
+            ("format", &self.format, |v| check(FormatArg::from_str(v, true))),
+            ("timezone", &self.timezone, |v| check(chrono_tz::Tz::from_str(v).map_err(|e| e.to_string()))),
+            ("input_tz", &self.input_tz, |v| check(chrono_tz::Tz::from_str(v).map_err(|e| e.to_string()))),
+            ("week_start", &self.week_start, |v| check(chrono::Weekday::from_str(v).map_err(|e| e.to_string()))),
+            ("color", &self.color, |v| check(ColorArg::from_str(v, true))),
+            ("confirm_complete_priority", &self.confirm_complete_priority, |v| check(PriorityArg::from_str(v))),
+        ];
+        for (key, value, validate) in checked {
+            if let Some(value) = value {
+                validate(value).map_err(|e| invalid(key, value, &e))?;
+                global.push((key, value.clone()));
+            }
+        }
+        global.extend(self.backups.map(|n| ("backups", n.to_string())));
+        global.extend(self.wal.map(|on| ("wal", on.to_string())));
+        global.extend(self.compress.map(|on| ("compress", on.to_string())));
+        global.extend(self.max_import_size.map(|n| ("max_import_size", n.to_string())));
+        global.extend(self.overdue_warn_threshold.map(|n| ("overdue_warn_threshold", n.to_string())));
+        for (key, value) in [
+            ("completion_good_threshold", self.completion_good_threshold),
+            ("completion_warn_threshold", self.completion_warn_threshold),
+        ] {
+            if let Some(pct) = value {
+                if !(0.0..=100.0).contains(&pct) {
+                    return Err(invalid(key, &pct.to_string(), &"must be a percentage from 0 to 100"));
+                }
+                global.push((key, pct.to_string()));
+            }
+        }
+
+        for (id, value) in global {
+            command = command.mut_arg(id, |arg| arg.default_value(value));
+        }
+
+        if let Some(priority) = &self.default_priority {
+            PriorityArg::from_str(priority).map_err(|e| invalid("default_priority", priority, &e))?;
+            let priority = priority.clone();
+            command = command.mut_subcommand("add", |add| add.mut_arg("priority", |arg| arg.default_value(priority)));
+        }
+        if let Some(sort) = &self.default_sort {
+            <SortArg as ValueEnum>::from_str(sort, true).map_err(|e| invalid("default_sort", sort, &e))?;
+            let sort = sort.clone();
+            command = command.mut_subcommand("list", |list| list.mut_arg("sort", |arg| arg.default_value(sort)));
+        }
+        Ok(command)
+    }
+}
+
+/// A config file listing every key, commented out at its built-in default
+pub fn template() -> String {
+    format!(
+        r#"# task-manager config file
+#
+# Each key sets the default for the option it names; command-line flags and
+# environment variables still take precedence. Uncomment a line to use it.
+
+# Data file, like --file (a leading ~/ is your home directory;
+# TASK_MANAGER_FILE and --project still win)
+# file = "tasks.json"
+
+# Data file format, like --format: json or yaml (default: from the file extension)
+# format = "json"
+
+# Priority for `add` without --priority: none, low, medium, high or critical
+# default_priority = "medium"
+
+# Order for `list` without --sort, e.g. created-desc, due-date-asc, priority-desc
+# default_sort = "created-desc"
+
+# Timezone used to display dates, like --timezone
+# timezone = "UTC"
+
+# Timezone for dates entered without an offset, like --input-tz
+# input_tz = "UTC"
+
+# First day of the week for `eow` due dates, like --week-start
+# week_start = "monday"
+
+# Colored output, like --color: auto, always or never
+# color = "auto"
+
+# Backups kept of the data file before each write, like --backups (0 disables)
+# backups = {backups}
+
+# Journal every change to a write-ahead log, like --wal
+# wal = false
+
+# Gzip the data file, like --compress
+# compress = false
+
+# Largest file `import` will accept, in bytes, like --max-import-size
+# max_import_size = {max_import_size}
+
+# Overdue task count at which `stats` warns, like --overdue-warn-threshold
+# overdue_warn_threshold = {overdue_warn_threshold}
+
+# Completion rate at or above which `stats` shows green, like --completion-good-threshold
+# completion_good_threshold = {completion_good_threshold:?}
+
+# Completion rate below which `stats` shows red, like --completion-warn-threshold
+# completion_warn_threshold = {completion_warn_threshold:?}
+
+# Ask before completing tasks at or above this priority, like --confirm-complete-priority
+# (unset by default, so `complete` never asks)
+# confirm_complete_priority = "critical"
+"#,
+        backups = crate::manager::DEFAULT_BACKUPS,
+        max_import_size = crate::manager::DEFAULT_MAX_IMPORT_SIZE,
+        overdue_warn_threshold = crate::manager::DEFAULT_OVERDUE_WARN_THRESHOLD,
+        completion_good_threshold = crate::manager::DEFAULT_COMPLETION_GOOD_THRESHOLD,
+        completion_warn_threshold = crate::manager::DEFAULT_COMPLETION_WARN_THRESHOLD,
+    )
+}
+
+/// Write the commented template to `path`, creating parent directories.
+///
+/// An existing file is only replaced when `force` is set.
+pub fn init(path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        return Err(TaskError::OperationNotAllowed(format!(
+            "{} already exists; pass --force to replace it", path.display()
+        )));
+    }
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, template())?;
+    Ok(())
+}
+
+/// Replace a leading `~/` with the home directory
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn write_config(dir: &Path, toml: &str) -> PathBuf {
+        let path = dir.join("config.toml");
+        std::fs::write(&path, toml).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_config_values_become_defaults_that_flags_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(dir.path(), "file = \"work.json\"\ndefault_priority = \"h\"\ndefault_sort = \"due-date-asc\"\nbackups = 9\n");
+        let command = load(&path).unwrap().apply(&path, crate::cli::Cli::command()).unwrap();
+
+        let matches = command.clone().try_get_matches_from(["task-manager", "add", "Plan"]).unwrap();
+        assert_eq!(matches.get_one::<PathBuf>("file"), Some(&PathBuf::from("work.json")));
+        assert_eq!(matches.get_one::<usize>("backups"), Some(&9));
+        let (_, add) = matches.subcommand().unwrap();
+        assert_eq!(add.get_one::<PriorityArg>("priority"), Some(&PriorityArg::High));
+
+        let matches = command.try_get_matches_from(["task-manager", "--file", "home.json", "add", "Plan", "-p", "low"]).unwrap();
+        assert_eq!(matches.get_one::<PathBuf>("file"), Some(&PathBuf::from("home.json")));
+        let (_, add) = matches.subcommand().unwrap();
+        assert_eq!(add.get_one::<PriorityArg>("priority"), Some(&PriorityArg::Low));
+    }
+
+    #[test]
+    fn test_config_errors_name_the_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(dir.path(), "default_priority = \"someday\"\n");
+        let err = load(&path).unwrap().apply(&path, crate::cli::Cli::command()).unwrap_err();
+        assert!(err.to_string().contains("invalid `default_priority` value 'someday'"));
+
+        let path = write_config(dir.path(), "confirm_complete_priority = \"asap\"\n");
+        let err = load(&path).unwrap().apply(&path, crate::cli::Cli::command()).unwrap_err();
+        assert!(err.to_string().contains("invalid `confirm_complete_priority` value 'asap'"));
+        let path = write_config(dir.path(), "completion_warn_threshold = 150\n");
+        let err = load(&path).unwrap().apply(&path, crate::cli::Cli::command()).unwrap_err();
+        assert!(err.to_string().contains("invalid `completion_warn_threshold` value '150'"));
+
+        let path = write_config(dir.path(), "backups = \"many\"\n");
+        assert!(matches!(load(&path), Err(TaskError::ConfigError(_))));
+        let path = write_config(dir.path(), "colour = \"never\"\n");
+        assert!(matches!(load(&path), Err(TaskError::ConfigError(_))));
+
+        assert!(load(&dir.path().join("missing.toml")).unwrap().file.is_none());
+    }
+
+    #[test]
+    fn test_init_writes_a_template_that_loads_when_uncommented() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("task-manager").join("config.toml");
+        init(&path, false).unwrap();
+        assert!(load(&path).unwrap().file.is_none());
+        assert!(matches!(init(&path, false), Err(TaskError::OperationNotAllowed(_))));
+        init(&path, true).unwrap();
+
+        // Every documented key is one the loader accepts, and its default is valid
+        let uncommented: String = template()
+            .lines()
+            .filter_map(|line| line.strip_prefix("# ").filter(|line| line.contains(" = ")))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        std::fs::write(&path, uncommented).unwrap();
+        let config = load(&path).unwrap();
+        assert_eq!(config.backups, Some(crate::manager::DEFAULT_BACKUPS));
+        assert_eq!(config.completion_good_threshold, Some(crate::manager::DEFAULT_COMPLETION_GOOD_THRESHOLD));
+        let matches = config.apply(&path, crate::cli::Cli::command()).unwrap()
+            .try_get_matches_from(["task-manager", "list"]).unwrap();
+        assert_eq!(matches.get_one::<PriorityArg>("confirm_complete_priority"), Some(&PriorityArg::Critical));
+    }
+}
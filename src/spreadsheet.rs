@@ -0,0 +1,292 @@
+use crate::error::{Result, TaskError};
+use crate::task::Task;
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use std::io::Read;
+use uuid::Uuid;
+
+/// A task field that can be written as a CSV column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    Title,
+    Description,
+    Status,
+    Priority,
+    Category,
+    Owner,
+    Tags,
+    Estimate,
+    DueDate,
+    CreatedAt,
+    UpdatedAt,
+    CompletedAt,
+}
+
+/// Every column, in the order `header` lists them
+const ALL_COLUMNS: &[Column] = &[
+    Column::Id,
+    Column::Title,
+    Column::Description,
+    Column::Status,
+    Column::Priority,
+    Column::Category,
+    Column::Owner,
+    Column::Tags,
+    Column::Estimate,
+    Column::DueDate,
+    Column::CreatedAt,
+    Column::UpdatedAt,
+    Column::CompletedAt,
+];
+
+/// Columns written when none are chosen explicitly
+pub const DEFAULT_COLUMNS: &[Column] = &[
+    Column::Id,
+    Column::Title,
+    Column::Status,
+    Column::Priority,
+    Column::Category,
+    Column::DueDate,
+    Column::CreatedAt,
+    Column::CompletedAt,
+];
+
+impl Column {
+    /// Header cell for the column, e.g. `due_date`
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Title => "title",
+            Column::Description => "description",
+            Column::Status => "status",
+            Column::Priority => "priority",
+            Column::Category => "category",
+            Column::Owner => "owner",
+            Column::Tags => "tags",
+            Column::Estimate => "estimate_minutes",
+            Column::DueDate => "due_date",
+            Column::CreatedAt => "created_at",
+            Column::UpdatedAt => "updated_at",
+            Column::CompletedAt => "completed_at",
+        }
+    }
+
+    /// The column with this header, ignoring case and surrounding whitespace
+    pub fn from_header(header: &str) -> Option<Column> {
+        let header = header.trim();
+        ALL_COLUMNS.iter().copied().find(|column| column.header().eq_ignore_ascii_case(header))
+    }
+
+    /// The cell for `task`; missing optional values are empty
+    fn cell(self, task: &Task) -> String {
+        let timestamp = |ts: Option<DateTime<Utc>>| {
+            ts.map(|ts| ts.to_rfc3339_opts(SecondsFormat::Secs, true)).unwrap_or_default()
+        };
+        match self {
+            Column::Id => task.id.to_string(),
+            Column::Title => task.title.clone(),
+            Column::Description => task.description.clone().unwrap_or_default(),
+            Column::Status => task.status.label().replace(' ', "-"),
+            Column::Priority => format!("{:?}", task.priority).to_lowercase(),
+            Column::Category => task.category.clone().unwrap_or_default(),
+            Column::Owner => task.owner.clone().unwrap_or_default(),
+            Column::Tags => task.tags.join(";"),
+            Column::Estimate => task.estimate_minutes.map(|m| m.to_string()).unwrap_or_default(),
+            Column::DueDate => timestamp(task.due_date),
+            Column::CreatedAt => timestamp(Some(task.created_at)),
+            Column::UpdatedAt => timestamp(Some(task.updated_at)),
+            Column::CompletedAt => timestamp(task.completed_at),
+        }
+    }
+}
+
+/// Render tasks as CSV: a header row, then one row per task.
+///
+/// Timestamps are RFC 3339 in UTC and multiple tags are joined with `;`. Cells
+/// containing commas, quotes or newlines are quoted.
+pub fn write_csv<'a>(tasks: impl IntoIterator<Item = &'a Task>, columns: &[Column]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(columns.iter().map(|column| column.header()))?;
+    for task in tasks {
+        writer.write_record(columns.iter().map(|column| column.cell(task)))?;
+    }
+    let data = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(data).expect("CSV built from UTF-8 strings"))
+}
+
+/// Tasks read from a CSV file, plus the rows that could not be turned into tasks
+#[derive(Debug, Default)]
+pub struct CsvImport {
+    pub tasks: Vec<Task>,
+    /// One message per rejected row, e.g. `line 4: invalid priority 'asap'`
+    pub errors: Vec<String>,
+    /// Header cells that don't name a known column; their values are ignored
+    pub unknown_columns: Vec<String>,
+}
+
+/// Read tasks from CSV with a header row naming the columns, in any order.
+///
+/// Only `title` is required. A missing or blank `id` gets a fresh UUID, and missing
+/// creation times default to `now`. Dates without an offset are read in `input_tz`.
+/// A row with a value that can't be parsed is reported in `errors` by line number
+/// and left out, rather than failing the whole file.
+pub fn read_csv<R: Read>(reader: R, input_tz: Tz, now: DateTime<Utc>) -> Result<CsvImport> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let mut import = CsvImport::default();
+
+    let headers = reader.headers()?.clone();
+    let columns: Vec<Option<Column>> = headers.iter().map(Column::from_header).collect();
+    import.unknown_columns = headers.iter()
+        .zip(&columns)
+        .filter(|(_, column)| column.is_none())
+        .map(|(header, _)| header.to_string())
+        .collect();
+    if !columns.contains(&Some(Column::Title)) {
+        return Err(TaskError::ValidationError("CSV file has no `title` column".to_string()));
+    }
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                let line = err.position().map(|p| p.line()).unwrap_or_default();
+                import.errors.push(format!("line {}: malformed record", line));
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        let cells = columns.iter().zip(record.iter()).filter_map(|(column, value)| column.map(|c| (c, value)));
+        match task_from_row(cells, input_tz, now) {
+            Ok(task) => import.tasks.push(task),
+            Err(message) => import.errors.push(format!("line {}: {}", line, message)),
+        }
+    }
+    Ok(import)
+}
+
+/// Build a task from `(column, cell)` pairs, describing the first bad cell on failure
+fn task_from_row<'a>(
+    cells: impl Iterator<Item = (Column, &'a str)>,
+    input_tz: Tz,
+    now: DateTime<Utc>,
+) -> std::result::Result<Task, String> {
+    let mut task = Task::new(String::new());
+    task.created_at = now;
+    let mut updated_at = None;
+
+    let timestamp = |value: &str, name: &str| {
+        crate::task::parse_datetime_in(value, input_tz).map_err(|_| format!("invalid {} '{}'", name, value))
+    };
+    for (column, value) in cells {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match column {
+            Column::Id => task.id = Uuid::parse_str(value).map_err(|_| format!("invalid id '{}'", value))?,
+            Column::Title => task.title = value.to_string(),
+            Column::Description => task.description = Some(value.to_string()),
+            Column::Status => {
+                task.status = value.parse().map_err(|_| format!("invalid status '{}'", value))?;
+            }
+            Column::Priority => {
+                task.priority = value.parse().map_err(|_| format!("invalid priority '{}'", value))?;
+            }
+            Column::Category => task.category = Some(value.to_string()),
+            Column::Owner => task.owner = Some(value.to_string()),
+            Column::Tags => {
+                let tags = value.split(';').filter(|tag| !tag.trim().is_empty()).map(str::to_string).collect();
+                task.tags = crate::task::normalize_tags(tags);
+            }
+            Column::Estimate => {
+                task.estimate_minutes = Some(value.parse().map_err(|_| format!("invalid estimate_minutes '{}'", value))?);
+            }
+            Column::DueDate => task.due_date = Some(timestamp(value, "due_date")?),
+            Column::CreatedAt => task.created_at = timestamp(value, "created_at")?,
+            Column::UpdatedAt => updated_at = Some(timestamp(value, "updated_at")?),
+            Column::CompletedAt => task.completed_at = Some(timestamp(value, "completed_at")?),
+        }
+    }
+
+    if task.title.is_empty() {
+        return Err("title is empty".to_string());
+    }
+    task.updated_at = updated_at.unwrap_or(task.created_at);
+    Ok(task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_csv_quotes_and_leaves_missing_fields_empty() {
+        let mut task = Task::new("Call Bob, then \"Alice\"".to_string());
+        task.description = Some("first line\nsecond line".to_string());
+        task.created_at = DateTime::parse_from_rfc3339("2024-05-01T12:30:00Z").unwrap().with_timezone(&Utc);
+
+        let csv = write_csv([&task], DEFAULT_COLUMNS).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("id,title,status,priority,category,due_date,created_at,completed_at"));
+        assert_eq!(
+            lines.next().unwrap(),
+            format!("{},\"Call Bob, then \"\"Alice\"\"\",todo,medium,,,2024-05-01T12:30:00Z,", task.id)
+        );
+
+        let csv = write_csv([&task], &[Column::Title, Column::Description]).unwrap();
+        assert_eq!(csv, "title,description\n\"Call Bob, then \"\"Alice\"\"\",\"first line\nsecond line\"\n");
+    }
+
+    #[test]
+    fn test_read_csv_maps_headers_and_reports_bad_rows() {
+        let now = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let id = Uuid::new_v4();
+        let data = format!(
+            "Title,priority,due_date,id,tags,source\n\
+             \"Call Bob, then Alice\",high,2024-06-01,{},a;b;a,crm\n\
+             Bad priority,asap,,,,\n\
+             Bad date,low,next-ish,,,\n\
+             Minimal\n",
+            id
+        );
+
+        let import = read_csv(data.as_bytes(), Tz::UTC, now).unwrap();
+        assert_eq!(import.unknown_columns, vec!["source"]);
+        assert_eq!(import.errors, vec!["line 3: invalid priority 'asap'", "line 4: invalid due_date 'next-ish'"]);
+        assert_eq!(import.tasks.len(), 2);
+
+        let first = &import.tasks[0];
+        assert_eq!(first.id, id);
+        assert_eq!(first.title, "Call Bob, then Alice");
+        assert_eq!(first.priority, crate::task::Priority::High);
+        assert_eq!(first.tags, vec!["a", "b"]);
+        assert_eq!(first.due_date.unwrap().to_rfc3339(), "2024-06-01T00:00:00+00:00");
+
+        let minimal = &import.tasks[1];
+        assert_ne!(minimal.id, id);
+        assert_eq!((minimal.created_at, minimal.updated_at), (now, now));
+
+        assert!(read_csv("name\nx\n".as_bytes(), Tz::UTC, now).is_err());
+
+        let mut data = b"title\nFirst\n".to_vec();
+        data.extend_from_slice(b"Bad \xff bytes\nLast\n");
+        let import = read_csv(data.as_slice(), Tz::UTC, now).unwrap();
+        assert_eq!(import.errors, vec!["line 3: malformed record"]);
+        assert_eq!(import.tasks.iter().map(|t| t.title.as_str()).collect::<Vec<_>>(), vec!["First", "Last"]);
+    }
+
+    #[test]
+    fn test_csv_round_trips_exported_columns() {
+        let mut task = Task::new("Ship, \"v2\"".to_string());
+        task.category = Some("work".to_string());
+        task.tags = vec!["x".to_string(), "y".to_string()];
+        task.created_at = DateTime::parse_from_rfc3339("2024-05-01T12:30:00Z").unwrap().with_timezone(&Utc);
+        task.updated_at = task.created_at;
+
+        let csv = write_csv([&task], ALL_COLUMNS).unwrap();
+        let import = read_csv(csv.as_bytes(), Tz::UTC, Utc::now()).unwrap();
+        assert!(import.errors.is_empty() && import.unknown_columns.is_empty());
+        assert_eq!(write_csv(&import.tasks, ALL_COLUMNS).unwrap(), csv);
+    }
+}
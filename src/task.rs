@@ -1,12 +1,32 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+/// Maximum number of links attached to a single task
+pub const MAX_LINKS: usize = 20;
+
+/// Maximum length of a single link
+pub const MAX_LINK_LENGTH: usize = 2048;
+
+/// Maximum number of tags on a single task
+pub const MAX_TAGS: usize = 20;
+
+/// Maximum length of a single tag
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// Number of change events kept per task; older ones are dropped first
+pub const MAX_HISTORY: usize = 100;
+
+/// Length at which old and new values in a change event are cut short
+const CHANGE_SUMMARY_LENGTH: usize = 60;
 
 /// Enum for update operations that distinguishes between keeping, clearing, or setting a value
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum UpdateValue<T> {
     /// Keep the current value unchanged
+    #[default]
     Keep,
     /// Clear the value (set to None)
     Clear,
@@ -14,18 +34,130 @@ pub enum UpdateValue<T> {
     Set(T),
 }
 
+/// Fields supplied when creating a task; everything but the title is optional
+#[derive(Debug, Clone, Default)]
+pub struct NewTask {
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Option<Priority>,
+    pub category: Option<String>,
+    pub due_date: Option<DateTime<Utc>>,
+    pub links: Vec<String>,
+    pub recurrence: Option<Recurrence>,
+    pub owner: Option<String>,
+    /// Expected effort in minutes
+    pub estimate_minutes: Option<u32>,
+    /// ID of the parent task, to create a subtask
+    pub parent: Option<String>,
+    /// IDs of tasks that must be done before this one can be completed
+    pub depends_on: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+/// Selective changes to apply to a task; unset fields are left untouched
+#[derive(Debug, Clone, Default)]
+pub struct TaskUpdate {
+    pub title: Option<String>,
+    pub description: UpdateValue<String>,
+    pub priority: Option<Priority>,
+    pub category: UpdateValue<String>,
+    pub due_date: UpdateValue<DateTime<Utc>>,
+    /// Replace the full list of links
+    pub links: Option<Vec<String>>,
+    pub owner: UpdateValue<String>,
+    pub estimate_minutes: UpdateValue<u32>,
+    /// Replace the full list of dependencies
+    pub depends_on: Option<Vec<Uuid>>,
+    /// Replace the full list of tags
+    pub tags: Option<Vec<String>>,
+}
+
 /// Priority levels for tasks.
 ///
-/// Implements `PartialOrd` and `Ord` where Critical > High > Medium > Low.
+/// Implements `PartialOrd` and `Ord` where Critical > High > Medium > Low > None.
+/// `None` marks a task as deliberately unprioritized, so it sorts below everything else.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Priority {
+    None = 0,
     Low = 1,
     Medium = 2,
     High = 3,
     Critical = 4,
 }
 
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    /// Parse a priority name leniently, accepting common abbreviations such as `hi` or `urgent`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" | "unset" | "no" | "-" => Ok(Priority::None),
+            "low" | "lo" | "l" => Ok(Priority::Low),
+            "medium" | "med" | "mid" | "m" | "normal" => Ok(Priority::Medium),
+            "high" | "hi" | "h" => Ok(Priority::High),
+            "critical" | "crit" | "c" | "urgent" => Ok(Priority::Critical),
+            _ => Err(format!(
+                "unknown priority '{}'. Accepted: none (unset, no, -), low (lo, l), medium (med, mid, m, normal), \
+                 high (hi, h), critical (crit, c, urgent)",
+                value
+            )),
+        }
+    }
+}
+
+/// How often a recurring task repeats once completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Recurrence {
+    /// Advance a date by one recurrence interval
+    pub fn advance(self, date: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Recurrence::Daily => date + Duration::days(1),
+            Recurrence::Weekly => date + Duration::weeks(1),
+            Recurrence::Monthly => date.checked_add_months(Months::new(1)).unwrap_or(date),
+        }
+    }
+}
+
+/// A tracked interval of work on a task; `end` is `None` while the timer is running.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeEntry {
+    /// Length of the interval, measuring a running entry up to `now`
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration {
+        self.end.unwrap_or(now) - self.start
+    }
+}
+
+/// A timestamped progress note attached to a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub timestamp: DateTime<Utc>,
+    pub text: String,
+}
+
+/// One recorded change to a task's field, for its audit history.
+///
+/// `old` and `new` are short human-readable summaries; `None` means unset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    pub timestamp: DateTime<Utc>,
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
 /// Status of a task representing its lifecycle.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -34,28 +166,125 @@ pub enum TaskStatus {
     Todo,
     /// Task is currently being worked on
     InProgress,
+    /// Task is blocked waiting on someone or something external
+    Waiting,
     /// Task has been finished successfully
     Done,
     /// Task has been cancelled and will not be completed
     Cancelled,
 }
 
+impl TaskStatus {
+    /// Returns true for Done and Cancelled, the statuses a task leaves only by reopening
+    pub fn is_closed(self) -> bool {
+        matches!(self, TaskStatus::Done | TaskStatus::Cancelled)
+    }
+
+    /// Plain lowercase name for messages, e.g. "in progress"
+    pub fn label(self) -> &'static str {
+        match self {
+            TaskStatus::Todo => "todo",
+            TaskStatus::InProgress => "in progress",
+            TaskStatus::Waiting => "waiting",
+            TaskStatus::Done => "done",
+            TaskStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether a task may move directly from this status to `next`.
+    ///
+    /// Open tasks may move to any other status; closed tasks only back to Todo.
+    pub fn can_transition_to(self, next: TaskStatus) -> bool {
+        if self == next {
+            false
+        } else if self.is_closed() {
+            next == TaskStatus::Todo
+        } else {
+            true
+        }
+    }
+}
+
+impl std::str::FromStr for TaskStatus {
+    type Err = String;
+
+    /// Parse a status name leniently, accepting common aliases such as `wip` or `finished`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "todo" | "to-do" | "open" | "new" => Ok(TaskStatus::Todo),
+            "in-progress" | "inprogress" | "in_progress" | "progress" | "wip" | "started" | "doing" => {
+                Ok(TaskStatus::InProgress)
+            }
+            "waiting" | "wait" | "blocked" | "on-hold" | "hold" => Ok(TaskStatus::Waiting),
+            "done" | "complete" | "completed" | "finished" => Ok(TaskStatus::Done),
+            "cancelled" | "canceled" | "cancel" => Ok(TaskStatus::Cancelled),
+            _ => Err(format!(
+                "unknown status '{}'. Accepted: todo (open, new), in-progress (inprogress, wip, started, doing), \
+                 waiting (wait, blocked, on-hold, hold), done (complete, completed, finished), cancelled (canceled, cancel)",
+                value
+            )),
+        }
+    }
+}
+
+/// A set of statuses, for filters that match any of several statuses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusSet(u8);
+
+impl StatusSet {
+    /// Every status
+    pub const ALL: StatusSet = StatusSet(0b11111);
+    /// Todo and in progress
+    pub const ACTIVE: StatusSet = StatusSet::of(&[TaskStatus::Todo, TaskStatus::InProgress]);
+    /// Done and cancelled
+    pub const CLOSED: StatusSet = StatusSet::of(&[TaskStatus::Done, TaskStatus::Cancelled]);
+    /// Everything except done
+    pub const OPEN: StatusSet = StatusSet::ALL.without(TaskStatus::Done);
+
+    /// The set holding exactly `statuses`
+    pub const fn of(statuses: &[TaskStatus]) -> StatusSet {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < statuses.len() {
+            bits |= Self::bit(statuses[i]);
+            i += 1;
+        }
+        StatusSet(bits)
+    }
+
+    const fn bit(status: TaskStatus) -> u8 {
+        1 << status as u8
+    }
+
+    pub fn contains(self, status: TaskStatus) -> bool {
+        self.0 & Self::bit(status) != 0
+    }
+
+    /// Statuses in both sets, for combining filters with AND
+    pub fn intersect(self, other: StatusSet) -> StatusSet {
+        StatusSet(self.0 & other.0)
+    }
+
+    /// This set with `status` removed
+    pub const fn without(self, status: TaskStatus) -> StatusSet {
+        StatusSet(self.0 & !Self::bit(status))
+    }
+}
+
 /// Comprehensive task model for enterprise use
 ///
 /// A Task represents a single work item with all necessary metadata
 /// for enterprise task management including validation, serialization,
 /// and status tracking.
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
 pub struct Task {
     /// Unique identifier for the task
     pub id: Uuid,
 
-    /// Task title - required, max 200 characters
-    #[validate(length(min = 1, max = 200, message = "Title must be between 1-200 characters"))]
+    /// Task title - required, max 200 characters by default (see `ValidationLimits`)
     pub title: String,
 
     /// Optional detailed description
-    #[validate(length(max = 2000, message = "Description must not exceed 2000 characters"))]
     pub description: Option<String>,
 
     /// Task priority level
@@ -65,7 +294,6 @@ pub struct Task {
     pub status: TaskStatus,
 
     /// Optional category/tag for organization
-    #[validate(length(max = 50, message = "Category must not exceed 50 characters"))]
     pub category: Option<String>,
 
     /// Optional due date
@@ -79,6 +307,186 @@ pub struct Task {
 
     /// Optional completion timestamp
     pub completed_at: Option<DateTime<Utc>>,
+
+    /// IDs of tasks that must be finished before this one
+    #[serde(default)]
+    pub depends_on: Vec<Uuid>,
+
+    /// Related URLs such as pull requests or tickets
+    #[serde(default)]
+    #[validate(custom(function = "validate_links"))]
+    pub links: Vec<String>,
+
+    /// Repeat interval; completing the task creates the next instance
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+
+    /// Shared by all instances of a recurring task (the first instance's ID)
+    #[serde(default)]
+    pub series_id: Option<Uuid>,
+
+    /// Manually tracked work intervals
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+
+    /// Progress notes, oldest first
+    #[serde(default)]
+    #[validate(custom(function = "validate_notes"))]
+    pub notes: Vec<Note>,
+
+    /// Person responsible for the task on shared lists
+    #[serde(default)]
+    #[validate(length(min = 1, max = 100, message = "Owner must be between 1-100 characters"))]
+    pub owner: Option<String>,
+
+    /// Expected effort in minutes, for planning and comparing against tracked time
+    #[serde(default)]
+    #[validate(range(min = 1, max = 10080, message = "Estimate must be between 1 minute and 1 week (10080 minutes)"))]
+    pub estimate_minutes: Option<u32>,
+
+    /// Parent task when this task is a subtask
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+
+    /// Free-form labels; unlike the single category, a task may have several
+    #[serde(default)]
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Vec<String>,
+
+    /// Recorded field changes, oldest first and capped at `MAX_HISTORY`
+    #[serde(default)]
+    pub history: Vec<ChangeEvent>,
+
+    /// When the task was moved to the archive; archived tasks are kept out of the
+    /// active list but stay in the data file
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+/// Maximum field lengths enforced by `Task::validate_with`, in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationLimits {
+    pub title_max: usize,
+    pub description_max: usize,
+    pub category_max: usize,
+    pub note_max: usize,
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        Self {
+            title_max: 200,
+            description_max: 2000,
+            category_max: 50,
+            note_max: 1000,
+        }
+    }
+}
+
+impl ValidationLimits {
+    /// Ensure every limit is positive; a zero limit would reject all values
+    pub fn check(&self) -> crate::error::Result<()> {
+        for (name, value) in [
+            ("title", self.title_max),
+            ("description", self.description_max),
+            ("category", self.category_max),
+            ("note", self.note_max),
+        ] {
+            if value == 0 {
+                return Err(crate::error::TaskError::ValidationError(
+                    format!("The {} length limit must be positive", name)
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ensure links are non-empty, bounded in length, and capped in count
+fn validate_links(links: &[String]) -> Result<(), ValidationError> {
+    if links.len() > MAX_LINKS {
+        return Err(ValidationError::new("links_count")
+            .with_message(format!("A task may have at most {} links", MAX_LINKS).into()));
+    }
+    if links.iter().any(|link| link.trim().is_empty()) {
+        return Err(ValidationError::new("link_empty").with_message("Links must not be empty".into()));
+    }
+    if links.iter().any(|link| link.len() > MAX_LINK_LENGTH) {
+        return Err(ValidationError::new("link_length")
+            .with_message(format!("Links must not exceed {} characters", MAX_LINK_LENGTH).into()));
+    }
+    Ok(())
+}
+
+/// Ensure notes are non-empty; their length is checked against `ValidationLimits`
+fn validate_notes(notes: &[Note]) -> Result<(), ValidationError> {
+    if notes.iter().any(|note| note.text.trim().is_empty()) {
+        return Err(ValidationError::new("note_empty").with_message("Notes must not be empty".into()));
+    }
+    Ok(())
+}
+
+/// Ensure tags are non-empty, bounded in length, and capped in count
+fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.len() > MAX_TAGS {
+        return Err(ValidationError::new("tags_count")
+            .with_message(format!("A task may have at most {} tags", MAX_TAGS).into()));
+    }
+    if tags.iter().any(|tag| tag.trim().is_empty()) {
+        return Err(ValidationError::new("tag_empty").with_message("Tags must not be empty".into()));
+    }
+    if tags.iter().any(|tag| tag.chars().count() > MAX_TAG_LENGTH) {
+        return Err(ValidationError::new("tag_length")
+            .with_message(format!("Tags must not exceed {} characters", MAX_TAG_LENGTH).into()));
+    }
+    Ok(())
+}
+
+/// Items of `first` followed by those of `second` not already present
+fn union<T: Clone + PartialEq>(first: &[T], second: &[T]) -> Vec<T> {
+    let mut combined = first.to_vec();
+    for item in second {
+        if !combined.contains(item) {
+            combined.push(item.clone());
+        }
+    }
+    combined
+}
+
+/// Shorten a value for a change event, marking the cut with an ellipsis
+fn summarize(value: &str) -> String {
+    let value = value.replace('\n', " ");
+    if value.chars().count() <= CHANGE_SUMMARY_LENGTH {
+        value
+    } else {
+        let cut: String = value.chars().take(CHANGE_SUMMARY_LENGTH - 1).collect();
+        format!("{}…", cut)
+    }
+}
+
+/// Summary of a list field, or `None` when it is empty
+fn summarize_list<T: ToString>(items: &[T]) -> Option<String> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(summarize(&items.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")))
+    }
+}
+
+fn summarize_date(date: Option<DateTime<Utc>>) -> Option<String> {
+    date.map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
+}
+
+/// Trim tags and drop repeats, keeping the first occurrence of each
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
 }
 
 impl Task {
@@ -98,6 +506,18 @@ impl Task {
             created_at: now,
             updated_at: now,
             completed_at: None,
+            depends_on: Vec::new(),
+            links: Vec::new(),
+            recurrence: None,
+            series_id: None,
+            time_entries: Vec::new(),
+            notes: Vec::new(),
+            owner: None,
+            estimate_minutes: None,
+            parent_id: None,
+            tags: Vec::new(),
+            history: Vec::new(),
+            archived_at: None,
         }
     }
 
@@ -123,63 +543,313 @@ impl Task {
             created_at: now,
             updated_at: now,
             completed_at: None,
+            depends_on: Vec::new(),
+            links: Vec::new(),
+            recurrence: None,
+            series_id: None,
+            time_entries: Vec::new(),
+            notes: Vec::new(),
+            owner: None,
+            estimate_minutes: None,
+            parent_id: None,
+            tags: Vec::new(),
+            history: Vec::new(),
+            archived_at: None,
         }
     }
 
-    /// Mark task as completed, setting status to Done and record completion time.
-    pub fn complete(&mut self) {
-        self.status = TaskStatus::Done;
-        self.completed_at = Some(Utc::now());
-        self.updated_at = Utc::now();
+    /// Mark task as completed at `now`, setting status to Done and recording the completion time.
+    pub fn complete(&mut self, now: DateTime<Utc>) {
+        self.set_status(TaskStatus::Done, now);
+        self.completed_at = Some(now);
+        self.updated_at = now;
     }
 
     /// Mark task as in progress, setting status to InProgress.
-    pub fn start(&mut self) {
-        self.status = TaskStatus::InProgress;
-        self.updated_at = Utc::now();
+    pub fn start(&mut self, now: DateTime<Utc>) {
+        self.set_status(TaskStatus::InProgress, now);
+        self.updated_at = now;
+    }
+
+    /// Mark task as waiting on an external dependency, setting status to Waiting.
+    pub fn wait(&mut self, now: DateTime<Utc>) {
+        self.set_status(TaskStatus::Waiting, now);
+        self.updated_at = now;
     }
 
     /// Mark task as cancelled, setting status to Cancelled.
-    pub fn cancel(&mut self) {
-        self.status = TaskStatus::Cancelled;
-        self.updated_at = Utc::now();
+    pub fn cancel(&mut self, now: DateTime<Utc>) {
+        self.set_status(TaskStatus::Cancelled, now);
+        self.updated_at = now;
     }
 
-    /// Update task details selectively based on the provided options.
+    /// Reopen a finished task, setting status back to Todo and clearing the completion time.
+    pub fn reopen(&mut self, now: DateTime<Utc>) {
+        self.set_status(TaskStatus::Todo, now);
+        self.completed_at = None;
+        self.updated_at = now;
+    }
+
+    fn set_status(&mut self, status: TaskStatus, now: DateTime<Utc>) {
+        let old = self.status;
+        self.status = status;
+        self.record_change(now, "status", Some(old.label().to_string()), Some(status.label().to_string()));
+    }
+
+    /// Append a change event to the history, dropping the oldest beyond `MAX_HISTORY`.
+    ///
+    /// Does nothing when `old` and `new` are equal, so callers can record unconditionally.
+    pub fn record_change(&mut self, now: DateTime<Utc>, field: &str, old: Option<String>, new: Option<String>) {
+        if old == new {
+            return;
+        }
+        self.history.push(ChangeEvent { timestamp: now, field: field.to_string(), old, new });
+        if self.history.len() > MAX_HISTORY {
+            let excess = self.history.len() - MAX_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+
+    /// Update task details selectively based on the provided changes.
     ///
     /// Uses `UpdateValue` to determine whether to keep, clear, or set new values
-    /// for description, category, and due date.
-    pub fn update(&mut self, title: Option<String>, description: UpdateValue<String>, priority: Option<Priority>, category: UpdateValue<String>, due_date: UpdateValue<DateTime<Utc>>) {
-        if let Some(title) = title {
+    /// for description, category, and due date. `now` becomes the update time, unless
+    /// every change leaves its field as it was, in which case the task is untouched.
+    pub fn update(&mut self, changes: TaskUpdate, now: DateTime<Utc>) {
+        let before = self.clone();
+        if let Some(title) = changes.title {
             self.title = title;
         }
-        match description {
+        match changes.description {
             UpdateValue::Set(desc) => self.description = Some(desc),
             UpdateValue::Clear => self.description = None,
             UpdateValue::Keep => {} // Keep current value
         }
-        if let Some(priority) = priority {
+        if let Some(priority) = changes.priority {
             self.priority = priority;
         }
-        match category {
+        match changes.category {
             UpdateValue::Set(cat) => self.category = Some(cat),
             UpdateValue::Clear => self.category = None,
             UpdateValue::Keep => {} // Keep current value
         }
-        match due_date {
+        match changes.due_date {
             UpdateValue::Set(date) => self.due_date = Some(date),
             UpdateValue::Clear => self.due_date = None,
             UpdateValue::Keep => {} // Keep current value
         }
-        self.updated_at = Utc::now();
+        if let Some(links) = changes.links {
+            self.links = links;
+        }
+        match changes.owner {
+            UpdateValue::Set(owner) => self.owner = Some(owner),
+            UpdateValue::Clear => self.owner = None,
+            UpdateValue::Keep => {} // Keep current value
+        }
+        match changes.estimate_minutes {
+            UpdateValue::Set(minutes) => self.estimate_minutes = Some(minutes),
+            UpdateValue::Clear => self.estimate_minutes = None,
+            UpdateValue::Keep => {} // Keep current value
+        }
+        if let Some(depends_on) = changes.depends_on {
+            self.depends_on = depends_on;
+        }
+        if let Some(tags) = changes.tags {
+            self.tags = tags;
+        }
+        if *self == before {
+            return;
+        }
+        self.record_changes_since(&before, now);
+        self.updated_at = now;
     }
 
-    /// Returns true if the task is not completed and its due date has passed.
-    pub fn is_overdue(&self) -> bool {
-        if let Some(due_date) = self.due_date {
-            self.status != TaskStatus::Done && Utc::now() > due_date
-        } else {
-            false
+    /// Record a change event for every editable field that differs from `before`
+    pub fn record_changes_since(&mut self, before: &Task, now: DateTime<Utc>) {
+        let priority = |p: Priority| Some(format!("{:?}", p).to_lowercase());
+        let changes = [
+            ("title", Some(summarize(&before.title)), Some(summarize(&self.title))),
+            ("description", before.description.as_deref().map(summarize), self.description.as_deref().map(summarize)),
+            ("priority", priority(before.priority), priority(self.priority)),
+            ("category", before.category.clone(), self.category.clone()),
+            ("due_date", summarize_date(before.due_date), summarize_date(self.due_date)),
+            ("links", summarize_list(&before.links), summarize_list(&self.links)),
+            ("owner", before.owner.clone(), self.owner.clone()),
+            ("estimate_minutes", before.estimate_minutes.map(|m| m.to_string()), self.estimate_minutes.map(|m| m.to_string())),
+            ("depends_on", summarize_list(&before.depends_on), summarize_list(&self.depends_on)),
+            ("tags", summarize_list(&before.tags), summarize_list(&self.tags)),
+        ];
+        for (field, old, new) in changes {
+            self.record_change(now, field, old, new);
+        }
+    }
+
+    /// Merge another copy of this task (same ID), e.g. one from an import file.
+    ///
+    /// Scalar fields such as title, status and due date come from whichever copy was
+    /// updated more recently, this one on a tie. Tags, links and dependencies are
+    /// unioned; notes and time entries from both copies are combined in time order
+    /// with exact duplicates dropped. The earlier creation time is kept.
+    pub fn merge(&mut self, other: Task) {
+        let created_at = self.created_at.min(other.created_at);
+        let tags = normalize_tags(self.tags.iter().chain(&other.tags).cloned().collect());
+        let links = union(&self.links, &other.links);
+        let depends_on = union(&self.depends_on, &other.depends_on);
+
+        let mut notes: Vec<Note> = self.notes.iter().chain(&other.notes).cloned().collect();
+        notes.sort_by(|a, b| (a.timestamp, &a.text).cmp(&(b.timestamp, &b.text)));
+        notes.dedup();
+
+        // A finished copy of an interval wins over one still shown as running
+        let mut time_entries: Vec<TimeEntry> = self.time_entries.iter().chain(&other.time_entries).cloned().collect();
+        time_entries.sort_by_key(|entry| (entry.start, entry.end.is_none(), entry.end));
+        time_entries.dedup_by_key(|entry| entry.start);
+
+        let mut history: Vec<ChangeEvent> = self.history.iter().chain(&other.history).cloned().collect();
+        history.sort_by_key(|event| event.timestamp);
+        history.dedup();
+        history.drain(..history.len().saturating_sub(MAX_HISTORY));
+
+        if other.updated_at > self.updated_at {
+            *self = other;
+        }
+        self.created_at = created_at;
+        self.tags = tags;
+        self.links = links;
+        self.depends_on = depends_on;
+        self.notes = notes;
+        self.time_entries = time_entries;
+        self.history = history;
+    }
+
+    /// Fold a different task into this one, keeping this task's ID and title.
+    ///
+    /// Descriptions are concatenated, the category is taken from `other` only if this
+    /// task has none, and tags, links, dependencies, notes and time entries are
+    /// combined. The earlier creation time and the nearer due date are kept.
+    /// The resulting field changes and the merge itself are recorded in the history at `now`.
+    pub fn absorb(&mut self, other: Task, now: DateTime<Utc>) {
+        let before = self.clone();
+        let merged_from = format!("{} ({})", &other.id.to_string()[..8], summarize(&other.title));
+        self.description = match (self.description.take(), other.description) {
+            (Some(own), Some(theirs)) if !own.is_empty() && !theirs.is_empty() => Some(format!("{}\n{}", own, theirs)),
+            (Some(own), _) if !own.is_empty() => Some(own),
+            (_, theirs) => theirs,
+        };
+        if self.category.is_none() {
+            self.category = other.category;
+        }
+        self.tags = normalize_tags(self.tags.iter().chain(&other.tags).cloned().collect());
+        self.links = union(&self.links, &other.links);
+
+        let own_id = self.id;
+        self.depends_on = union(&self.depends_on, &other.depends_on);
+        self.depends_on.retain(|dep| *dep != own_id && *dep != other.id);
+
+        self.notes.extend(other.notes);
+        self.notes.sort_by(|a, b| (a.timestamp, &a.text).cmp(&(b.timestamp, &b.text)));
+        self.notes.dedup();
+        self.time_entries.extend(other.time_entries);
+        self.time_entries.sort_by_key(|entry| (entry.start, entry.end.is_none(), entry.end));
+
+        self.created_at = self.created_at.min(other.created_at);
+        self.due_date = match (self.due_date, other.due_date) {
+            (Some(own), Some(theirs)) => Some(own.min(theirs)),
+            (own, theirs) => own.or(theirs),
+        };
+        if self.estimate_minutes.is_none() {
+            self.estimate_minutes = other.estimate_minutes;
+        }
+        if self.owner.is_none() {
+            self.owner = other.owner;
+        }
+        self.record_changes_since(&before, now);
+        self.record_change(now, "merged_from", None, Some(merged_from));
+        self.updated_at = now;
+    }
+
+    /// The description with `text` appended on a new line, or `text` alone if there is none
+    pub fn appended_description(&self, text: &str) -> String {
+        match self.description.as_deref() {
+            Some(existing) if !existing.is_empty() => format!("{}\n{}", existing, text),
+            _ => text.to_string(),
+        }
+    }
+
+    /// Build the next instance of a recurring task, in the same series.
+    ///
+    /// The due date (if any) advances by one interval and the new instance is
+    /// created at `now`. Returns `None` for tasks without a recurrence.
+    pub fn next_occurrence(&self, now: DateTime<Utc>) -> Option<Task> {
+        let recurrence = self.recurrence?;
+        let mut next = Task::with_details(
+            self.title.clone(),
+            self.description.clone(),
+            self.priority,
+            self.category.clone(),
+            self.due_date.map(|due| recurrence.advance(due)),
+        );
+        next.links = self.links.clone();
+        next.estimate_minutes = self.estimate_minutes;
+        next.recurrence = Some(recurrence);
+        next.series_id = Some(self.series_id.unwrap_or(self.id));
+        next.created_at = now;
+        next.updated_at = now;
+        Some(next)
+    }
+
+    /// Validate the task, enforcing the given length limits on text fields.
+    ///
+    /// Runs the derived field validation as well, so this is a superset of `validate()`.
+    pub fn validate_with(&self, limits: &ValidationLimits) -> Result<(), ValidationErrors> {
+        let mut errors = self.validate().err().unwrap_or_default();
+
+        let title_len = self.title.chars().count();
+        if title_len == 0 || title_len > limits.title_max {
+            errors.add("title", ValidationError::new("length")
+                .with_message(format!("Title must be between 1-{} characters", limits.title_max).into()));
+        }
+        if self.description.as_ref().is_some_and(|d| d.chars().count() > limits.description_max) {
+            errors.add("description", ValidationError::new("length")
+                .with_message(format!("Description must not exceed {} characters", limits.description_max).into()));
+        }
+        if self.category.as_ref().is_some_and(|c| c.chars().count() > limits.category_max) {
+            errors.add("category", ValidationError::new("length")
+                .with_message(format!("Category must not exceed {} characters", limits.category_max).into()));
+        }
+        if self.notes.iter().any(|note| note.text.chars().count() > limits.note_max) {
+            errors.add("notes", ValidationError::new("length")
+                .with_message(format!("Notes must not exceed {} characters", limits.note_max).into()));
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Returns true if the task's category is exactly `name`
+    pub fn in_category(&self, name: &str) -> bool {
+        self.category.as_deref() == Some(name)
+    }
+
+    /// The currently running time entry, if any
+    pub fn running_time_entry(&self) -> Option<&TimeEntry> {
+        self.time_entries.iter().find(|entry| entry.end.is_none())
+    }
+
+    /// Total tracked time across all entries, including a running one measured up to `now`
+    pub fn total_tracked(&self, now: DateTime<Utc>) -> Duration {
+        self.time_entries.iter().map(|entry| entry.duration(now)).fold(Duration::zero(), |total, d| total + d)
+    }
+
+    /// Returns true if the task is not completed and its due date is before `now`.
+    pub fn is_overdue_at(&self, now: DateTime<Utc>) -> bool {
+        self.overdue_by(now).is_some()
+    }
+
+    /// How long past its due date the task is at `now`, or `None` if it isn't overdue
+    pub fn overdue_by(&self, now: DateTime<Utc>) -> Option<Duration> {
+        match self.due_date {
+            Some(due_date) if self.status != TaskStatus::Done && now > due_date => Some(now - due_date),
+            _ => None,
         }
     }
 
@@ -188,6 +858,7 @@ impl Task {
         match self.status {
             TaskStatus::Todo => "📋 TODO",
             TaskStatus::InProgress => "🔄 IN PROGRESS",
+            TaskStatus::Waiting => "⏳ WAITING",
             TaskStatus::Done => "✅ DONE",
             TaskStatus::Cancelled => "❌ CANCELLED",
         }
@@ -196,6 +867,7 @@ impl Task {
     /// Get formatted priority string with emoji for CLI display.
     pub fn priority_display(&self) -> &'static str {
         match self.priority {
+            Priority::None => "⚪ NONE",
             Priority::Low => "🟢 LOW",
             Priority::Medium => "🟡 MEDIUM",
             Priority::High => "🟠 HIGH",
@@ -210,19 +882,288 @@ impl Default for Task {
     }
 }
 
-/// Parse a datetime string in ISO 8601 format
-pub fn parse_datetime(date_str: &str) -> crate::error::Result<DateTime<Utc>> {
-    DateTime::parse_from_rfc3339(date_str)
+/// Parse an effort estimate into minutes: a bare number of minutes (`45`), or hours
+/// and minutes with units (`45m`, `2h`, `1h30m`)
+pub fn parse_estimate(input: &str) -> crate::error::Result<u32> {
+    let invalid = || crate::error::TaskError::ValidationError(format!(
+        "Invalid estimate '{}'. Use minutes (e.g. 45) or hours and minutes (e.g. 2h, 1h30m)", input
+    ));
+
+    let trimmed = input.trim().to_ascii_lowercase();
+    if let Ok(minutes) = trimmed.parse::<u32>() {
+        return Ok(minutes);
+    }
+
+    let mut total: u32 = 0;
+    let mut rest = trimmed.as_str();
+    let mut seen_units = String::new();
+    while !rest.is_empty() {
+        let unit_start = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let amount: u32 = rest[..unit_start].parse().map_err(|_| invalid())?;
+        let unit_end = rest[unit_start..].find(|c: char| c.is_ascii_digit()).map_or(rest.len(), |end| unit_start + end);
+        let minutes_per_unit = match &rest[unit_start..unit_end] {
+            "h" | "hr" | "hrs" => 60,
+            "m" | "min" | "mins" => 1,
+            _ => return Err(invalid()),
+        };
+        // Each unit once, hours before minutes
+        if seen_units.contains('m') || (minutes_per_unit == 60 && seen_units.contains('h')) {
+            return Err(invalid());
+        }
+        seen_units.push(if minutes_per_unit == 60 { 'h' } else { 'm' });
+        total = amount.checked_mul(minutes_per_unit).and_then(|minutes| total.checked_add(minutes)).ok_or_else(invalid)?;
+        rest = &rest[unit_end..];
+    }
+    if seen_units.is_empty() { Err(invalid()) } else { Ok(total) }
+}
+
+/// Check that `id` could name a task: a full UUID in any form `Uuid::parse_str`
+/// accepts, or the start of a hyphenated one such as the short ID `list` prints.
+///
+/// Returns `TaskError::InvalidId` for anything else, so a mistyped ID is reported as
+/// such rather than as a task that doesn't exist.
+pub fn validate_id(id: &str) -> crate::error::Result<()> {
+    const LAYOUT: &str = "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx";
+
+    let id = id.trim();
+    let is_prefix = !id.is_empty() && id.len() <= LAYOUT.len() && id.chars().zip(LAYOUT.chars())
+        .all(|(c, slot)| if slot == '-' { c == '-' } else { c.is_ascii_hexdigit() });
+    if is_prefix || Uuid::parse_str(id).is_ok() {
+        Ok(())
+    } else {
+        Err(crate::error::TaskError::InvalidId(id.to_string()))
+    }
+}
+
+/// Parse a signed duration like `+1w`, `-2d`, `12h` or `30m` (weeks, days, hours, minutes)
+pub fn parse_duration_offset(input: &str) -> crate::error::Result<Duration> {
+    let invalid = || crate::error::TaskError::ValidationError(format!(
+        "Invalid duration '{}'. Use a signed number with a unit: m, h, d or w (e.g. +1w, -2d)", input
+    ));
+
+    let trimmed = input.trim();
+    let (sign, rest) = match trimmed.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let unit_start = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let amount: i64 = rest[..unit_start].parse().map_err(|_| invalid())?;
+    let amount = sign * amount;
+
+    match &rest[unit_start..] {
+        "m" | "min" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None,
+    }
+    .ok_or_else(invalid)
+}
+
+/// Local datetime layouts accepted without an explicit UTC offset
+const NAIVE_DATETIME_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"];
+
+/// Describe `timestamp` relative to `now`: "just now", "5m ago", "3h ago", "2d ago",
+/// or for future times "in 3 hours", "in 2 days".
+pub fn humanize_relative(timestamp: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+
+    let seconds = (now - timestamp).num_seconds();
+    let (count, short, long) = match seconds.abs() {
+        s if s < MINUTE => return "just now".to_string(),
+        s if s < HOUR => (s / MINUTE, "m", "minute"),
+        s if s < DAY => (s / HOUR, "h", "hour"),
+        s if s < 30 * DAY => (s / DAY, "d", "day"),
+        s if s < 365 * DAY => (s / (30 * DAY), "mo", "month"),
+        s => (s / (365 * DAY), "y", "year"),
+    };
+
+    if seconds > 0 {
+        format!("{}{} ago", count, short)
+    } else {
+        format!("in {} {}{}", count, long, if count == 1 { "" } else { "s" })
+    }
+}
+
+/// Format accepted for date-only input
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parse a datetime string in ISO 8601 format and convert it to UTC for storage.
+///
+/// Input with an explicit offset (`2024-01-01T09:00:00-05:00`) is honored as given;
+/// input without one (`2024-01-01T09:00`) is interpreted as local time in `input_tz`.
+/// A bare date (`2024-01-01`) means midnight at the start of that day in `input_tz`.
+pub fn parse_datetime_in(date_str: &str, input_tz: Tz) -> crate::error::Result<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    let naive = NAIVE_DATETIME_FORMATS.iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date_str, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(date_str, DATE_FORMAT).ok().map(|date| date.and_time(NaiveTime::MIN)))
+        .ok_or_else(|| crate::error::TaskError::DateParseError(format!(
+            "Invalid date format: {}. Use a full ISO 8601 timestamp like '2024-01-01T12:00:00Z' \
+             ('2024-01-01T12:00' for local time in the input timezone, {}) or a date like '2024-01-01'",
+            date_str, input_tz
+        )))?;
+
+    local_to_utc(naive, input_tz, date_str)
+}
+
+/// Convert a wall-clock time in `input_tz` to UTC, naming `date_str` if it doesn't exist there
+fn local_to_utc(naive: NaiveDateTime, input_tz: Tz, date_str: &str) -> crate::error::Result<DateTime<Utc>> {
+    // Ambiguous local times (clocks turned back) resolve to the earlier instant
+    input_tz.from_local_datetime(&naive)
+        .earliest()
         .map(|dt| dt.with_timezone(&Utc))
-        .map_err(|_| crate::error::TaskError::DateParseError(
-            format!("Invalid date format: {}. Use ISO 8601 format like '2024-01-01T12:00:00Z'", date_str)
+        .ok_or_else(|| crate::error::TaskError::DateParseError(
+            format!("{} does not exist in timezone {} (skipped by a clock change)", date_str, input_tz)
         ))
 }
 
+/// Parse a due date, accepting relative phrases and the period keywords `eow`,
+/// `eom` and `eoq` as well as everything [`parse_datetime_in`] understands.
+///
+/// Bare dates (`2024-06-01`), phrases (`today`, `tomorrow`, `friday`, `next monday`,
+/// `next week`, `in 3 days`) and keywords carry no time of day, so they resolve to
+/// 23:59:59 on that day in `input_tz`.
+/// The keywords mean the last day of the current week, month or quarter. Weeks
+/// start on `week_start`, so with the default Monday `eow` is the coming Sunday
+/// (today, if today is Sunday). Phrases and keywords count from the day `now` falls on in `input_tz`.
+pub fn parse_due_date(date_str: &str, input_tz: Tz, week_start: Weekday, now: DateTime<Utc>) -> crate::error::Result<DateTime<Utc>> {
+    let today = now.with_timezone(&input_tz).date_naive();
+    let day = NaiveDate::parse_from_str(date_str.trim(), DATE_FORMAT).ok()
+        .or_else(|| period_end(date_str, today, week_start))
+        .or_else(|| relative_day(date_str, today));
+    match day {
+        Some(day) => local_to_utc(day.and_hms_opt(23, 59, 59).expect("23:59:59 is a valid time"), input_tz, date_str),
+        None if !is_datetime_format(date_str) => Err(crate::error::TaskError::DateParseError(format!(
+            "Invalid due date: {}. Use ISO 8601 like '2024-01-01T12:00:00Z', '2024-01-01T12:00' \
+             (local time in {}) or '2024-01-01', a phrase like 'today', 'tomorrow', 'friday', 'next monday', \
+             'next week' or 'in 3 days', or one of eow, eom, eoq",
+            date_str, input_tz
+        ))),
+        None => parse_datetime_in(date_str, input_tz),
+    }
+}
+
+/// Returns true if the input has the shape of a datetime [`parse_datetime_in`] accepts
+fn is_datetime_format(date_str: &str) -> bool {
+    DateTime::parse_from_rfc3339(date_str).is_ok()
+        || NAIVE_DATETIME_FORMATS.iter().any(|format| NaiveDateTime::parse_from_str(date_str, format).is_ok())
+}
+
+/// The day a relative phrase such as `tomorrow`, `friday` or `in 3 days` refers to.
+///
+/// A bare weekday is its next occurrence, today included; `next <weekday>` skips today.
+fn relative_day(phrase: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let phrase = phrase.trim().to_ascii_lowercase();
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let days_until = |day: Weekday| (7 + day.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+
+    match words.as_slice() {
+        ["today"] => Some(today),
+        ["tomorrow"] => today.succ_opt(),
+        ["next", "week"] => today.checked_add_signed(Duration::weeks(1)),
+        ["next", day] => {
+            let day: Weekday = day.parse().ok()?;
+            let days = match days_until(day) { 0 => 7, n => n };
+            today.checked_add_signed(Duration::days(days.into()))
+        }
+        ["in", count, unit] => {
+            let count: i64 = count.parse().ok()?;
+            let length = match unit.trim_end_matches('s') {
+                "day" => Duration::try_days(count)?,
+                "week" => Duration::try_weeks(count)?,
+                _ => return None,
+            };
+            today.checked_add_signed(length)
+        }
+        [day] => {
+            let day: Weekday = day.parse().ok()?;
+            today.checked_add_signed(Duration::days(days_until(day).into()))
+        }
+        _ => None,
+    }
+}
+
+/// Last day of the week, month or quarter containing `today`, for a period keyword
+fn period_end(keyword: &str, today: NaiveDate, week_start: Weekday) -> Option<NaiveDate> {
+    match keyword.trim().to_ascii_lowercase().as_str() {
+        "eow" => {
+            let week_end = week_start.pred();
+            let days_left = (7 + week_end.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+            Some(today + Duration::days(days_left.into()))
+        }
+        "eom" => last_day_before_month(today.year(), today.month() + 1),
+        "eoq" => last_day_before_month(today.year(), today.month0() / 3 * 3 + 4),
+        _ => None,
+    }
+}
+
+/// The day before the first of `month` (1-based; 13 rolls into January of the next year)
+fn last_day_before_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (year, month) = if month > 12 { (year + 1, month - 12) } else { (year, month) };
+    NaiveDate::from_ymd_opt(year, month, 1)?.pred_opt()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_period_end_keywords() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        // Wednesday 2024-02-14
+        let today = date(2024, 2, 14);
+
+        assert_eq!(period_end("eow", today, Weekday::Mon), Some(date(2024, 2, 18)));
+        assert_eq!(period_end("EOW", today, Weekday::Sun), Some(date(2024, 2, 17)));
+        assert_eq!(period_end("eow", date(2024, 2, 18), Weekday::Mon), Some(date(2024, 2, 18)));
+        assert_eq!(period_end("eom", today, Weekday::Mon), Some(date(2024, 2, 29)));
+        assert_eq!(period_end("eom", date(2024, 12, 3), Weekday::Mon), Some(date(2024, 12, 31)));
+        assert_eq!(period_end("eoq", today, Weekday::Mon), Some(date(2024, 3, 31)));
+        assert_eq!(period_end("eoq", date(2024, 11, 30), Weekday::Mon), Some(date(2024, 12, 31)));
+        assert_eq!(period_end("tomorrow", today, Weekday::Mon), None);
+
+        let due = parse_due_date("eom", Tz::UTC, Weekday::Mon, Utc::now()).unwrap();
+        assert_eq!(due.time(), chrono::NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+    }
+
+    #[test]
+    fn test_relative_day_phrases() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        // Wednesday 2024-02-14
+        let today = date(2024, 2, 14);
+
+        assert_eq!(relative_day("today", today), Some(today));
+        assert_eq!(relative_day("Tomorrow", today), Some(date(2024, 2, 15)));
+        assert_eq!(relative_day("next week", today), Some(date(2024, 2, 21)));
+        assert_eq!(relative_day("in 3 days", today), Some(date(2024, 2, 17)));
+        assert_eq!(relative_day("in 1 week", today), Some(date(2024, 2, 21)));
+        assert_eq!(relative_day("friday", today), Some(date(2024, 2, 16)));
+        assert_eq!(relative_day("wed", today), Some(today));
+        assert_eq!(relative_day("next wednesday", today), Some(date(2024, 2, 21)));
+        assert_eq!(relative_day("next monday", today), Some(date(2024, 2, 19)));
+        assert_eq!(relative_day("in three days", today), None);
+        assert_eq!(relative_day("someday", today), None);
+
+        let now = Utc.with_ymd_and_hms(2024, 2, 14, 23, 59, 59).unwrap();
+        let due = parse_due_date("tomorrow", Tz::UTC, Weekday::Mon, now).unwrap();
+        assert_eq!(due.date_naive(), date(2024, 2, 15));
+        assert!(parse_due_date("2024-01-01T12:00:00Z", Tz::UTC, Weekday::Mon, now).is_ok());
+        let error = parse_due_date("whenever", Tz::UTC, Weekday::Mon, now).unwrap_err().to_string();
+        assert!(error.contains("next monday"));
+
+        // 23:59:59 UTC on the 14th is already the 15th in Tokyo, so "tomorrow" there is the 16th,
+        // ending at 23:59:59 Tokyo time
+        let tokyo: Tz = "Asia/Tokyo".parse().unwrap();
+        let due = parse_due_date("tomorrow", tokyo, Weekday::Mon, now).unwrap();
+        assert_eq!(due, Utc.with_ymd_and_hms(2024, 2, 16, 14, 59, 59).unwrap());
+    }
+
     #[test]
     fn test_task_creation() {
         let task = Task::new("Test Task".to_string());
@@ -240,11 +1181,12 @@ mod tests {
         let mut task = Task::new("Test Task".to_string());
         let before_complete = task.updated_at;
 
-        task.complete();
+        let done_at = before_complete + chrono::Duration::minutes(5);
+        task.complete(done_at);
 
         assert_eq!(task.status, TaskStatus::Done);
-        assert!(task.completed_at.is_some());
-        assert!(task.updated_at >= before_complete);
+        assert_eq!(task.completed_at, Some(done_at));
+        assert_eq!(task.updated_at, done_at);
     }
 
     #[test]
@@ -253,24 +1195,33 @@ mod tests {
         task.description = Some("Original desc".to_string());
         task.category = Some("Work".to_string());
 
-        task.update(
-            Some("Updated".to_string()),
-            UpdateValue::Set("New desc".to_string()),
-            Some(Priority::High),
-            UpdateValue::Clear,
-            UpdateValue::Keep,
-        );
+        task.update(TaskUpdate {
+            title: Some("Updated".to_string()),
+            description: UpdateValue::Set("New desc".to_string()),
+            priority: Some(Priority::High),
+            category: UpdateValue::Clear,
+            due_date: UpdateValue::Keep,
+            links: None,
+            owner: UpdateValue::Set("ana".to_string()),
+            estimate_minutes: UpdateValue::Set(90),
+            depends_on: None,
+            tags: Some(vec!["q3".to_string()]),
+        }, Utc::now());
 
         assert_eq!(task.title, "Updated");
         assert_eq!(task.description, Some("New desc".to_string()));
         assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.owner.as_deref(), Some("ana"));
+        assert_eq!(task.estimate_minutes, Some(90));
         assert!(task.category.is_none()); // Cleared
+        assert_eq!(task.tags, vec!["q3"]);
     }
 
     #[test]
     fn test_task_is_overdue() {
-        let past_date = Utc::now() - chrono::Duration::hours(1);
-        let future_date = Utc::now() + chrono::Duration::hours(1);
+        let now = Utc::now();
+        let past_date = now - chrono::Duration::hours(1);
+        let future_date = now + chrono::Duration::hours(1);
 
         let overdue_task = Task::with_details(
             "Overdue".to_string(),
@@ -288,7 +1239,314 @@ mod tests {
             Some(future_date),
         );
 
-        assert!(overdue_task.is_overdue());
-        assert!(!upcoming_task.is_overdue());
+        assert!(overdue_task.is_overdue_at(now));
+        assert!(!upcoming_task.is_overdue_at(now));
+    }
+
+    #[test]
+    fn test_task_links_validation() {
+        let mut task = Task::new("Linked".to_string());
+        task.links = vec!["https://example.com/pr/1".to_string()];
+        assert!(task.validate().is_ok());
+
+        task.links.push("   ".to_string());
+        assert!(task.validate().is_err());
+
+        task.links = vec!["https://example.com".to_string(); MAX_LINKS + 1];
+        assert!(task.validate().is_err());
+    }
+
+    #[test]
+    fn test_tag_validation_and_normalization() {
+        let mut task = Task::new("Tagged".to_string());
+        task.tags = normalize_tags(vec![" urgent ".to_string(), "home".to_string(), "urgent".to_string()]);
+        assert_eq!(task.tags, vec!["urgent", "home"]);
+        assert!(task.validate().is_ok());
+
+        task.tags.push(String::new());
+        assert!(task.validate().is_err());
+
+        task.tags = vec!["x".repeat(MAX_TAG_LENGTH + 1)];
+        assert!(task.validate().is_err());
+    }
+
+    #[test]
+    fn test_status_sets() {
+        assert!(StatusSet::ACTIVE.contains(TaskStatus::InProgress));
+        assert!(!StatusSet::ACTIVE.contains(TaskStatus::Waiting));
+        assert!(StatusSet::OPEN.contains(TaskStatus::Cancelled));
+        assert!(!StatusSet::OPEN.contains(TaskStatus::Done));
+        assert_eq!(StatusSet::OPEN.intersect(StatusSet::CLOSED), StatusSet::of(&[TaskStatus::Cancelled]));
+        assert_eq!(StatusSet::ACTIVE.without(TaskStatus::Todo).without(TaskStatus::InProgress), StatusSet::of(&[]));
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        assert!(TaskStatus::Todo.can_transition_to(TaskStatus::Waiting));
+        assert!(TaskStatus::Waiting.can_transition_to(TaskStatus::InProgress));
+        assert!(TaskStatus::InProgress.can_transition_to(TaskStatus::Done));
+        assert!(TaskStatus::Done.can_transition_to(TaskStatus::Todo));
+        assert!(!TaskStatus::Done.can_transition_to(TaskStatus::Waiting));
+        assert!(!TaskStatus::Cancelled.can_transition_to(TaskStatus::InProgress));
+        assert!(!TaskStatus::Waiting.can_transition_to(TaskStatus::Waiting));
+
+        let task: Task = serde_json::from_str(&serde_json::to_string(&{
+            let mut task = Task::new("Blocked".to_string());
+            task.wait(Utc::now());
+            task
+        }).unwrap()).unwrap();
+        assert_eq!(task.status, TaskStatus::Waiting);
+        assert_eq!(serde_json::to_value(TaskStatus::Waiting).unwrap(), "waiting");
+    }
+
+    #[test]
+    fn test_validate_with_custom_limits() {
+        let defaults = ValidationLimits::default();
+        let mut task = Task::new("A".repeat(200));
+        assert!(task.validate_with(&defaults).is_ok());
+
+        task.title = "A".repeat(201);
+        assert!(task.validate_with(&defaults).is_err());
+
+        let tight = ValidationLimits { title_max: 10, category_max: 3, ..defaults };
+        task.title = "Short".to_string();
+        task.category = Some("home".to_string());
+        let errors = task.validate_with(&tight).unwrap_err();
+        assert!(errors.field_errors().contains_key("category"));
+        assert!(!errors.field_errors().contains_key("title"));
+
+        task.title = String::new();
+        assert!(task.validate_with(&defaults).unwrap_err().field_errors().contains_key("title"));
+
+        task.title = "Short".to_string();
+        task.category = None;
+        task.notes = vec![Note { timestamp: Utc::now(), text: "n".repeat(20) }];
+        assert!(task.validate_with(&defaults).is_ok());
+        let errors = task.validate_with(&ValidationLimits { note_max: 10, ..defaults }).unwrap_err();
+        assert!(errors.field_errors().contains_key("notes"));
+
+        assert!(ValidationLimits { description_max: 0, ..defaults }.check().is_err());
+        assert!(defaults.check().is_ok());
+    }
+
+    #[test]
+    fn test_parse_datetime_with_offset_and_input_timezone() {
+        let expected = Utc.with_ymd_and_hms(2024, 1, 1, 14, 0, 0).unwrap();
+
+        assert_eq!(parse_datetime_in("2024-01-01T09:00:00-05:00", Tz::UTC).unwrap(), expected);
+        assert_eq!(parse_datetime_in("2024-01-01T09:00", chrono_tz::America::New_York).unwrap(), expected);
+        assert_eq!(parse_datetime_in("2024-01-01T14:00:00", Tz::UTC).unwrap(), expected);
+        assert!(parse_datetime_in("next tuesday-ish", Tz::UTC).is_err());
+    }
+
+    #[test]
+    fn test_humanize_relative() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        assert_eq!(humanize_relative(now - Duration::seconds(20), now), "just now");
+        assert_eq!(humanize_relative(now - Duration::minutes(5), now), "5m ago");
+        assert_eq!(humanize_relative(now - Duration::minutes(200), now), "3h ago");
+        assert_eq!(humanize_relative(now - Duration::days(2), now), "2d ago");
+        assert_eq!(humanize_relative(now - Duration::days(75), now), "2mo ago");
+        assert_eq!(humanize_relative(now - Duration::days(800), now), "2y ago");
+        assert_eq!(humanize_relative(now + Duration::days(2), now), "in 2 days");
+        assert_eq!(humanize_relative(now + Duration::hours(1), now), "in 1 hour");
+    }
+
+    #[test]
+    fn test_parse_datetime_accepts_date_only() {
+        assert_eq!(parse_datetime_in("2024-06-01", Tz::UTC).unwrap(), Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap());
+        assert_eq!(
+            parse_datetime_in("2024-06-01", chrono_tz::Europe::Berlin).unwrap(),
+            Utc.with_ymd_and_hms(2024, 5, 31, 22, 0, 0).unwrap()
+        );
+        assert_eq!(parse_datetime_in("2024-06-01T08:30:00Z", Tz::UTC).unwrap(), Utc.with_ymd_and_hms(2024, 6, 1, 8, 30, 0).unwrap());
+
+        let error = parse_datetime_in("06/01/2024", Tz::UTC).unwrap_err().to_string();
+        assert!(error.contains("2024-01-01T12:00:00Z") && error.contains("'2024-01-01'"));
+        assert!(parse_datetime_in("2024-13-01", Tz::UTC).is_err());
+
+        // Due dates given as a bare day last until the end of it
+        let due = parse_due_date("2024-06-01", Tz::UTC, Weekday::Mon, Utc::now()).unwrap();
+        assert_eq!(due, Utc.with_ymd_and_hms(2024, 6, 1, 23, 59, 59).unwrap());
+        let new_york: Tz = "America/New_York".parse().unwrap();
+        let due = parse_due_date("2024-06-01", new_york, Weekday::Mon, Utc::now()).unwrap();
+        assert_eq!(due, Utc.with_ymd_and_hms(2024, 6, 2, 3, 59, 59).unwrap());
+    }
+
+    #[test]
+    fn test_next_occurrence_shares_series() {
+        let due = Utc.with_ymd_and_hms(2024, 1, 31, 17, 0, 0).unwrap();
+        let mut task = Task::with_details("Report".to_string(), None, Priority::High, None, Some(due));
+        assert!(task.next_occurrence(due).is_none());
+
+        task.recurrence = Some(Recurrence::Monthly);
+        let next = task.next_occurrence(due).unwrap();
+        assert_ne!(next.id, task.id);
+        assert_eq!(next.created_at, due);
+        assert_eq!(next.series_id, Some(task.id));
+        assert_eq!(next.status, TaskStatus::Todo);
+        assert_eq!(next.due_date, Some(Utc.with_ymd_and_hms(2024, 2, 29, 17, 0, 0).unwrap()));
+
+        let after = next.next_occurrence(due).unwrap();
+        assert_eq!(after.series_id, Some(task.id));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_estimate() {
+        assert_eq!(parse_estimate("45").unwrap(), 45);
+        assert_eq!(parse_estimate("45m").unwrap(), 45);
+        assert_eq!(parse_estimate("2h").unwrap(), 120);
+        assert_eq!(parse_estimate(" 1H30M ").unwrap(), 90);
+        for invalid in ["", "h", "1.5h", "30m1h", "1h1h", "3d", "-5"] {
+            assert!(parse_estimate(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_estimate_is_validated() {
+        let mut task = Task::new("Plan".to_string());
+        task.estimate_minutes = Some(90);
+        assert!(task.validate().is_ok());
+        for absurd in [0, 10081] {
+            task.estimate_minutes = Some(absurd);
+            assert!(task.validate().is_err(), "{}", absurd);
+        }
+    }
+
+    #[test]
+    fn test_validate_id() {
+        assert!(validate_id("3f2a9c1b").is_ok());
+        assert!(validate_id("3F2A9C1B-").is_ok());
+        assert!(validate_id("3f2a9c1b-0000-4000-8000-000000000001").is_ok());
+        assert!(validate_id("3f2a9c1b000040008000000000000001").is_ok());
+        for invalid in ["", "report", "3f2a9c1bz", "3f2a-9c1b", "3f2a9c1b-0000-4000-8000-0000000000011"] {
+            assert!(matches!(validate_id(invalid), Err(crate::error::TaskError::InvalidId(_))), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parse_duration_offset() {
+        assert_eq!(parse_duration_offset("+1w").unwrap(), Duration::weeks(1));
+        assert_eq!(parse_duration_offset("-2d").unwrap(), Duration::days(-2));
+        assert_eq!(parse_duration_offset("36h").unwrap(), Duration::hours(36));
+        assert!(parse_duration_offset("2 days").is_err());
+        assert!(parse_duration_offset("d").is_err());
+    }
+
+    #[test]
+    fn test_in_category_is_exact() {
+        let mut task = Task::new("Task".to_string());
+        assert!(!task.in_category("work"));
+        task.category = Some("Work".to_string());
+        assert!(task.in_category("Work"));
+        assert!(!task.in_category("work"));
+        assert!(!task.in_category("home"));
+    }
+
+    #[test]
+    fn test_merge_combines_collections_and_keeps_newer_scalars() {
+        let t = |hour| Utc.with_ymd_and_hms(2024, 3, 1, hour, 0, 0).unwrap();
+        let shared_note = Note { timestamp: t(9), text: "Kickoff".to_string() };
+
+        let mut stored = Task::new("Old title".to_string());
+        stored.created_at = t(8);
+        stored.updated_at = t(10);
+        stored.category = Some("work".to_string());
+        stored.tags = vec!["a".to_string(), "b".to_string()];
+        stored.links = vec!["https://example.com/1".to_string()];
+        stored.notes = vec![shared_note.clone(), Note { timestamp: t(11), text: "Stored".to_string() }];
+        stored.time_entries = vec![TimeEntry { start: t(9), end: None }];
+
+        let mut incoming = stored.clone();
+        incoming.title = "New title".to_string();
+        incoming.category = None;
+        incoming.status = TaskStatus::Done;
+        incoming.created_at = t(7);
+        incoming.updated_at = t(12);
+        incoming.tags = vec!["b".to_string(), "c".to_string()];
+        incoming.links = vec!["https://example.com/2".to_string()];
+        incoming.notes = vec![shared_note.clone(), Note { timestamp: t(10), text: "Incoming".to_string() }];
+        incoming.time_entries = vec![TimeEntry { start: t(9), end: Some(t(10)) }];
+
+        let mut merged = stored.clone();
+        merged.merge(incoming.clone());
+        assert_eq!(merged.title, "New title");
+        assert_eq!(merged.status, TaskStatus::Done);
+        assert_eq!(merged.category, None);
+        assert_eq!(merged.created_at, t(7));
+        assert_eq!(merged.updated_at, t(12));
+        assert_eq!(merged.tags, vec!["a", "b", "c"]);
+        assert_eq!(merged.links, vec!["https://example.com/1", "https://example.com/2"]);
+        let texts: Vec<&str> = merged.notes.iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(texts, vec!["Kickoff", "Incoming", "Stored"]);
+        assert_eq!(merged.time_entries, vec![TimeEntry { start: t(9), end: Some(t(10)) }]);
+
+        // An older copy contributes its collections but not its fields
+        let mut kept = incoming.clone();
+        kept.merge(stored);
+        assert_eq!(kept.title, "New title");
+        assert_eq!(kept.tags, vec!["b", "c", "a"]);
+        assert_eq!(kept.notes.len(), 3);
+    }
+
+    #[test]
+    fn test_history_records_changes_and_stays_bounded() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let mut task = Task::new("Draft".to_string());
+
+        task.update(TaskUpdate { title: Some("Final".to_string()), priority: Some(Priority::Medium), ..Default::default() }, now);
+        assert_eq!(task.history, vec![ChangeEvent {
+            timestamp: now,
+            field: "title".to_string(),
+            old: Some("Draft".to_string()),
+            new: Some("Final".to_string()),
+        }]);
+
+        task.start(now);
+        task.complete(now);
+        let statuses: Vec<_> = task.history[1..].iter().map(|e| (e.old.as_deref().unwrap(), e.new.as_deref().unwrap())).collect();
+        assert_eq!(statuses, [("todo", "in progress"), ("in progress", "done")]);
+
+        task.update(TaskUpdate { description: UpdateValue::Set("x".repeat(500)), ..Default::default() }, now);
+        assert!(task.history.last().unwrap().new.as_ref().unwrap().chars().count() <= CHANGE_SUMMARY_LENGTH);
+
+        for i in 0..MAX_HISTORY {
+            task.update(TaskUpdate { title: Some(format!("Title {}", i)), ..Default::default() }, now);
+        }
+        assert_eq!(task.history.len(), MAX_HISTORY);
+        assert_eq!(task.history.last().unwrap().new.as_deref(), Some("Title 99"));
+    }
+
+    #[test]
+    fn test_absorb_combines_fields_and_keeps_identity() {
+        let t = |day| Utc.with_ymd_and_hms(2024, 3, day, 9, 0, 0).unwrap();
+
+        let mut keep = Task::new("Keep".to_string());
+        keep.description = Some("First half".to_string());
+        keep.created_at = t(5);
+        keep.due_date = Some(t(20));
+        keep.tags = vec!["a".to_string(), "b".to_string()];
+
+        let mut other = Task::new("Other".to_string());
+        other.description = Some("Second half".to_string());
+        other.category = Some("work".to_string());
+        other.created_at = t(2);
+        other.due_date = Some(t(10));
+        other.tags = vec!["b".to_string(), "c".to_string()];
+        other.depends_on = vec![keep.id];
+        other.notes = vec![Note { timestamp: t(3), text: "Moved".to_string() }];
+
+        let id = keep.id;
+        keep.absorb(other, t(30));
+        assert_eq!(keep.id, id);
+        assert_eq!(keep.title, "Keep");
+        assert_eq!(keep.description.as_deref(), Some("First half\nSecond half"));
+        assert_eq!(keep.category.as_deref(), Some("work"));
+        assert_eq!(keep.tags, vec!["a", "b", "c"]);
+        assert_eq!(keep.created_at, t(2));
+        assert_eq!(keep.due_date, Some(t(10)));
+        assert!(keep.depends_on.is_empty(), "a dependency on itself must be dropped");
+        assert_eq!(keep.notes.len(), 1);
+        assert_eq!(keep.updated_at, t(30));
+    }
+}
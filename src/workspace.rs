@@ -0,0 +1,67 @@
+use crate::error::{Result, TaskError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Default workspace file, looked up in the current directory
+pub const DEFAULT_WORKSPACE_FILE: &str = "workspace.json";
+
+/// Named projects, each mapped to its own task file.
+///
+/// Stored as JSON, e.g. `{"projects": {"work": "work/tasks.json"}}`. Relative
+/// paths are resolved against the directory containing the workspace file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Workspace {
+    #[serde(default)]
+    pub projects: BTreeMap<String, PathBuf>,
+
+    /// Directory relative project paths are resolved against
+    #[serde(skip)]
+    base_dir: PathBuf,
+}
+
+impl Workspace {
+    /// Load a workspace definition from disk
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path).map_err(|e| {
+            TaskError::FileOperationError(format!("cannot read workspace file {}: {}", path.display(), e))
+        })?;
+        let mut workspace: Workspace = serde_json::from_str(&data)?;
+        workspace.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(workspace)
+    }
+
+    /// Get the task file for a named project
+    pub fn resolve(&self, name: &str) -> Result<PathBuf> {
+        let path = self.projects.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.projects.keys().map(String::as_str).collect();
+            TaskError::ProjectNotFound(format!("{} (configured: {})", name, known.join(", ")))
+        })?;
+        Ok(self.base_dir.join(path))
+    }
+
+    /// Iterate over project names and their resolved task files, sorted by name
+    pub fn projects(&self) -> impl Iterator<Item = (&str, PathBuf)> {
+        self.projects.iter().map(|(name, path)| (name.as_str(), self.base_dir.join(path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_relative_to_workspace_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_WORKSPACE_FILE);
+        std::fs::write(&path, r#"{"projects": {"work": "work.json", "home": "/abs/home.json"}}"#).unwrap();
+
+        let workspace = Workspace::load(&path).unwrap();
+        assert_eq!(workspace.resolve("work").unwrap(), dir.path().join("work.json"));
+        assert_eq!(workspace.resolve("home").unwrap(), PathBuf::from("/abs/home.json"));
+        assert!(matches!(workspace.resolve("missing"), Err(TaskError::ProjectNotFound(_))));
+
+        let names: Vec<&str> = workspace.projects().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["home", "work"]);
+    }
+}
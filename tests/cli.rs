@@ -0,0 +1,1077 @@
+//! End-to-end tests that run the built binary against a temporary data file.
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// A temp directory holding the `tasks.json` every command in a test operates on
+struct Sandbox {
+    dir: TempDir,
+}
+
+impl Sandbox {
+    fn new() -> Self {
+        Self { dir: tempfile::tempdir().unwrap() }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.path().join(name)
+    }
+
+    /// The CLI pointed at this sandbox's data file, with plain-text output
+    fn cmd(&self) -> Command {
+        self.cmd_for(&self.path("tasks.json"))
+    }
+
+    fn cmd_for(&self, file: &Path) -> Command {
+        let mut cmd = cargo_bin_cmd!("task-manager");
+        cmd.current_dir(self.dir.path())
+            .env_remove("TASK_PROJECT")
+            .env_remove("TASK_MANAGER_FILE")
+            .env_remove("TASK_MANAGER_KEY")
+            .env_remove("TASK_MANAGER_CONFIG")
+            .env("XDG_CONFIG_HOME", self.dir.path())
+            .arg("--color")
+            .arg("never")
+            .arg("--file")
+            .arg(file);
+        cmd
+    }
+
+    /// Add a task and return its ID as printed by the CLI
+    fn add(&self, args: &[&str]) -> String {
+        let output = self.cmd().arg("add").args(args).assert().success().get_output().stdout.clone();
+        let stdout = String::from_utf8(output).unwrap();
+        stdout.rsplit("ID: ").next().unwrap().trim().to_string()
+    }
+
+    /// Tasks currently stored in a data file
+    fn tasks_in(&self, file: &Path) -> Vec<Value> {
+        serde_json::from_str(&std::fs::read_to_string(file).unwrap()).unwrap()
+    }
+}
+
+#[test]
+fn add_then_list_shows_the_task() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Write report", "--priority", "high", "--category", "work"]);
+    assert_eq!(id.len(), 36);
+
+    sandbox.cmd()
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Write report"));
+
+    let tasks = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["priority"], "high");
+    assert_eq!(tasks[0]["category"], "work");
+}
+
+#[test]
+fn complete_marks_the_task_done_on_disk() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Ship it"]);
+
+    sandbox.cmd().args(["complete", &id]).assert().success();
+
+    let tasks = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    assert_eq!(tasks[0]["status"], "done");
+    assert!(tasks[0]["completed_at"].is_string());
+
+    sandbox.cmd()
+        .args(["list", "--status", "todo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ship it").not());
+}
+
+#[test]
+fn complete_accepts_several_ids_and_continues_past_failures() {
+    let sandbox = Sandbox::new();
+    let first = sandbox.add(&["First"]);
+    let second = sandbox.add(&["Second"]);
+
+    sandbox.cmd()
+        .args(["complete", &first, "00000000-0000-0000-0000-000000000000", &second])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(&first).and(predicate::str::contains(&second)))
+        .stdout(predicate::str::contains("completed 2, 1 failed (not found)"))
+        .stderr(predicate::str::contains("✗ 00000000-0000-0000-0000-000000000000"));
+
+    let tasks = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    assert!(tasks.iter().all(|task| task["status"] == "done"));
+}
+
+#[test]
+fn complete_all_in_category_finishes_only_open_tasks_there() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Draft", "--category", "writing"]);
+    sandbox.add(&["Edit", "--category", "writing"]);
+    sandbox.add(&["Pitch", "--category", "Writing"]);
+    let dropped = sandbox.add(&["Outline", "--category", "writing"]);
+    sandbox.add(&["Invoice", "--category", "admin"]);
+    sandbox.cmd().args(["cancel", &dropped]).assert().success();
+
+    sandbox.cmd()
+        .args(["complete", "--all-in-category", "writing"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("completed 2"));
+
+    let tasks = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    let status = |title: &str| tasks.iter().find(|t| t["title"] == title).unwrap()["status"].clone();
+    assert_eq!(status("Draft"), "done");
+    assert_eq!(status("Edit"), "done");
+    assert_eq!(status("Outline"), "cancelled");
+    assert_eq!(status("Pitch"), "todo", "categories match by exact name");
+    assert_eq!(status("Invoice"), "todo");
+
+    sandbox.cmd()
+        .args(["complete", "--all-in-category", "writing"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No open tasks in category"));
+}
+
+#[test]
+fn completing_a_critical_task_asks_first_when_configured() {
+    let sandbox = Sandbox::new();
+    let routine = sandbox.add(&["Routine", "--priority", "low"]);
+    let critical = sandbox.add(&["Release", "--priority", "critical"]);
+    let status_of = |id: &str| {
+        sandbox.tasks_in(&sandbox.path("tasks.json"))
+            .into_iter()
+            .find(|task| task["id"] == id)
+            .map(|task| task["status"].clone())
+            .unwrap()
+    };
+
+    sandbox.cmd().args(["--confirm-complete-priority", "critical", "complete", &routine]).assert().success();
+    assert_eq!(status_of(&routine), "done");
+
+    sandbox.cmd()
+        .args(["--confirm-complete-priority", "critical", "complete", &critical])
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped"));
+    assert_eq!(status_of(&critical), "todo");
+
+    sandbox.cmd().args(["--confirm-complete-priority", "critical", "complete", &critical, "--yes"]).assert().success();
+    assert_eq!(status_of(&critical), "done");
+}
+
+#[test]
+fn edit_last_applies_changes_made_in_the_editor() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Older"]);
+    sandbox.add(&["Draft", "--category", "inbox"]);
+
+    sandbox.cmd()
+        .args(["edit", "--last"])
+        .env_remove("VISUAL")
+        .env("EDITOR", "sed -i -e s/^title:.*/title:Final/ -e s/^category:.*/category:/")
+        .assert()
+        .success();
+
+    let tasks = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    let edited = tasks.iter().find(|task| task["title"] == "Final").unwrap();
+    assert!(edited["category"].is_null());
+    assert!(tasks.iter().any(|task| task["title"] == "Older"));
+}
+
+#[test]
+fn export_then_import_round_trips_tasks() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["First"]);
+    sandbox.add(&["Second", "--description", "with details"]);
+
+    let export = sandbox.path("export.json");
+    sandbox.cmd().arg("export").arg(&export).assert().success();
+    assert!(export.exists());
+
+    let other = sandbox.path("other.json");
+    sandbox.cmd_for(&other).arg("import").arg(&export).assert().success();
+
+    let mut titles: Vec<String> = sandbox.tasks_in(&other)
+        .iter()
+        .map(|task| task["title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, vec!["First", "Second"]);
+}
+
+#[test]
+fn dash_exports_to_stdout_and_imports_from_stdin() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Piped"]);
+
+    let output = sandbox.cmd().args(["export", "-"]).assert().success().get_output().stdout.clone();
+    let exported: Vec<Value> = serde_json::from_slice(&output).unwrap();
+    assert_eq!(exported[0]["title"], "Piped");
+    assert!(!sandbox.path("-").exists());
+
+    let other = sandbox.path("other.json");
+    sandbox.cmd_for(&other)
+        .args(["import", "-"])
+        .write_stdin(output.clone())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 tasks from stdin"));
+    assert_eq!(sandbox.tasks_in(&other)[0]["title"], "Piped");
+
+    sandbox.cmd_for(&other)
+        .args(["--max-import-size", "10", "import", "-"])
+        .write_stdin(output)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Input too large"));
+}
+
+#[test]
+fn restore_brings_back_tasks_from_before_delete_all() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Keep me"]);
+    sandbox.add(&["Me too"]);
+    sandbox.cmd().args(["delete-all", "--force"]).assert().success();
+    assert!(sandbox.tasks_in(&sandbox.path("tasks.json")).is_empty());
+
+    sandbox.cmd()
+        .arg("restore")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Backups of").and(predicate::str::contains(" 2. ")));
+
+    sandbox.cmd()
+        .args(["restore", "1", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored 2 tasks"));
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json")).len(), 2);
+
+    sandbox.cmd().args(["restore", "9", "--force"]).assert().failure().stderr(predicate::str::contains("No backup #9"));
+}
+
+#[test]
+fn encrypted_data_file_needs_the_right_passphrase() {
+    let sandbox = Sandbox::new();
+    sandbox.cmd()
+        .env("TASK_MANAGER_KEY", "correct horse")
+        .args(["--encrypt", "add", "Secret plan"])
+        .assert()
+        .success();
+
+    let stored = std::fs::read(sandbox.path("tasks.json")).unwrap();
+    assert!(stored.starts_with(b"TMCRYPT"));
+    assert!(!String::from_utf8_lossy(&stored).contains("Secret plan"));
+
+    // Encryption is detected from the file; --encrypt is only needed to start it
+    sandbox.cmd()
+        .env("TASK_MANAGER_KEY", "correct horse")
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Secret plan"));
+
+    sandbox.cmd()
+        .env("TASK_MANAGER_KEY", "battery staple")
+        .args(["add", "Would overwrite"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Error: Wrong passphrase"));
+    assert_eq!(std::fs::read(sandbox.path("tasks.json")).unwrap(), stored);
+}
+
+#[test]
+fn encrypting_a_plain_file_leaves_no_plaintext_backups() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Secret plan"]);
+    sandbox.add(&["Another secret"]);
+    sandbox.cmd().env("TASK_MANAGER_KEY", "correct horse").args(["--encrypt", "list"]).assert().success();
+
+    let backups: Vec<PathBuf> = std::fs::read_dir(sandbox.dir.path()).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().contains(".bak-"))
+        .collect();
+    assert_eq!(backups.len(), 2);
+    for backup in backups {
+        let stored = std::fs::read(&backup).unwrap();
+        assert!(stored.starts_with(b"TMCRYPT"), "{} is not encrypted", backup.display());
+        assert!(!String::from_utf8_lossy(&stored).to_lowercase().contains("secret"));
+    }
+
+    // The encrypted backups still restore with the passphrase
+    sandbox.cmd()
+        .env("TASK_MANAGER_KEY", "correct horse")
+        .args(["restore", "1", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored 2 tasks"));
+}
+
+#[test]
+fn export_refuses_to_overwrite_an_existing_file() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Keep me"]);
+    let export = sandbox.path("export.json");
+    std::fs::write(&export, "precious").unwrap();
+
+    sandbox.cmd().arg("export").arg(&export).assert().failure();
+    assert_eq!(std::fs::read_to_string(&export).unwrap(), "precious");
+
+    sandbox.cmd().arg("export").arg(&export).arg("--rename-on-conflict").assert().success();
+    assert_eq!(std::fs::read_to_string(&export).unwrap(), "precious");
+    assert_eq!(sandbox.tasks_in(&sandbox.path("export-1.json")).len(), 1);
+
+    sandbox.cmd().arg("export").arg(&export).arg("--overwrite").assert().success();
+    assert_eq!(sandbox.tasks_in(&export).len(), 1);
+}
+
+#[test]
+fn config_show_reports_the_file_in_use() {
+    let sandbox = Sandbox::new();
+    let file = sandbox.path("tasks.json");
+
+    sandbox.cmd()
+        .args(["--week-start", "sunday", "config", "show"])
+        .env("EDITOR", "nano")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(file.display().to_string()))
+        .stdout(predicate::str::contains("(--file, not created yet)"))
+        .stdout(predicate::str::contains("Week starts on: Sun"))
+        .stdout(predicate::str::contains("EDITOR=nano"));
+}
+
+#[test]
+fn unknown_task_fails_without_touching_the_file() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Only task"]);
+    let before = std::fs::read_to_string(sandbox.path("tasks.json")).unwrap();
+
+    sandbox.cmd()
+        .args(["complete", "00000000-0000-0000-0000-000000000000"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Error: Task with ID '00000000-0000-0000-0000-000000000000' not found"));
+
+    assert_eq!(std::fs::read_to_string(sandbox.path("tasks.json")).unwrap(), before);
+}
+
+#[test]
+fn export_modified_since_writes_only_the_delta() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Already synced"]);
+    let synced_at = sandbox.tasks_in(&sandbox.path("tasks.json"))[0]["updated_at"].as_str().unwrap().to_string();
+    let fresh = sandbox.add(&["Added after sync"]);
+
+    let out = sandbox.path("delta.json");
+    sandbox.cmd().arg("export").arg(&out).args(["--modified-since", &synced_at]).assert().success();
+    let delta = sandbox.tasks_in(&out);
+    assert_eq!(delta.len(), 1);
+    assert_eq!(delta[0]["id"], fresh.as_str());
+
+    sandbox.cmd()
+        .args(["list", "--modified-since", &synced_at])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added after sync").and(predicate::str::contains("Already synced").not()));
+}
+
+#[test]
+fn idempotent_complete_succeeds_on_done_task() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Nightly backup"]);
+    sandbox.cmd().args(["complete", &id]).assert().success();
+
+    sandbox.cmd().args(["complete", &id]).assert().failure().stderr(predicate::str::contains("already done"));
+    sandbox.cmd()
+        .args(["complete", &id, "--idempotent"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("is already done"));
+    sandbox.cmd().args(["start", &id, "--idempotent"]).assert().failure();
+}
+
+#[test]
+fn show_renders_dates_in_display_timezone() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Standup", "--due-date", "2024-02-01T14:30:00Z"]);
+
+    sandbox.cmd()
+        .args(["--timezone", "America/New_York", "show", &id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2024-02-01 09:30:00 EST (UTC-05:00)"));
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json"))[0]["due_date"], "2024-02-01T14:30:00Z");
+
+    sandbox.cmd().args(["--timezone", "Nowhere/Special", "list"]).assert().failure();
+}
+
+#[test]
+fn id_file_applies_to_each_listed_task() {
+    let sandbox = Sandbox::new();
+    let first = sandbox.add(&["First"]);
+    let second = sandbox.add(&["Second"]);
+    let kept = sandbox.add(&["Kept"]);
+    let list = sandbox.path("ids.txt");
+    std::fs::write(&list, format!("# from the weekly review\n{}\n\n{}\nmissing-id\n", first, second)).unwrap();
+
+    sandbox.cmd()
+        .args(["complete", "--id-file"])
+        .arg(&list)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(&first).and(predicate::str::contains(&second)))
+        .stderr(predicate::str::contains("✗ missing-id"));
+
+    let tasks = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    let status = |id: &str| tasks.iter().find(|t| t["id"] == id).unwrap()["status"].clone();
+    assert_eq!(status(&first), "done");
+    assert_eq!(status(&second), "done");
+    assert_eq!(status(&kept), "todo");
+
+    std::fs::write(&list, "# nothing yet\n").unwrap();
+    sandbox.cmd().args(["delete", "--force", "--id-file"]).arg(&list).assert().failure();
+}
+
+#[test]
+fn tags_lists_usage_counts() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Buy milk", "--tag", "errand", "--tag", "home"]);
+    sandbox.add(&["Fix sink", "--tag", "home"]);
+    let id = sandbox.add(&["Call plumber"]);
+    sandbox.cmd().args(["update", &id, "--tag", "home", "--tag", "urgent"]).assert().success();
+
+    let output = sandbox.cmd().arg("tags").assert().success().get_output().stdout.clone();
+    let lines: Vec<String> = String::from_utf8(output).unwrap().lines()
+        .filter(|l| l.starts_with("  "))
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    assert_eq!(lines, ["home 3", "errand 1", "urgent 1"]);
+
+    sandbox.cmd()
+        .args(["tags", "--alpha"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("(?s)errand.*home.*urgent").unwrap());
+}
+
+#[test]
+fn export_checksum_is_verified_on_import() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Archive me"]);
+    let archive = sandbox.path("archive.json");
+    sandbox.cmd().arg("export").arg(&archive).arg("--with-checksum").assert().success();
+    let sidecar = sandbox.path("archive.json.sha256");
+    assert!(std::fs::read_to_string(&sidecar).unwrap().ends_with("  archive.json\n"));
+
+    let restored = sandbox.path("restored.json");
+    sandbox.cmd_for(&restored).arg("import").arg(&archive).assert().success().stdout(predicate::str::contains("Checksum verified"));
+
+    let corrupted = std::fs::read_to_string(&archive).unwrap().replace("Archive me", "Archive m3");
+    std::fs::write(&archive, corrupted).unwrap();
+    let other = sandbox.path("other.json");
+    sandbox.cmd_for(&other).arg("import").arg(&archive).assert().failure().stderr(predicate::str::contains("Checksum mismatch"));
+    sandbox.cmd_for(&other).arg("import").arg(&archive).arg("--skip-checksum").assert().success();
+}
+
+#[test]
+fn yaml_export_and_storage_round_trip_to_the_same_tasks() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["First", "--tag", "home"]);
+    sandbox.add(&["Second", "--description", "with: details", "--due-date", "2030-01-01"]);
+
+    let export = sandbox.path("export.yaml");
+    sandbox.cmd().arg("export").arg(&export).assert().success();
+    assert!(std::fs::read_to_string(&export).unwrap().contains("title: First"));
+
+    // A .yml data file is stored as YAML without needing --format
+    let yaml_store = sandbox.path("tasks.yml");
+    sandbox.cmd_for(&yaml_store).arg("import").arg(&export).assert().success();
+    assert!(std::fs::read_to_string(&yaml_store).unwrap().contains("title: Second"));
+
+    let back = sandbox.path("back.json");
+    sandbox.cmd_for(&yaml_store).arg("export").arg(&back).assert().success();
+
+    let key = |task: &Value| task["id"].as_str().unwrap().to_string();
+    let mut original = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    let mut round_tripped = sandbox.tasks_in(&back);
+    original.sort_by_key(key);
+    round_tripped.sort_by_key(key);
+    assert_eq!(original, round_tripped);
+}
+
+#[test]
+fn focus_limits_show_and_list_to_the_focused_task() {
+    let sandbox = Sandbox::new();
+    let parent = sandbox.add(&["Write thesis"]);
+    sandbox.add(&["Draft chapter one", "--parent", &parent]);
+    sandbox.add(&["Buy groceries"]);
+
+    sandbox.cmd().args(["focus", "show"]).assert().failure().stderr(predicate::str::contains("No task is focused"));
+
+    sandbox.cmd().args(["focus", &parent]).assert().success();
+    sandbox.cmd()
+        .args(["focus", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Write thesis").and(predicate::str::contains("Buy groceries").not()));
+    sandbox.cmd()
+        .args(["list", "--focus"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Draft chapter one")
+                .and(predicate::str::contains("Tasks (2 found)"))
+                .and(predicate::str::contains("Buy groceries").not()),
+        );
+
+    sandbox.cmd().args(["focus", "clear"]).assert().success();
+    sandbox.cmd().args(["list", "--focus"]).assert().failure();
+}
+
+#[test]
+fn csv_export_writes_a_header_and_selected_columns() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Plan, then ship", "--category", "work"]);
+
+    let out = sandbox.path("tasks.csv");
+    sandbox.cmd().arg("export").arg(&out).assert().success();
+    let csv = std::fs::read_to_string(&out).unwrap();
+    assert!(csv.starts_with("id,title,status,priority,category,due_date,created_at,completed_at\n"));
+    assert!(csv.contains(",\"Plan, then ship\",todo,medium,work,,"));
+
+    let narrow = sandbox.path("narrow.csv");
+    sandbox.cmd().arg("export").arg(&narrow).args(["--columns", "title,due_date"]).assert().success();
+    assert_eq!(std::fs::read_to_string(&narrow).unwrap(), "title,due_date\n\"Plan, then ship\",\n");
+
+    sandbox.cmd()
+        .arg("export")
+        .arg(sandbox.path("tasks-copy.json"))
+        .args(["--columns", "title"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("only to CSV"));
+}
+
+#[test]
+fn csv_import_loads_good_rows_and_reports_bad_ones() {
+    let sandbox = Sandbox::new();
+    let csv = sandbox.path("from-other-tool.csv");
+    std::fs::write(&csv, "title,priority,due_date\nWrite docs,high,2030-01-01\nBroken,someday,\nReview PR,,\n").unwrap();
+
+    sandbox.cmd()
+        .arg("import")
+        .arg(&csv)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Imported 2 tasks"))
+        .stderr(predicate::str::contains("line 3: invalid priority 'someday'"));
+
+    let mut titles: Vec<String> = sandbox.tasks_in(&sandbox.path("tasks.json"))
+        .iter()
+        .map(|task| task["title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, vec!["Review PR", "Write docs"]);
+}
+
+#[test]
+fn stats_by_priority_scales_bars_to_the_busiest_level() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["One", "--priority", "high"]);
+    sandbox.add(&["Two", "--priority", "high"]);
+    sandbox.add(&["Three", "--priority", "low"]);
+
+    let bar = |n: usize| "█".repeat(n);
+    sandbox.cmd()
+        .args(["stats", "--by-priority"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(format!("High     {} 2", bar(30)))
+                .and(predicate::str::contains(format!("Low      {} 1", bar(15))))
+                .and(predicate::str::contains("Critical  0")),
+        );
+}
+
+#[test]
+fn markdown_export_is_chosen_by_extension_or_flag() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Write docs"]);
+
+    let md = sandbox.path("tasks.md");
+    sandbox.cmd().arg("export").arg(&md).assert().success();
+    assert!(std::fs::read_to_string(&md).unwrap().contains("- [ ] Write docs · medium"));
+
+    let txt = sandbox.path("tasks.txt");
+    sandbox.cmd().arg("export").arg(&txt).args(["--as", "markdown"]).assert().success();
+    assert!(std::fs::read_to_string(&txt).unwrap().starts_with("# Tasks\n"));
+}
+
+#[test]
+fn status_groups_combine_with_exclusions() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Planned"]);
+    let started = sandbox.add(&["Started"]);
+    let finished = sandbox.add(&["Finished"]);
+    let dropped = sandbox.add(&["Dropped"]);
+    sandbox.cmd().args(["start", &started]).assert().success();
+    sandbox.cmd().args(["complete", &finished]).assert().success();
+    sandbox.cmd().args(["cancel", &dropped]).assert().success();
+
+    let titles = |args: &[&str]| {
+        let output = sandbox.cmd().arg("list").args(args).assert().success().get_output().stdout.clone();
+        let stdout = String::from_utf8(output).unwrap();
+        ["Planned", "Started", "Finished", "Dropped"].into_iter().filter(|t| stdout.contains(t)).collect::<Vec<_>>()
+    };
+    assert_eq!(titles(&["--active"]), vec!["Planned", "Started"]);
+    assert_eq!(titles(&["--closed"]), vec!["Finished", "Dropped"]);
+    assert_eq!(titles(&["--open"]), vec!["Planned", "Started", "Dropped"]);
+    assert_eq!(titles(&["--open", "--exclude-status", "cancelled", "--exclude-status", "todo"]), vec!["Started"]);
+}
+
+#[test]
+fn config_file_supplies_defaults_below_flags() {
+    let sandbox = Sandbox::new();
+    let config = sandbox.path("config.toml");
+    std::fs::write(&config, "default_priority = \"critical\"\n").unwrap();
+
+    sandbox.cmd().arg("--config").arg(&config).args(["add", "From config"]).assert().success();
+    sandbox.cmd().arg("--config").arg(&config).args(["add", "From flag", "--priority", "low"]).assert().success();
+    let tasks = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    assert_eq!(tasks.iter().find(|t| t["title"] == "From config").unwrap()["priority"], "critical");
+    assert_eq!(tasks.iter().find(|t| t["title"] == "From flag").unwrap()["priority"], "low");
+
+    std::fs::write(&config, "default_priority = \"someday\"\n").unwrap();
+    sandbox.cmd().arg("--config").arg(&config).arg("list")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid `default_priority` value 'someday'"));
+}
+
+#[test]
+fn config_init_scaffolds_the_default_config_file_once() {
+    let sandbox = Sandbox::new();
+    let config = sandbox.path("task-manager").join("config.toml");
+
+    sandbox.cmd().args(["config", "init"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(config.display().to_string()));
+    assert!(std::fs::read_to_string(&config).unwrap().contains("# default_priority = \"medium\""));
+
+    sandbox.cmd().args(["config", "init"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+    sandbox.cmd().args(["config", "init", "--force"]).assert().success();
+    sandbox.cmd().args(["config", "show"]).assert().success().stdout(predicate::str::contains(config.display().to_string()));
+}
+
+#[test]
+fn data_file_env_var_sits_between_the_flag_and_the_default() {
+    let sandbox = Sandbox::new();
+    let from_env = sandbox.path("from-env.json");
+    let mut cmd = cargo_bin_cmd!("task-manager");
+    cmd.current_dir(sandbox.path(""))
+        .env_remove("TASK_PROJECT")
+        .env("XDG_CONFIG_HOME", sandbox.path(""))
+        .env("TASK_MANAGER_FILE", &from_env)
+        .args(["--color", "never", "add", "Via env"])
+        .assert()
+        .success();
+    assert_eq!(sandbox.tasks_in(&from_env)[0]["title"], "Via env");
+
+    // --file still wins over the variable
+    sandbox.cmd().env("TASK_MANAGER_FILE", &from_env).args(["add", "Via flag"]).assert().success();
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json"))[0]["title"], "Via flag");
+    assert_eq!(sandbox.tasks_in(&from_env).len(), 1);
+
+    let mut cmd = cargo_bin_cmd!("task-manager");
+    cmd.current_dir(sandbox.path(""))
+        .env("XDG_CONFIG_HOME", sandbox.path(""))
+        .env("TASK_MANAGER_FILE", "")
+        .arg("list")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("TASK_MANAGER_FILE is set but empty"));
+}
+
+#[test]
+fn project_flag_beats_the_data_file_env_var() {
+    let sandbox = Sandbox::new();
+    std::fs::write(sandbox.path("workspace.json"), r#"{"projects": {"work": "work.json"}}"#).unwrap();
+    let from_env = sandbox.path("from-env.json");
+
+    let mut cmd = cargo_bin_cmd!("task-manager");
+    cmd.current_dir(sandbox.path(""))
+        .env_remove("TASK_PROJECT")
+        .env("XDG_CONFIG_HOME", sandbox.path(""))
+        .env("TASK_MANAGER_FILE", &from_env)
+        .args(["--color", "never", "--project", "work", "add", "Project task"])
+        .assert()
+        .success();
+    assert_eq!(sandbox.tasks_in(&sandbox.path("work.json"))[0]["title"], "Project task");
+    assert!(!from_env.exists());
+}
+
+#[test]
+fn no_color_flag_and_env_strip_ansi_codes() {
+    let sandbox = Sandbox::new();
+    let run = |args: &[&str], env: &[(&str, &str)]| {
+        let mut cmd = cargo_bin_cmd!("task-manager");
+        cmd.current_dir(sandbox.path(""))
+            .env_remove("NO_COLOR")
+            .env_remove("CLICOLOR_FORCE")
+            .env("XDG_CONFIG_HOME", sandbox.path(""))
+            .envs(env.iter().copied())
+            .arg("--file")
+            .arg(sandbox.path("tasks.json"))
+            .args(args);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        String::from_utf8(output).unwrap()
+    };
+
+    assert!(run(&["add", "Forced"], &[("CLICOLOR_FORCE", "1")]).contains("\x1b["));
+    assert!(!run(&["--no-color", "add", "Flag"], &[("CLICOLOR_FORCE", "1")]).contains("\x1b["));
+    assert!(!run(&["--color", "always", "--no-color", "add", "Both"], &[]).contains("\x1b["));
+    assert!(!run(&["add", "Env"], &[("NO_COLOR", "1")]).contains("\x1b["));
+    // Output captured by the test is not a terminal, so auto mode is plain
+    assert!(!run(&["add", "Piped"], &[]).contains("\x1b["));
+}
+
+#[test]
+fn json_flag_prints_parseable_output() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Pipe me", "--priority", "high"]);
+    sandbox.add(&["Other"]);
+
+    let output = sandbox.cmd().args(["list", "--json", "--priority", "high"]).assert().success().get_output().stdout.clone();
+    let tasks: Vec<Value> = serde_json::from_slice(&output).unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["title"], "Pipe me");
+
+    let output = sandbox.cmd().args(["list", "--json", "--search", "nothing"]).assert().success().get_output().stdout.clone();
+    assert_eq!(serde_json::from_slice::<Vec<Value>>(&output).unwrap().len(), 0);
+
+    let output = sandbox.cmd().args(["show", &id, "--json"]).assert().success().get_output().stdout.clone();
+    assert_eq!(serde_json::from_slice::<Value>(&output).unwrap()["id"], id.as_str());
+
+    let output = sandbox.cmd().args(["stats", "--json"]).assert().success().get_output().stdout.clone();
+    assert_eq!(serde_json::from_slice::<Value>(&output).unwrap()["total"], 2);
+}
+
+#[test]
+fn list_format_switches_between_table_json_and_csv() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Quarterly, report", "--category", "work"]);
+
+    sandbox.cmd().args(["list", "--format", "table"]).assert().success().stdout(predicate::str::contains("Tasks (1 found)"));
+
+    let output = sandbox.cmd().args(["list", "--format", "json"]).assert().success().get_output().stdout.clone();
+    assert_eq!(serde_json::from_slice::<Vec<Value>>(&output).unwrap()[0]["category"], "work");
+
+    let output = sandbox.cmd().args(["list", "--format", "csv"]).assert().success().get_output().stdout.clone();
+    let csv = String::from_utf8(output).unwrap();
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().starts_with("id,title,"));
+    assert!(lines.next().unwrap().contains("\"Quarterly, report\""));
+    assert!(lines.next().is_none());
+
+    sandbox.cmd().args(["list", "--json", "--format", "csv"]).assert().failure();
+
+    // Table-only flags are refused rather than silently ignored
+    sandbox.cmd().args(["list", "--json", "--numbered"]).assert().failure();
+    sandbox.cmd().args(["list", "--format", "csv", "--narrow"]).assert().failure()
+        .stderr(predicate::str::contains("only apply to the table format"));
+    assert!(!sandbox.path("tasks.last-list").exists());
+    sandbox.cmd().args(["list", "--format", "table", "--narrow"]).assert().success();
+}
+
+#[test]
+fn short_id_prefixes_work_in_place_of_full_ids() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Short and sweet"]);
+
+    sandbox.cmd().args(["show", &id[..8]]).assert().success().stdout(predicate::str::contains("Short and sweet"));
+    sandbox.cmd().args(["complete", &id[..6]]).assert().success();
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json"))[0]["status"], "done");
+
+    sandbox.cmd().args(["show", "Short"]).assert().failure().stderr(predicate::str::contains("'Short' is not a valid task ID"));
+}
+
+#[test]
+fn timer_accumulates_tracked_time_and_rejects_a_second_start() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Deep work"]);
+
+    sandbox.cmd().args(["timer", "start", &id]).assert().success();
+    sandbox.cmd().args(["timer", "start", &id])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already running"));
+    sandbox.cmd().args(["timer", "stop", &id]).assert().success();
+
+    sandbox.cmd().args(["show", &id]).assert().success().stdout(predicate::str::contains("Time tracked:"));
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json"))[0]["time_entries"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn estimates_filter_sort_report_and_round_trip() {
+    let sandbox = Sandbox::new();
+    let long = sandbox.add(&["Migrate database", "--estimate", "3h"]);
+    sandbox.add(&["Reply to email", "--estimate", "15"]);
+    sandbox.add(&["Someday"]);
+
+    sandbox.cmd().args(["add", "Forever", "--estimate", "200h"]).assert().failure();
+    sandbox.cmd().args(["update", &long, "--estimate", "2h30m"]).assert().success();
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json")).iter().find(|t| t["title"] == "Migrate database").unwrap()["estimate_minutes"], 150);
+
+    let output = sandbox.cmd().args(["list", "--json", "--max-estimate", "1h"]).assert().success().get_output().stdout.clone();
+    let quick: Vec<Value> = serde_json::from_slice(&output).unwrap();
+    assert_eq!(quick.len(), 1);
+    assert_eq!(quick[0]["title"], "Reply to email");
+
+    let output = sandbox.cmd().args(["list", "--json", "--sort", "estimate-desc"]).assert().success().get_output().stdout.clone();
+    let titles: Vec<String> = serde_json::from_slice::<Vec<Value>>(&output).unwrap().iter().map(|t| t["title"].as_str().unwrap().to_string()).collect();
+    assert_eq!(titles, ["Migrate database", "Reply to email", "Someday"]);
+
+    sandbox.cmd().arg("stats").assert().success().stdout(predicate::str::contains("2h 45m estimated"));
+
+    let csv = sandbox.path("out.csv");
+    sandbox.cmd().arg("export").arg(&csv).args(["--columns", "title,estimate"]).assert().success();
+    let copy = sandbox.path("copy.json");
+    sandbox.cmd_for(&copy).arg("import").arg(&csv).assert().success();
+    let imported = sandbox.tasks_in(&copy);
+    assert_eq!(imported.iter().find(|t| t["title"] == "Reply to email").unwrap()["estimate_minutes"], 15);
+}
+
+#[test]
+fn delete_confirms_once_for_every_task_found() {
+    let sandbox = Sandbox::new();
+    let first = sandbox.add(&["Old draft"]);
+    let second = sandbox.add(&["Stale idea"]);
+    let keep = sandbox.add(&["Keeper"]);
+
+    sandbox.cmd()
+        .args(["delete", &first, "ffffffff", &second])
+        .write_stdin("y\n")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Delete these 2 tasks?").count(1))
+        .stdout(predicate::str::contains("Old draft").and(predicate::str::contains("Stale idea")))
+        .stdout(predicate::str::contains("deleted 2, 1 failed (not found)"))
+        .stderr(predicate::str::contains("✗ ffffffff"));
+
+    let tasks = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["id"], keep.as_str());
+}
+
+#[test]
+fn merge_folds_a_duplicate_into_the_kept_task() {
+    let sandbox = Sandbox::new();
+    let keep = sandbox.add(&["Write report", "--description", "Outline", "--tag", "docs"]);
+    let dup = sandbox.add(&["Report draft", "--description", "Gather numbers", "--category", "work", "--tag", "q3"]);
+
+    sandbox.cmd().args(["merge", &keep, &keep]).assert().failure().stderr(predicate::str::contains("into itself"));
+    sandbox.cmd().args(["merge", &keep, &dup[..8]]).assert().success().stdout(predicate::str::contains("Merged task"));
+
+    let tasks = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["id"], keep.as_str());
+    assert_eq!(tasks[0]["description"], "Outline\nGather numbers");
+    assert_eq!(tasks[0]["category"], "work");
+    assert_eq!(tasks[0]["tags"], serde_json::json!(["docs", "q3"]));
+}
+
+#[test]
+fn notes_are_appended_and_shown_newest_first() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Flaky test"]);
+
+    sandbox.cmd().args(["note", &id, "Reproduced on CI"]).assert().success();
+    sandbox.cmd().args(["note", &id, "Fixed the race"]).assert().success();
+    sandbox.cmd().args(["note", &id, "   "]).assert().failure();
+
+    let output = sandbox.cmd().args(["show", &id]).assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let newer = stdout.find("Fixed the race").unwrap();
+    let older = stdout.find("Reproduced on CI").unwrap();
+    assert!(newer < older);
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json"))[0]["notes"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn history_lists_status_and_field_changes() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Ship release"]);
+
+    sandbox.cmd().args(["history", &id]).assert().success().stdout(predicate::str::contains("No changes recorded yet."));
+    sandbox.cmd().args(["update", &id, "--priority", "high"]).assert().success();
+    sandbox.cmd().args(["start", &id]).assert().success();
+
+    sandbox.cmd()
+        .args(["history", &id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("priority: medium → high"))
+        .stdout(predicate::str::contains("status: todo → in progress"));
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json"))[0]["history"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn deleted_tasks_wait_in_the_trash_until_emptied() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Oops"]);
+
+    sandbox.cmd().args(["delete", &id, "--force"]).assert().success().stdout(predicate::str::contains("to the trash"));
+    assert!(sandbox.tasks_in(&sandbox.path("tasks.json")).is_empty());
+    sandbox.cmd().arg("trash").assert().success().stdout(predicate::str::contains("Oops"));
+
+    sandbox.cmd().args(["trash", "restore", &id[..8]]).assert().success();
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json")).len(), 1);
+    assert!(!sandbox.path("tasks.trash").exists());
+
+    sandbox.cmd().args(["delete-all", "--force"]).assert().success();
+    sandbox.cmd().args(["trash", "empty"]).write_stdin("y\n").assert().success().stdout(predicate::str::contains("Permanently deleted 1 tasks"));
+    sandbox.cmd().args(["trash", "list"]).assert().success().stdout(predicate::str::contains("The trash is empty."));
+}
+
+#[test]
+fn cascade_delete_accepts_subtasks_listed_after_their_parent() {
+    let sandbox = Sandbox::new();
+    let parent = sandbox.add(&["Launch"]);
+    let child = sandbox.add(&["Write announcement", "--parent", &parent]);
+    sandbox.add(&["Unrelated"]);
+
+    sandbox.cmd()
+        .args(["delete", &parent, &child, "--cascade", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("with its parent"))
+        .stdout(predicate::str::contains("deleted 2"))
+        .stderr(predicate::str::contains("✗").not());
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json")).len(), 1);
+}
+
+#[test]
+fn archive_hides_finished_tasks_from_list_and_stats() {
+    let sandbox = Sandbox::new();
+    let done = sandbox.add(&["Shipped feature"]);
+    sandbox.add(&["Still open"]);
+    sandbox.cmd().args(["complete", &done]).assert().success();
+
+    sandbox.cmd().arg("archive").assert().success().stdout(predicate::str::contains("Archived 1 tasks"));
+    sandbox.cmd().arg("list").assert().success().stdout(predicate::str::contains("Shipped feature").not());
+    sandbox.cmd().args(["list", "--archived"]).assert().success().stdout(predicate::str::contains("Shipped feature"));
+
+    let total = |args: &[&str]| {
+        let output = sandbox.cmd().args(["stats", "--json"]).args(args).assert().success().get_output().stdout.clone();
+        serde_json::from_slice::<Value>(&output).unwrap()["total"].clone()
+    };
+    assert_eq!(total(&[]), 1);
+    assert_eq!(total(&["--include-archived"]), 2);
+
+    // Archived tasks stay in the data file
+    let stored = sandbox.tasks_in(&sandbox.path("tasks.json"));
+    assert_eq!(stored.len(), 2);
+    assert!(stored.iter().any(|t| t["id"] == done.as_str() && !t["archived_at"].is_null()));
+
+    sandbox.cmd().args(["unarchive", &done[..8]]).assert().success();
+    sandbox.cmd().arg("list").assert().success().stdout(predicate::str::contains("Shipped feature"));
+}
+
+#[test]
+fn anonymized_export_leaks_no_original_text() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Secret merger talks", "--category", "deals", "--owner", "alice@corp"]);
+    sandbox.cmd()
+        .args(["update", &id, "--title", "Secret merger signing", "--description", "wire funds to the Zurich account"])
+        .assert()
+        .success();
+    sandbox.cmd().args(["note", &id, "Met the board"]).assert().success();
+
+    let out = sandbox.path("out.json");
+    sandbox.cmd().arg("export").arg(&out).arg("--anonymize").assert().success();
+    let written = std::fs::read_to_string(&out).unwrap();
+    // Only words that can't turn up by chance in UUIDs or timestamps
+    for original in ["Secret", "merger", "Zurich", "deals", "board", "alice"] {
+        assert!(!written.contains(original), "{} leaked into {}", original, written);
+    }
+}
+
+#[test]
+fn export_after_deleting_a_dependency_can_be_imported() {
+    let sandbox = Sandbox::new();
+    let first = sandbox.add(&["Prerequisite"]);
+    sandbox.add(&["Follow-up", "--depends-on", &first]);
+
+    sandbox.cmd().args(["delete", &first, "--force"]).assert().success();
+    sandbox.cmd().args(["trash", "empty", "--force"]).assert().success();
+
+    let out = sandbox.path("out.json");
+    sandbox.cmd().arg("export").arg(&out).assert().success();
+    let copy = sandbox.path("copy.json");
+    sandbox.cmd_for(&copy).arg("import").arg(&out).assert().success();
+    assert_eq!(sandbox.tasks_in(&copy).len(), 1);
+}
+
+#[test]
+fn export_keeps_archived_tasks_that_others_depend_on() {
+    let sandbox = Sandbox::new();
+    let done = sandbox.add(&["Groundwork"]);
+    let dependent = sandbox.add(&["Build on it", "--depends-on", &done]);
+    sandbox.cmd().args(["complete", &done]).assert().success();
+    sandbox.cmd().arg("archive").assert().success();
+
+    let out = sandbox.path("e.json");
+    sandbox.cmd().arg("export").arg(&out).assert().success();
+    let copy = sandbox.path("copy.json");
+    sandbox.cmd_for(&copy).arg("import").arg(&out).assert().success();
+    assert_eq!(sandbox.tasks_in(&copy).len(), 2);
+
+    sandbox.cmd()
+        .args(["show", &dependent])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Groundwork").and(predicate::str::contains("(archived)")))
+        .stdout(predicate::str::contains("(missing)").not());
+
+    // Importing the same export again must not add a second copy of the archived task
+    sandbox.cmd().arg("import").arg(&out).assert().success();
+    assert_eq!(sandbox.tasks_in(&sandbox.path("tasks.json")).len(), 2);
+}
+
+#[test]
+fn numbered_list_under_no_save_leaves_row_numbers_alone() {
+    let sandbox = Sandbox::new();
+    let id = sandbox.add(&["Only task"]);
+
+    sandbox.cmd().args(["--no-save", "list", "--numbered"]).assert().success();
+    assert!(!sandbox.path("tasks.last-list").exists());
+
+    sandbox.cmd().args(["list", "--numbered"]).assert().success();
+    sandbox.cmd().args(["show", "#1"]).assert().success().stdout(predicate::str::contains(&id[..8]));
+}
+
+#[test]
+fn check_fails_once_overdue_tasks_exceed_the_threshold() {
+    let sandbox = Sandbox::new();
+    sandbox.add(&["Late", "--due-date", "2024-02-01"]);
+
+    sandbox.cmd().args(["check", "--overdue", "--threshold", "1"]).assert().success();
+    sandbox.cmd()
+        .args(["check", "--overdue"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("1 overdue tasks (threshold: 0)"));
+}